@@ -1,4 +1,23 @@
-pub use self::{cli::Cli, http::Http};
+pub use self::{
+    cli::Cli,
+    http::{Http, HttpBuilder, PingError, PullPolicy},
+};
 
 mod cli;
 mod http;
+
+/// Marks the networks created by testcontainers, which allows pruning them without touching
+/// networks that were created by anyone else.
+const NETWORK_LABEL: (&str, &str) = ("org.testcontainers.rs", "true");
+
+/// The first API version that stores the annotations of a container.
+const ANNOTATIONS_API_VERSION: (u32, u32) = (1, 43);
+
+/// Parses an API version like `1.43`.
+fn parse_api_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.trim().splitn(2, '.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+
+    Some((major, minor))
+}
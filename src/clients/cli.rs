@@ -1,10 +1,21 @@
+use super::{parse_api_version, ANNOTATIONS_API_VERSION, NETWORK_LABEL};
 use crate::core::{
-    env, env::GetEnvValue, logs::LogStream, ports::Ports, Container, Docker, Image, RunArgs,
+    copy::{file_from_tar_archive, tar_archive},
+    env,
+    env::GetEnvValue,
+    health::{parse_health, Health},
+    logs::LogStream,
+    ports::Ports,
+    retry::{retry_start, StartError},
+    shell_quote, Container, CopyToContainer, Docker, ExecCommand, ExecOutput, Gpus, Image,
+    RemovalPolicy, RunArgs, StartupPhase, StartupReport,
 };
 use shiplift::rep::ContainerDetails;
 use std::{
     collections::HashMap,
     ffi::{OsStr, OsString},
+    fs::File,
+    io::{self, Read, Write},
     process::{Command, Stdio},
     sync::{Arc, RwLock},
     thread::sleep,
@@ -14,6 +25,13 @@ use std::{
 const ONE_SECOND: Duration = Duration::from_secs(1);
 const ZERO: Duration = Duration::from_secs(0);
 
+/// The label that marks containers shared through [`Cli::run_or_attach`].
+const LEASE_LABEL: &str = "org.testcontainers.rs.lease";
+const LEASE_NAME_PREFIX: &str = "testcontainers-lease-";
+/// The file the creator of a leased container copies into it once the container is ready and ran
+/// the commands after start, which attachers wait for.
+const LEASE_READY_FILE: &str = "/.testcontainers-lease-ready";
+
 /// Implementation of the Docker client API using the docker cli.
 ///
 /// This (fairly naive) implementation of the Docker client API simply creates `Command`s to the `docker` CLI. It thereby assumes that the `docker` CLI is installed and that it is in the PATH of the current execution environment.
@@ -28,38 +46,177 @@ impl Cli {
     }
 
     pub fn run_with_args<I: Image>(&self, image: I, run_args: RunArgs) -> Container<'_, I> {
-        let mut docker = self.inner.command();
+        assert_supported(&run_args);
+        run_args.assert_valid_memory();
+        run_args.assert_logs_readable(&image);
+
+        let mut report = StartupReport::default();
+        let mut retries_left = run_args.start_retries();
+        let id = self.start_container(&image, &run_args, &mut retries_left, &mut report);
+        let mut container = self.container(id, image, &run_args);
+
+        // a container that did not become ready is replaced as a whole
+        let mut waiting = Instant::now();
+        while let Err(e) = container.try_until_ready() {
+            report.record_since(StartupPhase::Ready, waiting);
+            if retries_left == 0 {
+                panic!("container {} did not become ready: {}", container.id(), e)
+            }
+            retries_left -= 1;
 
-        if let Some(network) = run_args.network() {
-            if self.inner.create_network_if_not_exists(&network) {
-                let mut guard = self
-                    .inner
-                    .created_networks
-                    .write()
-                    .expect("failed to lock RwLock");
+            log::warn!(
+                "Container {} did not become ready, retrying: {}",
+                container.id(),
+                e
+            );
 
-                guard.push(network);
-            }
+            // the replacement might need the name and the host ports of the failed container
+            container.remove_for_replacement();
+            let id =
+                self.start_container(container.image(), &run_args, &mut retries_left, &mut report);
+            container.replace_with(id);
+            waiting = Instant::now();
         }
+        container.exec_after_start();
+        report.record_since(StartupPhase::Ready, waiting);
+        *container.startup_report_mut() = report;
 
-        let command = Client::build_run_command(&image, &mut docker, &run_args);
+        container
+    }
 
-        log::debug!("Executing command: {:?}", command);
+    /// Starts the container but returns right away instead of waiting for it to be ready.
+    ///
+    /// This allows checking the readiness through a custom protocol. Use
+    /// [`Container::wait_until_ready`] or [`Container::wait_for`] to wait later on.
+    ///
+    /// [`Container::wait_until_ready`]: ../core/struct.Container.html#method.wait_until_ready
+    /// [`Container::wait_for`]: ../core/struct.Container.html#method.wait_for
+    pub fn run_without_wait<I: Image>(&self, image: I, run_args: RunArgs) -> Container<'_, I> {
+        assert_supported(&run_args);
+        run_args.assert_valid_memory();
+        run_args.assert_logs_readable(&image);
 
-        let output = command.output().expect("Failed to execute docker command");
+        let mut report = StartupReport::default();
+        let mut retries_left = run_args.start_retries();
+        let id = self.start_container(&image, &run_args, &mut retries_left, &mut report);
 
-        assert!(output.status.success(), "failed to start container");
-        let container_id = String::from_utf8(output.stdout)
-            .expect("output is not valid utf8")
-            .trim()
-            .to_string();
-        self.inner.register_container_started(container_id.clone());
+        let mut container = self.container(id, image, &run_args);
+        *container.startup_report_mut() = report;
 
-        let client = Cli {
-            inner: self.inner.clone(),
-        };
+        container
+    }
+
+    /// Runs a container that is shared by all callers passing the same lease, also across
+    /// processes, e.g. a database that is expensive to start for tests that run in parallel.
+    ///
+    /// The container is named `testcontainers-lease-<lease>` and labeled with the lease. As the
+    /// daemon grants a name only once, exactly one caller creates the container, all others attach
+    /// to it and wait until it is ready, including the [`Image::exec_after_start`] commands run by
+    /// the creator. The lease therefore needs to be valid in a container name, i.e. consist of
+    /// ASCII letters, digits, `_`, `.` and `-`. Leased containers are kept when they are dropped,
+    /// because other callers might still use them, and need to be removed through
+    /// [`Container::rm`] in the end.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the lease contains other characters or is empty.
+    ///
+    /// [`Image::exec_after_start`]: ../core/trait.Image.html#method.exec_after_start
+    /// [`Container::rm`]: ../core/struct.Container.html#method.rm
+    pub fn run_or_attach<I: Image>(
+        &self,
+        image: I,
+        run_args: RunArgs,
+        lease: &str,
+    ) -> Container<'_, I> {
+        assert_valid_lease(lease);
+        assert_supported(&run_args);
+        run_args.assert_valid_memory();
+        run_args.assert_logs_readable(&image);
+
+        let run_args = run_args
+            .with_name(format!("{}{}", LEASE_NAME_PREFIX, lease))
+            .with_label(LEASE_LABEL, lease)
+            .with_removal(RemovalPolicy::Keep);
+        self.create_network(&run_args);
+
+        let supported_run_args = self.inner.supported_run_args(&run_args);
+        let mut report = StartupReport::default();
+        self.inner.pull_if_missing(&image.descriptor(), &mut report);
+        match self
+            .inner
+            .try_start_container(&image, &supported_run_args, &mut report)
+        {
+            Ok(id) => {
+                self.inner.register_container_started(id.clone());
+
+                let mut container = self.container(id, image, &run_args);
+                let waiting = Instant::now();
+                container.wait_until_ready();
+                self.inner.copy_to_container(
+                    container.id(),
+                    &[CopyToContainer::new(Vec::new(), LEASE_READY_FILE)],
+                );
+                report.record_since(StartupPhase::Ready, waiting);
+                *container.startup_report_mut() = report;
 
-        Container::new(container_id, client, image, self.inner.command)
+                container
+            }
+            Err(e) if e.is_name_conflict() => {
+                let id = self.inner.leased_container(lease).unwrap_or_else(|| {
+                    panic!("container of lease {} was removed while attaching", lease)
+                });
+                log::debug!("Attaching to container {} of lease {}", id, lease);
+
+                let mut container = self.container(id, image, &run_args);
+                let waiting = Instant::now();
+                // the ready conditions might be met before the commands after start set it up
+                if let Err(e) = container.wait_for_file(LEASE_READY_FILE) {
+                    panic!(
+                        "container {} of lease {} did not become ready: {}",
+                        container.id(),
+                        lease,
+                        e
+                    )
+                }
+                report.record_since(StartupPhase::Ready, waiting);
+                *container.startup_report_mut() = report;
+
+                container
+            }
+            Err(e) => panic!("failed to start container: {}", e),
+        }
+    }
+
+    /// Removes the networks created through [`RunArgs::with_network`] that are no longer used by
+    /// any container, e.g. because a previous test run crashed before it could clean them up.
+    ///
+    /// Networks that were not created by testcontainers are never removed. Returns the names of
+    /// the removed networks.
+    ///
+    /// [`RunArgs::with_network`]: ../core/struct.RunArgs.html#method.with_network
+    pub fn prune_networks(&self) -> Vec<String> {
+        self.inner.prune_networks()
+    }
+
+    /// Returns the `docker run` command that [`run_with_args`] starts the image with, without
+    /// running it, e.g. to reproduce a container outside of a test.
+    ///
+    /// Arguments are quoted for a POSIX shell. Files copied into the container through
+    /// [`Image::copy_to_sources`] are not part of the command, as they are copied into a created
+    /// container before it starts, and neither is the network that `run_with_args` creates
+    /// through `docker network create` first.
+    ///
+    /// [`run_with_args`]: struct.Cli.html#method.run_with_args
+    /// [`Image::copy_to_sources`]: ../core/trait.Image.html#method.copy_to_sources
+    pub fn render_run_command<I: Image>(&self, image: &I, run_args: &RunArgs) -> String {
+        assert_supported(run_args);
+
+        std::iter::once(self.inner.binary.to_string_lossy().into_owned())
+            .chain(Client::run_command_args(image, run_args))
+            .map(|arg| shell_quote(&arg))
+            .collect::<Vec<_>>()
+            .join(" ")
     }
 }
 
@@ -83,6 +240,24 @@ impl Client {
         Command::new(self.binary.clone())
     }
 
+    /// Runs a docker command like `docker pause <id>` and panics if it fails.
+    fn assert_command_succeeds(&self, command: &str, id: &str) {
+        let output = self
+            .command()
+            .arg(command)
+            .arg(id)
+            .output()
+            .expect("Failed to execute docker command");
+
+        assert!(
+            output.status.success(),
+            "Failed to {} docker container {}: {}",
+            command,
+            id,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
     fn register_container_started(&self, id: String) {
         let mut lock_guard = match self.container_startup_timestamps.write() {
             Ok(lock_guard) => lock_guard,
@@ -126,57 +301,365 @@ impl Client {
         }
     }
 
+    /// Returns the API version both the docker CLI and the daemon speak, if it can be determined.
+    fn api_version(&self) -> Option<(u32, u32)> {
+        let output = self
+            .command()
+            .arg("version")
+            .arg("--format")
+            .arg("{{.Client.APIVersion}} {{.Server.APIVersion}}")
+            .output()
+            .ok()?;
+
+        String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .map(parse_api_version)
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .min()
+    }
+
+    /// Returns the id of the container labeled with the lease, see [`Cli::run_or_attach`].
+    fn leased_container(&self, lease: &str) -> Option<String> {
+        let output = self
+            .command()
+            .args(["ps", "-a", "-q", "--no-trunc", "--filter"])
+            .arg(format!("label={}={}", LEASE_LABEL, lease))
+            .output()
+            .expect("Failed to execute docker command");
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .map(ToOwned::to_owned)
+    }
+
+    /// Drops the options the daemon is too old for but that are safe to omit, i.e. annotations.
+    fn supported_run_args(&self, run_args: &RunArgs) -> RunArgs {
+        if run_args.annotations().is_empty() {
+            return run_args.clone();
+        }
+
+        match self.api_version() {
+            Some(version) if version >= ANNOTATIONS_API_VERSION => run_args.clone(),
+            version => {
+                log::warn!(
+                    "Dropping annotations because docker API version {:?} does not support them",
+                    version
+                );
+
+                run_args.clone().without_annotations()
+            }
+        }
+    }
+
     fn build_run_command<'a, I: Image>(
         image: &I,
         command: &'a mut Command,
         run_args: &RunArgs,
     ) -> &'a mut Command {
-        command.arg("run");
+        command
+            .args(Self::run_command_args(image, run_args))
+            .stdout(Stdio::piped())
+    }
+
+    /// Returns the arguments of the `docker run` command for the image, i.e. without the binary.
+    fn run_command_args<I: Image>(image: &I, run_args: &RunArgs) -> Vec<String> {
+        let mut args = vec!["run".to_owned()];
+        args.extend(Self::container_options(image, run_args));
+        args.push("-d".to_owned()); // Always run detached
+        args.push(image.descriptor());
+        args.extend(run_args.cmd(image));
+
+        args
+    }
+
+    /// Like [`build_run_command`](Self::build_run_command) but only creates the container.
+    fn build_create_command<'a, I: Image>(
+        image: &I,
+        command: &'a mut Command,
+        run_args: &RunArgs,
+    ) -> &'a mut Command {
+        command
+            .arg("create")
+            .args(Self::container_options(image, run_args))
+            .arg(image.descriptor())
+            .args(run_args.cmd(image))
+            .stdout(Stdio::piped())
+    }
+
+    /// Returns the options of `docker run` and `docker create` for the image.
+    fn container_options<I: Image>(image: &I, run_args: &RunArgs) -> Vec<String> {
+        let mut options = Vec::new();
 
         if let Some(network) = run_args.network() {
-            command.arg(format!("--network={}", network));
+            options.push(format!("--network={}", network));
         }
 
         if let Some(name) = run_args.name() {
-            command.arg(format!("--name={}", name));
+            options.push(format!("--name={}", name));
+        }
+
+        if let Some(memory) = run_args.memory() {
+            options.push(format!("--memory={}", memory));
+        }
+
+        if let Some(memory_swap) = run_args.memory_swap() {
+            options.push(format!("--memory-swap={}", memory_swap));
+        }
+
+        if run_args.oom_kill_disabled() {
+            options.push("--oom-kill-disable".to_owned());
+        }
+
+        if let Some(oom_score_adj) = run_args.oom_score_adj() {
+            options.push(format!("--oom-score-adj={}", oom_score_adj));
+        }
+
+        match run_args.gpus() {
+            Some(Gpus::All) => {
+                options.push("--gpus=all".to_owned());
+            }
+            Some(Gpus::Count(count)) => {
+                options.push(format!("--gpus={}", count));
+            }
+            None => {}
+        }
+
+        if let Some(isolation) = run_args.isolation() {
+            options.push(format!("--isolation={}", isolation.as_str()));
+        }
+
+        for security_opt in run_args.security_opts() {
+            options.push(format!("--security-opt={}", security_opt));
+        }
+
+        if let Some(userns_mode) = run_args.userns_mode() {
+            options.push(format!("--userns={}", userns_mode));
+        }
+
+        for (key, value) in run_args.storage_opts() {
+            options.push(format!("--storage-opt={}={}", key, value));
+        }
+
+        if let Some(health_check) = run_args.health_check() {
+            options.extend(health_check.to_args());
+        }
+
+        for (key, value) in run_args.labels() {
+            options.push(format!("--label={}={}", key, value));
+        }
+
+        for (key, value) in run_args.annotations() {
+            options.push(format!("--annotation={}={}", key, value));
+        }
+
+        if let Some(log_config) = run_args.log_config() {
+            options.push(format!("--log-driver={}", log_config.driver));
+
+            for (key, value) in log_config.options {
+                options.push("--log-opt".to_owned());
+                options.push(format!("{}={}", key, value));
+            }
         }
 
         for (key, value) in image.env_vars() {
-            command.arg("-e").arg(format!("{}={}", key, value));
+            options.push("-e".to_owned());
+            options.push(format!("{}={}", key, value));
+        }
+        for (key, value) in run_args.env_vars() {
+            options.push("-e".to_owned());
+            options.push(format!("{}={}", key, value));
         }
 
         for (orig, dest) in image.volumes() {
-            command.arg("-v").arg(format!("{}:{}", orig, dest));
+            options.push("-v".to_owned());
+            options.push(format!("{}:{}", orig, dest));
+        }
+
+        for bind_mount in run_args.bind_mounts() {
+            options.push("-v".to_owned());
+            options.push(bind_mount);
+        }
+
+        for volumes_from in run_args.volumes_from() {
+            options.push(format!("--volumes-from={}", volumes_from));
+        }
+
+        for (hostname, address) in image.hosts() {
+            options.push(format!("--add-host={}:{}", hostname, address));
+        }
+
+        if image.privileged() {
+            options.push("--privileged".to_owned());
+        }
+
+        for path in image.tmpfs() {
+            options.push(format!("--tmpfs={}", path));
         }
 
         if let Some(entrypoint) = image.entrypoint() {
-            command.arg("--entrypoint").arg(entrypoint);
+            options.push("--entrypoint".to_owned());
+            options.push(entrypoint);
+        }
+
+        for port in image.mapped_ports() {
+            options.push("-p".to_owned());
+            options.push(format!("{}:{}", port.local, port.internal));
         }
 
         if let Some(ports) = run_args.ports() {
             for port in &ports {
-                command
-                    .arg("-p")
-                    .arg(format!("{}:{}", port.local, port.internal));
+                options.push("-p".to_owned());
+                options.push(format!("{}:{}", port.local, port.internal));
             }
         } else {
-            command.arg("-P"); // expose all ports
+            for port in image.expose_ports() {
+                options.push(format!("--expose={}", port));
+            }
+            options.push("-P".to_owned()); // expose all ports
         }
 
-        command
-            .arg("-d") // Always run detached
-            .arg(image.descriptor())
-            .args(image.args())
-            .stdout(Stdio::piped())
+        options
+    }
+
+    /// Starts a container of the image and returns its id.
+    ///
+    /// A container that was created but failed to start is removed again.
+    fn try_start_container<I: Image>(
+        &self,
+        image: &I,
+        run_args: &RunArgs,
+        report: &mut StartupReport,
+    ) -> Result<String, StartError> {
+        let mut docker = self.command();
+        let files = image.copy_to_sources();
+
+        // files can only be copied into a container that has been created but not started yet,
+        // and a container that failed to start can only be removed before retrying if its id is
+        // known
+        let create = !files.is_empty() || run_args.start_retries() > 0;
+        let command = if create {
+            Client::build_create_command(image, &mut docker, run_args)
+        } else {
+            Client::build_run_command(image, &mut docker, run_args)
+        };
+
+        log::debug!("Executing command: {:?}", command);
+
+        let creating = Instant::now();
+        let output = command.output().expect("Failed to execute docker command");
+
+        if !output.status.success() {
+            let phase = if create {
+                StartupPhase::Create
+            } else {
+                StartupPhase::CreateAndStart
+            };
+            report.record_since(phase, creating);
+
+            return Err(StartError::new(String::from_utf8_lossy(&output.stderr)));
+        }
+
+        let id = String::from_utf8(output.stdout)
+            .expect("output is not valid utf8")
+            .trim()
+            .to_string();
+
+        if !create {
+            report.record_since(StartupPhase::CreateAndStart, creating);
+        } else {
+            if !files.is_empty() {
+                self.copy_to_container(&id, &files);
+            }
+            report.record_since(StartupPhase::Create, creating);
+
+            let starting = Instant::now();
+            let output = self
+                .command()
+                .arg("start")
+                .arg(&id)
+                .output()
+                .expect("Failed to execute docker command");
+            report.record_since(StartupPhase::Start, starting);
+
+            if !output.status.success() {
+                // best effort, the error of starting the container is more relevant
+                let _ = self.command().args(["rm", "-f", "-v"]).arg(&id).output();
+
+                return Err(StartError::new(String::from_utf8_lossy(&output.stderr)));
+            }
+        }
+
+        Ok(id)
+    }
+
+    /// Copies the files into the container by streaming a tar archive to `docker cp`.
+    fn copy_to_container(&self, id: &str, files: &[CopyToContainer]) {
+        let mut child = self
+            .command()
+            .arg("cp")
+            .arg("-")
+            .arg(format!("{}:/", id))
+            .stdin(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute docker command");
+
+        child
+            .stdin
+            .take()
+            .expect("stdin to be captured")
+            .write_all(&tar_archive(files))
+            .expect("failed to stream files to docker cp");
+
+        let status = child.wait().expect("Failed to copy files into container");
+        assert!(
+            status.success(),
+            "failed to copy files into container {}",
+            id
+        );
+    }
+
+    /// Pulls the image unless it is available locally, so the time it takes is reported apart
+    /// from creating the container.
+    fn pull_if_missing(&self, descriptor: &str, report: &mut StartupReport) {
+        let available = self
+            .command()
+            .args(["image", "inspect"])
+            .arg(descriptor)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .expect("Failed to execute docker command")
+            .success();
+        if available {
+            return;
+        }
+
+        let pulling = Instant::now();
+        let output = self
+            .command()
+            .arg("pull")
+            .arg(descriptor)
+            .output()
+            .expect("Failed to execute docker command");
+        report.record_since(StartupPhase::Pull, pulling);
+
+        assert!(
+            output.status.success(),
+            "failed to pull image {}: {}",
+            descriptor,
+            String::from_utf8_lossy(&output.stderr)
+        );
     }
 
-    fn create_network_if_not_exists(&self, name: &str) -> bool {
+    fn create_network_if_not_exists(&self, name: &str, run_args: &RunArgs) -> bool {
         if self.network_exists(name) {
             return false;
         }
 
         let mut docker = self.command();
-        docker.args(&["network", "create", name]);
+        Self::build_network_create_command(name, &mut docker, run_args);
 
         let output = docker.output().expect("failed to create docker network");
         assert!(output.status.success(), "failed to create docker network");
@@ -184,9 +667,62 @@ impl Client {
         true
     }
 
+    fn build_network_create_command<'a>(
+        name: &str,
+        command: &'a mut Command,
+        run_args: &RunArgs,
+    ) -> &'a mut Command {
+        command
+            .args(["network", "create", "--label"])
+            .arg(format!("{}={}", NETWORK_LABEL.0, NETWORK_LABEL.1));
+
+        if let Some(mtu) = run_args.network_mtu() {
+            command
+                .arg("--opt")
+                .arg(format!("com.docker.network.driver.mtu={}", mtu));
+        }
+        if run_args.internal_network() {
+            command.arg("--internal");
+        }
+
+        command.arg(name)
+    }
+
+    /// Returns the names of the removed networks.
+    fn prune_networks(&self) -> Vec<String> {
+        let mut docker = self.command();
+        docker
+            .args(["network", "prune", "--force", "--filter"])
+            .arg(format!("label={}={}", NETWORK_LABEL.0, NETWORK_LABEL.1));
+
+        let output = docker.output().expect("failed to prune docker networks");
+
+        assert!(
+            output.status.success(),
+            "failed to prune docker networks: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let pruned = String::from_utf8(output.stdout)
+            .expect("output is not valid utf-8")
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && *line != "Deleted Networks:")
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+
+        // pruned networks must not be deleted again once the client is dropped
+        self.created_networks
+            .write()
+            .expect("failed to lock RwLock")
+            .retain(|network| !pruned.contains(network));
+
+        pruned
+    }
+
     fn network_exists(&self, name: &str) -> bool {
         let mut docker = self.command();
-        docker.args(&["network", "ls", "--format", "{{.Name}}"]);
+        docker.args(["network", "ls", "--format", "{{.Name}}"]);
 
         let output = docker.output().expect("failed to list docker networks");
         let output = String::from_utf8(output.stdout).expect("output is not valid utf-8");
@@ -200,7 +736,7 @@ impl Client {
         S: AsRef<OsStr>,
     {
         let mut docker = self.command();
-        docker.args(&["network", "rm"]);
+        docker.args(["network", "rm"]);
         docker.args(networks);
 
         let output = docker.output().expect("failed to delete docker networks");
@@ -213,6 +749,88 @@ impl Client {
     }
 }
 
+impl Cli {
+    /// Starts a container of the image and returns its id, retrying transient errors.
+    fn start_container<I: Image>(
+        &self,
+        image: &I,
+        run_args: &RunArgs,
+        retries_left: &mut u32,
+        report: &mut StartupReport,
+    ) -> String {
+        self.create_network(run_args);
+        self.inner.pull_if_missing(&image.descriptor(), report);
+
+        let run_args = self.inner.supported_run_args(run_args);
+        let id = retry_start(retries_left, || {
+            self.inner.try_start_container(image, &run_args, report)
+        })
+        .unwrap_or_else(|e| panic!("failed to start container: {}", e));
+
+        self.inner.register_container_started(id.clone());
+
+        id
+    }
+
+    fn create_network(&self, run_args: &RunArgs) {
+        if run_args.existing_network() {
+            return;
+        }
+
+        if let Some(network) = run_args.network() {
+            if self.inner.create_network_if_not_exists(&network, run_args) {
+                let mut guard = self
+                    .inner
+                    .created_networks
+                    .write()
+                    .expect("failed to lock RwLock");
+
+                guard.push(network);
+            }
+        }
+    }
+
+    /// Returns the given number of the last lines, or `all`, the container logged to stdout and
+    /// stderr.
+    fn logs_tail(&self, id: &str, tail: &str) -> String {
+        let output = self
+            .inner
+            .command()
+            .arg("logs")
+            .arg("--tail")
+            .arg(tail)
+            .arg(id)
+            .output()
+            .expect("Failed to execute docker command");
+
+        // the order of lines across both streams is lost, stderr usually explains the failure
+        format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        )
+    }
+
+    fn container<I: Image>(&self, id: String, image: I, run_args: &RunArgs) -> Container<'_, I> {
+        let client = Cli {
+            inner: self.inner.clone(),
+        };
+
+        let log_file = run_args.log_file(&id);
+
+        Container::new(
+            id,
+            client,
+            image,
+            run_args.removal(self.inner.command),
+            run_args.startup_timeout(),
+            run_args.progress_interval(),
+            run_args.full_logs_on_timeout(),
+            log_file,
+        )
+    }
+}
+
 impl Default for Cli {
     fn default() -> Self {
         Self::docker()
@@ -247,6 +865,8 @@ impl Cli {
 }
 
 impl Docker for Cli {
+    // The `docker logs -f` process exits on its own once the container is removed.
+    #[allow(clippy::zombie_processes)]
     fn stdout_logs(&self, id: &str) -> LogStream {
         self.inner
             .wait_at_least_one_second_after_container_was_started(id);
@@ -264,6 +884,8 @@ impl Docker for Cli {
         LogStream::new(child.stdout.expect("stdout to be captured"))
     }
 
+    // The `docker logs -f` process exits on its own once the container is removed.
+    #[allow(clippy::zombie_processes)]
     fn stderr_logs(&self, id: &str) -> LogStream {
         self.inner
             .wait_at_least_one_second_after_container_was_started(id);
@@ -290,18 +912,15 @@ impl Docker for Cli {
     }
 
     fn inspect(&self, id: &str) -> ContainerDetails {
-        let child = self
+        let output = self
             .inner
             .command()
             .arg("inspect")
             .arg(id)
-            .stdout(Stdio::piped())
-            .spawn()
+            .output()
             .expect("Failed to execute docker command");
 
-        let stdout = child.stdout.unwrap();
-
-        let mut infos: Vec<ContainerDetails> = serde_json::from_reader(stdout).unwrap();
+        let mut infos: Vec<ContainerDetails> = serde_json::from_slice(&output.stdout).unwrap();
 
         let info = infos.remove(0);
 
@@ -344,7 +963,24 @@ impl Docker for Cli {
             .expect("Failed to stop docker container");
     }
 
-    fn start(&self, id: &str) {
+    fn kill(&self, id: &str) {
+        let output = self
+            .inner
+            .command()
+            .arg("kill")
+            .arg(id)
+            .output()
+            .expect("Failed to execute docker command");
+
+        assert!(
+            output.status.success(),
+            "Failed to kill docker container {}: {}",
+            id,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn start(&self, id: &str) {
         self.inner
             .command()
             .arg("start")
@@ -355,12 +991,143 @@ impl Docker for Cli {
             .wait()
             .expect("Failed to start docker container");
     }
+
+    fn pause(&self, id: &str) {
+        self.inner.assert_command_succeeds("pause", id)
+    }
+
+    fn unpause(&self, id: &str) {
+        self.inner.assert_command_succeeds("unpause", id)
+    }
+
+    fn exec(&self, id: &str, cmd: ExecCommand) -> ExecOutput {
+        let output = self
+            .inner
+            .command()
+            .arg("exec")
+            .arg(id)
+            .args(cmd.cmd)
+            .output()
+            .expect("Failed to execute docker command");
+
+        exec_output(output)
+    }
+
+    fn write_logs(&self, id: &str, file: File) -> io::Result<()> {
+        // both streams go to the same file to preserve the order of the lines
+        let status = self
+            .inner
+            .command()
+            .arg("logs")
+            .arg(id)
+            .stdout(Stdio::from(file.try_clone()?))
+            .stderr(Stdio::from(file))
+            .status()?;
+
+        if !status.success() {
+            return Err(io::Error::other(format!(
+                "docker logs exited with {}",
+                status
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn copy_file_from(&self, id: &str, path: &str) -> io::Result<Vec<u8>> {
+        // `-` writes a tar archive of the path to stdout
+        let output = self
+            .inner
+            .command()
+            .arg("cp")
+            .arg(format!("{}:{}", id, path))
+            .arg("-")
+            .output()?;
+
+        if !output.status.success() {
+            return Err(io::Error::other(
+                String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            ));
+        }
+
+        file_from_tar_archive(&output.stdout)
+    }
+
+    fn health(&self, id: &str) -> Option<Health> {
+        let output = self
+            .inner
+            .command()
+            .arg("inspect")
+            .arg("--format")
+            .arg("{{json .State}}")
+            .arg(id)
+            .output()
+            .expect("Failed to execute docker command");
+
+        let state = serde_json::from_slice(&output.stdout)
+            .unwrap_or_else(|e| panic!("failed to parse state of container {}: {}", id, e));
+
+        parse_health(&state)
+    }
+
+    fn recent_logs(&self, id: &str, lines: usize) -> String {
+        self.logs_tail(id, &lines.to_string())
+    }
+
+    fn all_logs(&self, id: &str) -> String {
+        self.logs_tail(id, "all")
+    }
+
+    fn exec_with_stdin(
+        &self,
+        id: &str,
+        cmd: ExecCommand,
+        mut input: Box<dyn Read + Send>,
+    ) -> ExecOutput {
+        let mut child = self
+            .inner
+            .command()
+            .arg("exec")
+            .arg("-i")
+            .arg(id)
+            .args(cmd.cmd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute docker command");
+
+        // write from a separate thread, the command might not read its input before it has
+        // written its output, which would block on full pipes otherwise
+        let mut stdin = child.stdin.take().expect("stdin to be captured");
+        let writer = std::thread::spawn(move || io::copy(&mut input, &mut stdin));
+
+        let output = child
+            .wait_with_output()
+            .expect("Failed to execute docker command");
+
+        if let Err(e) = writer.join().expect("thread writing stdin panicked") {
+            // the command may legitimately exit before consuming all input
+            log::debug!("failed to write stdin of exec: {}", e);
+        }
+
+        exec_output(output)
+    }
+}
+
+fn exec_output(output: std::process::Output) -> ExecOutput {
+    ExecOutput {
+        // `code` is only absent if the docker CLI itself was killed by a signal
+        exit_code: output.status.code().map(i64::from).unwrap_or(-1),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    }
 }
 
 impl Drop for Client {
     fn drop(&mut self) {
         let networks = self.created_networks.read().expect("failed to lock RwLock");
-        let created_networks = networks.len() > 0;
+        let created_networks = !networks.is_empty();
 
         match self.command {
             env::Command::Remove if created_networks => {
@@ -381,10 +1148,39 @@ impl Drop for Client {
     }
 }
 
+/// Panics if `run_args` contain an option that cannot be passed to `docker run`.
+fn assert_supported(run_args: &RunArgs) {
+    if run_args.create_modifier().is_some() {
+        panic!("`RunArgs::with_create_modifier` is not supported by the Cli client, use `clients::Http` instead")
+    }
+}
+
+/// Panics if `lease` cannot be part of the name of a leased container.
+fn assert_valid_lease(lease: &str) {
+    let valid = !lease.is_empty()
+        && lease
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+
+    assert!(
+        valid,
+        "lease {:?} must only consist of ASCII letters, digits, `_`, `.` and `-`",
+        lease
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{core::WaitFor, images::generic::GenericImage, Image};
+    use crate::{
+        core::logs::WaitError,
+        core::{
+            AccessMode, BindMount, Consistency, ContainerState, ExecCommand, HealthCheck,
+            HttpWaitStrategy, Isolation, LogSource, Propagation, RemovalPolicy, WaitFor,
+        },
+        images::generic::GenericImage,
+        Image,
+    };
     use spectral::prelude::*;
 
     #[derive(Default)]
@@ -424,6 +1220,152 @@ mod tests {
         }
     }
 
+    /// Never becomes ready, but gives up after the timeout it declares.
+    #[derive(Debug)]
+    struct NeverReady {
+        startup_timeout: Duration,
+    }
+
+    impl Default for NeverReady {
+        fn default() -> Self {
+            NeverReady {
+                startup_timeout: Duration::from_secs(1),
+            }
+        }
+    }
+
+    impl Image for NeverReady {
+        type Args = Vec<String>;
+        type EnvVars = HashMap<String, String>;
+        type Volumes = HashMap<String, String>;
+        type EntryPoint = std::convert::Infallible;
+
+        fn descriptor(&self) -> String {
+            String::from("alpine:3.13")
+        }
+
+        fn ready_conditions(&self) -> Vec<WaitFor> {
+            vec![WaitFor::message_on_stdout("never")]
+        }
+
+        fn args(&self) -> <Self as Image>::Args {
+            vec!["sleep".to_owned(), "infinity".to_owned()]
+        }
+
+        fn volumes(&self) -> Self::Volumes {
+            HashMap::new()
+        }
+
+        fn env_vars(&self) -> Self::EnvVars {
+            HashMap::new()
+        }
+
+        fn with_args(self, _arguments: <Self as Image>::Args) -> Self {
+            self
+        }
+
+        fn startup_timeout(&self) -> Option<Duration> {
+            Some(self.startup_timeout)
+        }
+    }
+
+    /// Is ready right away, but only set up once the commands after start finished.
+    #[derive(Default)]
+    struct SetUpAfterStart;
+
+    impl Image for SetUpAfterStart {
+        type Args = Vec<String>;
+        type EnvVars = HashMap<String, String>;
+        type Volumes = HashMap<String, String>;
+        type EntryPoint = std::convert::Infallible;
+
+        fn descriptor(&self) -> String {
+            String::from("alpine:3.13")
+        }
+
+        fn ready_conditions(&self) -> Vec<WaitFor> {
+            vec![WaitFor::message_on_stdout("started")]
+        }
+
+        fn args(&self) -> <Self as Image>::Args {
+            vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "echo started; sleep infinity".to_owned(),
+            ]
+        }
+
+        fn volumes(&self) -> Self::Volumes {
+            HashMap::new()
+        }
+
+        fn env_vars(&self) -> Self::EnvVars {
+            HashMap::new()
+        }
+
+        fn with_args(self, _arguments: <Self as Image>::Args) -> Self {
+            self
+        }
+
+        fn exec_after_start(&self, _: ContainerState) -> Vec<ExecCommand> {
+            vec![ExecCommand::shell("sleep 2 && touch /set-up")]
+        }
+    }
+
+    /// Serves a page through busybox `httpd`, which the commands after start restart.
+    #[derive(Default)]
+    struct RestartingWebServer;
+
+    impl Image for RestartingWebServer {
+        type Args = Vec<String>;
+        type EnvVars = HashMap<String, String>;
+        type Volumes = HashMap<String, String>;
+        type EntryPoint = std::convert::Infallible;
+
+        fn descriptor(&self) -> String {
+            String::from("alpine:3.13")
+        }
+
+        fn ready_conditions(&self) -> Vec<WaitFor> {
+            vec![WaitFor::http(HttpWaitStrategy::new(8080, "/"))]
+        }
+
+        fn args(&self) -> <Self as Image>::Args {
+            vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "mkdir /www && echo ok > /www/index.html && httpd -p 8080 -h /www && sleep infinity"
+                    .to_owned(),
+            ]
+        }
+
+        fn volumes(&self) -> Self::Volumes {
+            HashMap::new()
+        }
+
+        fn env_vars(&self) -> Self::EnvVars {
+            HashMap::new()
+        }
+
+        fn with_args(self, _arguments: <Self as Image>::Args) -> Self {
+            self
+        }
+
+        fn exec_after_start(&self, _: ContainerState) -> Vec<ExecCommand> {
+            vec![ExecCommand::shell(
+                "pkill httpd && setsid sh -c 'sleep 3 && httpd -p 8080 -h /www' > /dev/null 2>&1 &",
+            )]
+        }
+
+        fn recheck_readiness_after_exec(&self) -> bool {
+            true
+        }
+
+        fn expose_ports(&self) -> Vec<u16> {
+            vec![8080]
+        }
+    }
+
     #[test]
     fn cli_run_command_should_include_env_vars() {
         let mut volumes = HashMap::new();
@@ -450,6 +1392,74 @@ mod tests {
         assert!(format!("{:?}", command).contains(r#""-e" "two-key=two-value""#));
     }
 
+    #[test]
+    fn cli_render_run_command_should_quote_options_of_image_and_run_args() {
+        let mut volumes = HashMap::new();
+        volumes.insert("/tmp/data".to_owned(), "/data".to_owned());
+
+        let mut env_vars = HashMap::new();
+        env_vars.insert("GREETING".to_owned(), "hello world".to_owned());
+
+        let image = HelloWorld { volumes, env_vars };
+        let run_args = RunArgs::default()
+            .with_name("hello")
+            .with_mapped_port((8080, 80));
+
+        let command = Cli::docker().render_run_command(&image, &run_args);
+
+        assert!(command.starts_with("docker run --name=hello "));
+        assert!(command.contains(" -e 'GREETING=hello world' "));
+        assert!(command.contains(" -v /tmp/data:/data "));
+        assert!(command.contains(" -p 8080:80 "));
+        assert!(command.ends_with(" -d hello-world"));
+    }
+
+    #[test]
+    fn cli_run_command_should_include_hosts() {
+        let image = crate::images::prometheus::Prometheus::default()
+            .with_scrape_target(crate::images::prometheus::HOST_GATEWAY, 8080);
+
+        let mut docker = Command::new("docker");
+        let command = Client::build_run_command(&image, &mut docker, &RunArgs::default());
+
+        assert!(
+            format!("{:?}", command).contains(r#""--add-host=host.docker.internal:host-gateway""#)
+        );
+    }
+
+    #[test]
+    fn cli_run_command_should_run_privileged_with_tmpfs() {
+        let image = crate::images::k3s::K3s::default();
+
+        let mut docker = Command::new("docker");
+        let command = Client::build_run_command(&image, &mut docker, &RunArgs::default());
+
+        assert!(format!("{:?}", command)
+            .contains(r#""--privileged" "--tmpfs=/run" "--tmpfs=/var/run""#));
+    }
+
+    #[test]
+    fn cli_run_command_should_expose_additional_ports_of_image() {
+        let image = crate::images::toxiproxy::Toxiproxy::default().with_proxy_ports(8666..=8667);
+
+        let mut docker = Command::new("docker");
+        let command = Client::build_run_command(&image, &mut docker, &RunArgs::default());
+
+        assert!(format!("{:?}", command).contains(r#""--expose=8666" "--expose=8667" "-P""#));
+    }
+
+    #[test]
+    fn cli_run_command_should_map_fixed_ports_of_image() {
+        let image = crate::images::ftp::PureFtpd::default().with_passive_ports(31000..=31001);
+
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default().with_mapped_port((2121, 21));
+        let command = Client::build_run_command(&image, &mut docker, &run_args);
+
+        assert!(format!("{:?}", command)
+            .contains(r#""-p" "31000:31000" "-p" "31001:31001" "-p" "2121:21""#));
+    }
+
     #[test]
     fn cli_run_command_should_expose_all_ports_if_no_explicit_mapping_requested() {
         let image = GenericImage::new("hello");
@@ -517,47 +1527,303 @@ mod tests {
     }
 
     #[test]
-    fn should_create_network_if_image_needs_it_and_drop_it_in_the_end() {
-        {
-            let docker = Cli::default();
-
-            assert!(!docker.inner.network_exists("awesome-net"));
+    fn cli_run_command_should_include_oom_score_adj() {
+        let image = GenericImage::new("hello");
 
-            // creating the first container creates the network
-            let _container1 = docker.run_with_args(
-                HelloWorld::default(),
-                RunArgs::default().with_network("awesome-net"),
-            );
-            // creating a 2nd container doesn't fail because check if the network exists already
-            let _container2 = docker.run_with_args(
-                HelloWorld::default(),
-                RunArgs::default().with_network("awesome-net"),
-            );
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default().with_oom_score_adj(-500);
+        let command = Client::build_run_command(&image, &mut docker, &run_args);
 
-            assert!(docker.inner.network_exists("awesome-net"));
-        }
+        println!("Executing command: {:?}", command);
 
-        {
-            let docker = Cli::default();
-            // original client has been dropped, should clean up networks
-            assert!(!docker.inner.network_exists("awesome-net"))
-        }
+        assert!(format!("{:?}", command).contains(r#"--oom-score-adj=-500"#));
     }
 
-    struct FakeEnvAlwaysKeep;
+    #[test]
+    fn cli_inspect_should_reflect_oom_score_adj() {
+        let docker = Cli::default();
+        let container = docker.run_with_args(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            RunArgs::default().with_oom_score_adj(500),
+        );
 
-    impl GetEnvValue for FakeEnvAlwaysKeep {
-        fn get_env_value(_: &str) -> Option<String> {
-            Some("keep".to_owned())
-        }
+        let output = docker
+            .inner
+            .command()
+            .arg("inspect")
+            .arg("--format")
+            .arg("{{.HostConfig.OomScoreAdj}}")
+            .arg(container.id())
+            .output()
+            .unwrap();
+
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "500");
     }
 
     #[test]
-    fn should_not_delete_network_if_command_is_keep() {
-        let network_name = "foobar-net";
+    fn cli_create_command_should_not_start_container() {
+        let image = GenericImage::new("hello")
+            .with_env_var("one-key", "one-value")
+            .with_entrypoint("/bin/sh");
 
-        {
-            let docker = Cli::new::<FakeEnvAlwaysKeep, _>("docker");
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default().with_name("hello_container");
+        let command = Client::build_create_command(&image, &mut docker, &run_args);
+
+        println!("Executing command: {:?}", command);
+
+        assert_eq!(
+            format!("{:?}", command),
+            r#""docker" "create" "--name=hello_container" "-e" "one-key=one-value" "--entrypoint" "/bin/sh" "-P" "hello""#
+        );
+    }
+
+    #[test]
+    fn cli_run_command_should_append_cmd_to_image_args() {
+        let image = crate::images::mongo::Mongo::default();
+
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default().with_cmd_append(vec!["--quiet".to_owned()]);
+        let command = Client::build_run_command(&image, &mut docker, &run_args);
+
+        assert!(
+            format!("{:?}", command).ends_with(&format!(r#""{}" "--quiet""#, image.descriptor()))
+        );
+    }
+
+    #[test]
+    fn cli_run_command_should_replace_image_args_with_cmd() {
+        let image =
+            GenericImage::new("alpine:3.13").with_args(vec!["sleep".to_owned(), "1".to_owned()]);
+
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default().with_cmd(vec!["echo", "hello"]);
+        let command = Client::build_run_command(&image, &mut docker, &run_args);
+
+        assert!(format!("{:?}", command).ends_with(r#""alpine:3.13" "echo" "hello""#));
+    }
+
+    #[test]
+    #[should_panic(expected = "lease \"my lease\" must only consist of ASCII letters, digits")]
+    fn run_or_attach_should_reject_lease_that_is_invalid_in_container_name() {
+        let docker = Cli::default();
+        docker.run_or_attach(GenericImage::new("hello"), RunArgs::default(), "my lease");
+    }
+
+    #[test]
+    #[should_panic(expected = "`with_cmd` and `with_cmd_append` are mutually exclusive")]
+    fn should_reject_cmd_together_with_cmd_append() {
+        RunArgs::default()
+            .with_cmd(vec!["echo"])
+            .with_cmd_append(vec!["hello"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "OOM score adjustment must be within -1000 and 1000, got 1001")]
+    fn oom_score_adj_out_of_range_should_panic() {
+        let _ = RunArgs::default().with_oom_score_adj(1001);
+    }
+
+    const MIB: u64 = 1024 * 1024;
+
+    #[test]
+    fn cli_run_command_should_include_memory_limits() {
+        let image = GenericImage::new("hello");
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default()
+            .with_memory(64 * MIB)
+            .with_memory_swap(128 * MIB as i64)
+            .with_oom_kill_disabled();
+        let command = Client::build_run_command(&image, &mut docker, &run_args);
+
+        assert!(format!("{:?}", command)
+            .contains(r#""--memory=67108864" "--memory-swap=134217728" "--oom-kill-disable""#));
+    }
+
+    #[test]
+    fn should_accept_consistent_memory_limits() {
+        RunArgs::default().assert_valid_memory();
+        RunArgs::default()
+            .with_memory(64 * MIB)
+            .with_memory_swap(64 * MIB as i64)
+            .with_oom_kill_disabled()
+            .assert_valid_memory();
+        RunArgs::default()
+            .with_memory(64 * MIB)
+            .with_memory_swap(-1)
+            .assert_valid_memory();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "memory limit of 1048576 bytes is below the minimum of 6291456 bytes the daemon accepts"
+    )]
+    fn memory_below_minimum_should_panic() {
+        RunArgs::default().with_memory(MIB).assert_valid_memory();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`RunArgs::with_memory_swap` needs a memory limit set through `RunArgs::with_memory`"
+    )]
+    fn memory_swap_without_memory_should_panic() {
+        RunArgs::default()
+            .with_memory_swap(128 * MIB as i64)
+            .assert_valid_memory();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "memory swap limit of 33554432 bytes is below the memory limit of 67108864 bytes, but it limits memory and swap together, e.g. pass 134217728 to allow as much swap as memory"
+    )]
+    fn memory_swap_below_memory_should_panic() {
+        RunArgs::default()
+            .with_memory(64 * MIB)
+            .with_memory_swap(32 * MIB as i64)
+            .assert_valid_memory();
+    }
+
+    #[test]
+    #[should_panic(expected = "memory swap limit must be at least -1 for unlimited swap, got -2")]
+    fn negative_memory_swap_should_panic() {
+        RunArgs::default()
+            .with_memory(64 * MIB)
+            .with_memory_swap(-2)
+            .assert_valid_memory();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`RunArgs::with_oom_kill_disabled` needs a memory limit set through `RunArgs::with_memory`"
+    )]
+    fn oom_kill_disabled_without_memory_should_panic() {
+        RunArgs::default()
+            .with_oom_kill_disabled()
+            .assert_valid_memory();
+    }
+
+    #[test]
+    fn should_create_network_if_image_needs_it_and_drop_it_in_the_end() {
+        {
+            let docker = Cli::default();
+
+            assert!(!docker.inner.network_exists("awesome-net"));
+
+            // creating the first container creates the network
+            let _container1 = docker.run_with_args(
+                HelloWorld::default(),
+                RunArgs::default().with_network("awesome-net"),
+            );
+            // creating a 2nd container doesn't fail because check if the network exists already
+            let _container2 = docker.run_with_args(
+                HelloWorld::default(),
+                RunArgs::default().with_network("awesome-net"),
+            );
+
+            assert!(docker.inner.network_exists("awesome-net"));
+        }
+
+        {
+            let docker = Cli::default();
+            // original client has been dropped, should clean up networks
+            assert!(!docker.inner.network_exists("awesome-net"))
+        }
+    }
+
+    #[test]
+    fn should_attach_to_existing_network_and_keep_it_in_the_end() {
+        let network = Cli::default()
+            .inner
+            .command()
+            .args(["network", "create", "external-net"])
+            .output()
+            .unwrap();
+        assert!(network.status.success());
+
+        {
+            let docker = Cli::default();
+            let container = docker.run_with_args(
+                HelloWorld::default(),
+                RunArgs::default().with_existing_network("external-net"),
+            );
+
+            let networks = docker
+                .inspect(container.id())
+                .network_settings
+                .networks
+                .into_keys()
+                .collect::<Vec<_>>();
+            assert_eq!(networks, vec!["external-net"]);
+        }
+
+        let docker = Cli::default();
+        // the network was not created by the dropped client
+        let exists = docker.inner.network_exists("external-net");
+        docker.inner.delete_networks(vec!["external-net"]);
+        assert!(exists);
+    }
+
+    #[test]
+    #[should_panic(expected = "`RunArgs::with_create_modifier` is not supported by the Cli client")]
+    fn cli_run_should_reject_create_modifier() {
+        let docker = Cli::default();
+
+        docker.run_with_args(
+            HelloWorld::default(),
+            RunArgs::default().with_create_modifier(|_| {}),
+        );
+    }
+
+    #[test]
+    fn cli_network_create_command_should_include_mtu_and_internal() {
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default()
+            .with_network("isolated-net")
+            .with_network_mtu(1400)
+            .with_internal_network();
+        let command = Client::build_network_create_command("isolated-net", &mut docker, &run_args);
+
+        assert!(format!("{:?}", command).ends_with(
+            r#""--opt" "com.docker.network.driver.mtu=1400" "--internal" "isolated-net""#
+        ));
+    }
+
+    #[test]
+    fn cli_internal_network_should_block_outbound_access() {
+        let docker = Cli::default();
+        let container = docker.run_with_args(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            RunArgs::default()
+                .with_network("internal-net")
+                .with_network_mtu(1400)
+                .with_internal_network(),
+        );
+
+        let link = container.exec(ExecCommand::new(vec!["ip", "link", "show", "eth0"]));
+        assert!(link.stdout.contains("mtu 1400"), "{}", link.stdout);
+
+        let outbound = container.exec(ExecCommand::shell(
+            "wget -q -T 3 -O /dev/null http://1.1.1.1",
+        ));
+        assert_ne!(outbound.exit_code, 0);
+    }
+
+    struct FakeEnvAlwaysKeep;
+
+    impl GetEnvValue for FakeEnvAlwaysKeep {
+        fn get_env_value(_: &str) -> Option<String> {
+            Some("keep".to_owned())
+        }
+    }
+
+    #[test]
+    fn should_not_delete_network_if_command_is_keep() {
+        let network_name = "foobar-net";
+
+        {
+            let docker = Cli::new::<FakeEnvAlwaysKeep, _>("docker");
 
             assert!(!docker.inner.network_exists(network_name));
 
@@ -580,6 +1846,1039 @@ mod tests {
         docker.inner.delete_networks(vec![network_name]);
     }
 
+    #[test]
+    fn should_prune_only_unused_networks_created_by_testcontainers() {
+        let docker = Cli::default();
+        let output = docker
+            .inner
+            .command()
+            .args(["network", "create", "user-net"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let image = GenericImage::new("redis:5.0")
+            .with_wait_for(WaitFor::message_on_stdout("Ready to accept connections"));
+        let container = docker.run_with_args(image, RunArgs::default().with_network("pruned-net"));
+
+        // the network is still used by the container
+        assert_that!(docker.prune_networks()).does_not_contain("pruned-net".to_owned());
+
+        drop(container);
+
+        let pruned = docker.prune_networks();
+        assert_that!(pruned).contains("pruned-net".to_owned());
+        assert_that!(pruned).does_not_contain("user-net".to_owned());
+        assert!(!docker.inner.network_exists("pruned-net"));
+        assert!(docker.inner.network_exists("user-net"));
+
+        docker.inner.delete_networks(vec!["user-net"]);
+    }
+
+    #[test]
+    fn cli_run_command_should_include_log_driver() {
+        let image = GenericImage::new("hello");
+
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default().with_log_driver("json-file", vec![("max-size", "10m")]);
+        let command = Client::build_run_command(&image, &mut docker, &run_args);
+
+        println!("Executing command: {:?}", command);
+
+        assert!(format!("{:?}", command)
+            .contains(r#""--log-driver=json-file" "--log-opt" "max-size=10m""#));
+    }
+
+    #[test]
+    fn cli_run_command_should_include_gpus() {
+        let image = GenericImage::new("hello");
+
+        let mut docker = Command::new("docker");
+        let command = Client::build_run_command(
+            &image,
+            &mut docker,
+            &RunArgs::default().with_gpus(Gpus::All),
+        );
+        assert!(format!("{:?}", command).contains(r#""--gpus=all""#));
+
+        let mut docker = Command::new("docker");
+        let command = Client::build_run_command(
+            &image,
+            &mut docker,
+            &RunArgs::default().with_gpus(Gpus::Count(2)),
+        );
+        assert!(format!("{:?}", command).contains(r#""--gpus=2""#));
+    }
+
+    #[test]
+    fn cli_run_command_should_include_isolation() {
+        let image = GenericImage::new("hello");
+
+        let mut docker = Command::new("docker");
+        let command = Client::build_run_command(
+            &image,
+            &mut docker,
+            &RunArgs::default().with_isolation(Isolation::Hyperv),
+        );
+
+        assert!(format!("{:?}", command).contains(r#""--isolation=hyperv""#));
+    }
+
+    #[test]
+    #[ignore] // requires a GPU and the NVIDIA container toolkit
+    fn cli_inspect_should_reflect_gpu_device_request() {
+        let docker = Cli::default();
+        let container = docker.run_with_args(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            RunArgs::default().with_gpus(Gpus::All),
+        );
+
+        let output = docker
+            .inner
+            .command()
+            .arg("inspect")
+            .arg("--format")
+            .arg("{{json .HostConfig.DeviceRequests}}")
+            .arg(container.id())
+            .output()
+            .unwrap();
+        let device_requests = String::from_utf8_lossy(&output.stdout);
+
+        assert!(device_requests.contains(r#""Count":-1"#));
+        assert!(device_requests.contains(r#"[["gpu"]]"#));
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn cli_inspect_should_reflect_process_isolation() {
+        let docker = Cli::default();
+        let container = docker.run_with_args(
+            GenericImage::new("mcr.microsoft.com/windows/nanoserver:ltsc2022").with_args(vec![
+                "ping".to_owned(),
+                "-t".to_owned(),
+                "localhost".to_owned(),
+            ]),
+            RunArgs::default().with_isolation(Isolation::Process),
+        );
+
+        let output = docker
+            .inner
+            .command()
+            .arg("inspect")
+            .arg("--format")
+            .arg("{{.HostConfig.Isolation}}")
+            .arg(container.id())
+            .output()
+            .unwrap();
+
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "process");
+    }
+
+    #[test]
+    #[should_panic(expected = "never found with the `none` log driver")]
+    fn cli_run_should_reject_none_log_driver_when_waiting_for_logs() {
+        let docker = Cli::default();
+
+        docker.run_with_args(
+            HelloWorld::default(),
+            RunArgs::default().with_log_driver("none", Vec::<(String, String)>::new()),
+        );
+    }
+
+    #[test]
+    fn cli_run_command_should_include_security_opts() {
+        let image = GenericImage::new("hello");
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default()
+            .with_security_opt("seccomp=unconfined")
+            .with_security_opt("apparmor=unconfined");
+        let command = Client::build_run_command(&image, &mut docker, &run_args);
+
+        assert!(format!("{:?}", command).contains(
+            r#""--security-opt=seccomp=unconfined" "--security-opt=apparmor=unconfined""#
+        ));
+    }
+
+    #[test]
+    fn cli_inspect_should_reflect_security_opts() {
+        let docker = Cli::default();
+        let container = docker.run_with_args(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            RunArgs::default()
+                .with_security_opt("no-new-privileges")
+                .with_security_opt("seccomp=unconfined"),
+        );
+
+        let output = docker
+            .inner
+            .command()
+            .arg("inspect")
+            .arg("--format")
+            .arg("{{json .HostConfig.SecurityOpt}}")
+            .arg(container.id())
+            .output()
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            r#"["no-new-privileges","seccomp=unconfined"]"#
+        );
+    }
+
+    #[test]
+    fn cli_run_command_should_include_userns_mode() {
+        let image = GenericImage::new("hello");
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default().with_userns_mode("host");
+        let command = Client::build_run_command(&image, &mut docker, &run_args);
+
+        assert!(format!("{:?}", command).contains(r#""--userns=host""#));
+    }
+
+    #[test]
+    fn cli_run_command_should_include_storage_opts() {
+        let image = GenericImage::new("hello");
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default().with_storage_opt("size", "10G");
+        let command = Client::build_run_command(&image, &mut docker, &run_args);
+
+        assert!(format!("{:?}", command).contains(r#""--storage-opt=size=10G""#));
+    }
+
+    #[test]
+    fn cli_inspect_should_reflect_storage_opts_if_supported_by_driver() {
+        let docker = Cli::default();
+        let run = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            docker.run_with_args(
+                GenericImage::new("alpine:3.13")
+                    .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+                RunArgs::default().with_storage_opt("size", "1G"),
+            )
+        }));
+
+        // most daemons run overlay2 on a file system without project quotas
+        let container = match run {
+            Ok(container) => container,
+            Err(e) => {
+                let message = e.downcast_ref::<String>().expect("panic with message");
+                assert!(message.contains("storage-opt"), "{}", message);
+                return;
+            }
+        };
+        let output = docker
+            .inner
+            .command()
+            .arg("inspect")
+            .arg("--format")
+            .arg("{{json .HostConfig.StorageOpt}}")
+            .arg(container.id())
+            .output()
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            r#"{"size":"1G"}"#
+        );
+    }
+
+    #[test]
+    fn cli_run_command_should_include_health_check() {
+        let image = GenericImage::new("hello");
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default().with_health_check(
+            HealthCheck::new("pg_isready")
+                .with_interval(Duration::from_secs(1))
+                .with_retries(5),
+        );
+        let command = format!(
+            "{:?}",
+            Client::build_run_command(&image, &mut docker, &run_args)
+        );
+
+        assert!(command.contains(
+            r#""--health-cmd=pg_isready" "--health-interval=1000ms" "--health-retries=5""#
+        ));
+    }
+
+    #[test]
+    fn cli_run_command_should_include_labels() {
+        let image = GenericImage::new("hello");
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default().with_label("org.example.shard", "3");
+        let command = Client::build_run_command(&image, &mut docker, &run_args);
+
+        assert!(format!("{:?}", command).contains(r#""--label=org.example.shard=3""#));
+    }
+
+    #[test]
+    fn cli_run_command_should_include_annotations() {
+        let image = GenericImage::new("hello");
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default().with_annotation("org.example.team", "payments");
+        let command = Client::build_run_command(&image, &mut docker, &run_args);
+
+        assert!(format!("{:?}", command).contains(r#""--annotation=org.example.team=payments""#));
+    }
+
+    #[test]
+    fn should_parse_api_version() {
+        assert_eq!(parse_api_version("1.43\n"), Some((1, 43)));
+        assert_eq!(parse_api_version("1.9"), Some((1, 9)));
+        assert_eq!(parse_api_version("unknown"), None);
+        assert!(parse_api_version("1.41").unwrap() < ANNOTATIONS_API_VERSION);
+    }
+
+    #[test]
+    fn cli_inspect_should_reflect_annotations_if_supported() {
+        let docker = Cli::default();
+        let container = docker.run_with_args(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            RunArgs::default().with_annotation("org.example.team", "payments"),
+        );
+
+        let output = docker
+            .inner
+            .command()
+            .arg("inspect")
+            .arg("--format")
+            .arg("{{json .HostConfig.Annotations}}")
+            .arg(container.id())
+            .output()
+            .unwrap();
+        let annotations = String::from_utf8_lossy(&output.stdout);
+
+        if docker.inner.api_version() >= Some(ANNOTATIONS_API_VERSION) {
+            assert_eq!(annotations.trim(), r#"{"org.example.team":"payments"}"#);
+        } else {
+            assert!(!annotations.contains("payments"));
+        }
+    }
+
+    #[test]
+    fn cli_inspect_should_reflect_log_driver() {
+        let docker = Cli::default();
+        let container = docker.run_with_args(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            RunArgs::default().with_log_driver("json-file", vec![("max-size", "1m")]),
+        );
+
+        let output = docker
+            .inner
+            .command()
+            .arg("inspect")
+            .arg("--format")
+            .arg("{{.HostConfig.LogConfig.Type}} {{index .HostConfig.LogConfig.Config \"max-size\"}}")
+            .arg(container.id())
+            .output()
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "json-file 1m"
+        );
+    }
+
+    #[test]
+    fn cli_exec_should_return_output_and_exit_code() {
+        let docker = Cli::default();
+        let container = docker.run(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+        );
+
+        let output = container.exec(ExecCommand::shell("echo foo; echo bar >&2; exit 3"));
+
+        assert_eq!(output.exit_code, 3);
+        assert_eq!(output.stdout, "foo\n");
+        assert_eq!(output.stderr, "bar\n");
+    }
+
+    #[test]
+    fn cli_exec_with_stdin_should_pass_input_to_command() {
+        let docker = Cli::default();
+        let container = docker.run(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+        );
+
+        let output = container.exec_with_stdin(
+            ExecCommand::new(vec!["wc", "-l"]),
+            io::Cursor::new("one\ntwo\nthree\n"),
+        );
+
+        assert!(output.success(), "{}", output.stderr);
+        assert_eq!(output.stdout.trim(), "3");
+    }
+
+    #[test]
+    fn cli_run_should_wait_for_messages_in_order() {
+        let docker = Cli::default();
+        let image = GenericImage::new("alpine:3.13")
+            .with_args(vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "echo two; echo one; sleep 1; echo two; sleep 1; echo three; sleep infinity"
+                    .to_owned(),
+            ])
+            .with_wait_for(WaitFor::messages_in_order_on_stdout(vec![
+                "one", "two", "three",
+            ]));
+
+        let before_run = Instant::now();
+        let container = docker.run(image);
+
+        assert_that(&before_run.elapsed()).is_greater_than(Duration::from_secs(2));
+        assert_eq!(
+            container.exec(ExecCommand::shell("echo ready")).stdout,
+            "ready\n"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "progress interval must not be zero")]
+    fn should_reject_zero_progress_interval() {
+        RunArgs::default().with_progress_interval(Duration::from_secs(0));
+    }
+
+    #[test]
+    fn cli_run_command_should_pass_through_host_env_vars() {
+        std::env::set_var("TESTCONTAINERS_PASSTHROUGH_SET", "from-host");
+        std::env::remove_var("TESTCONTAINERS_PASSTHROUGH_UNSET");
+
+        let image = GenericImage::new("hello");
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default().with_env_passthrough([
+            "TESTCONTAINERS_PASSTHROUGH_SET",
+            "TESTCONTAINERS_PASSTHROUGH_UNSET",
+        ]);
+        let command = Client::build_run_command(&image, &mut docker, &run_args);
+
+        assert!(
+            format!("{:?}", command).contains(r#""-e" "TESTCONTAINERS_PASSTHROUGH_SET=from-host""#)
+        );
+        assert!(!format!("{:?}", command).contains("TESTCONTAINERS_PASSTHROUGH_UNSET"));
+    }
+
+    #[test]
+    fn cli_run_command_should_include_volumes_from() {
+        let image = GenericImage::new("hello");
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default()
+            .with_volumes_from("writer", AccessMode::ReadWrite)
+            .with_volumes_from("config", AccessMode::ReadOnly);
+        let command = Client::build_run_command(&image, &mut docker, &run_args);
+
+        assert!(format!("{:?}", command)
+            .contains(r#""--volumes-from=writer:rw" "--volumes-from=config:ro""#));
+    }
+
+    #[test]
+    fn cli_run_command_should_include_bind_mounts_with_options() {
+        let image = GenericImage::new("hello");
+        let mut docker = Command::new("docker");
+        let run_args = RunArgs::default()
+            .with_bind_mount(BindMount::new("/host/data", "/data"))
+            .with_bind_mount(
+                BindMount::new("/host/config", "/config")
+                    .with_access_mode(AccessMode::ReadOnly)
+                    .with_consistency(Consistency::Cached)
+                    .with_propagation(Propagation::Rshared),
+            );
+        let command = Client::build_run_command(&image, &mut docker, &run_args);
+
+        assert!(format!("{:?}", command).contains(
+            r#""-v" "/host/data:/data:rw" "-v" "/host/config:/config:ro,cached,rshared""#
+        ));
+    }
+
+    #[test]
+    fn cli_should_mount_volumes_of_other_container() {
+        let docker = Cli::default();
+        // the image declares `/data` as volume
+        let writer = docker.run(
+            GenericImage::new("redis:5.0")
+                .with_wait_for(WaitFor::message_on_stdout("Ready to accept connections")),
+        );
+        let output = writer.exec(ExecCommand::shell("echo hello > /data/greeting"));
+        assert!(output.success());
+
+        let reader = docker.run_with_args(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            RunArgs::default().with_volumes_from(writer.id(), AccessMode::ReadOnly),
+        );
+
+        let output = reader.exec(ExecCommand::shell("cat /data/greeting"));
+        assert_eq!(output.stdout.trim(), "hello");
+
+        let output = reader.exec(ExecCommand::shell("touch /data/other"));
+        assert!(!output.success(), "volume should be mounted read-only");
+    }
+
+    #[test]
+    fn cli_should_pass_through_host_env_vars_into_container() {
+        std::env::set_var("TESTCONTAINERS_PASSTHROUGH_RUN", "from-host");
+
+        let docker = Cli::default();
+        let container = docker.run_with_args(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            RunArgs::default().with_env_passthrough(vec!["TESTCONTAINERS_PASSTHROUGH_RUN"]),
+        );
+
+        let output = container.exec(ExecCommand::shell(
+            "printenv TESTCONTAINERS_PASSTHROUGH_RUN",
+        ));
+
+        assert_eq!(output.stdout, "from-host\n");
+    }
+
+    #[test]
+    fn log_file_should_be_named_after_container() {
+        let run_args = RunArgs::default().with_log_to_file("target/testcontainers-logs");
+
+        assert_eq!(
+            run_args.log_file("abc123"),
+            Some("target/testcontainers-logs/abc123.log".into())
+        );
+        assert_eq!(
+            run_args.with_name("db").log_file("abc123"),
+            Some("target/testcontainers-logs/db.log".into())
+        );
+        assert_eq!(RunArgs::default().log_file("abc123"), None);
+    }
+
+    #[test]
+    fn cli_should_write_logs_to_file_when_container_is_removed() {
+        let dir =
+            std::env::temp_dir().join(format!("testcontainers-logs-{}", rand::random::<u32>()));
+
+        {
+            let docker = Cli::default();
+            let _container = docker.run_with_args(
+                HelloWorld::default(),
+                RunArgs::default()
+                    .with_name("hello_logs")
+                    .with_log_to_file(&dir),
+            );
+        }
+
+        let logs = std::fs::read_to_string(dir.join("hello_logs.log")).unwrap();
+        assert!(logs.contains("Hello from Docker!"), "{}", logs);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn cli_run_without_wait_should_skip_ready_conditions() {
+        let docker = Cli::default();
+        let image = GenericImage::new("alpine:3.13")
+            .with_args(vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "sleep 3; echo ready; sleep infinity".to_owned(),
+            ])
+            .with_wait_for(WaitFor::message_on_stdout("ready"));
+
+        let before_run = Instant::now();
+        let container = docker.run_without_wait(image, RunArgs::default());
+
+        assert_that(&before_run.elapsed()).is_less_than(Duration::from_secs(3));
+        assert!(docker.inspect(container.id()).state.running);
+
+        container.wait_until_ready();
+        assert_that(&before_run.elapsed()).is_greater_than(Duration::from_secs(3));
+    }
+
+    #[test]
+    fn cli_drop_should_not_wait_for_graceful_shutdown() {
+        let docker = Cli::default();
+        // `sleep` runs as PID 1 and therefore ignores `SIGTERM`, which makes `stop` time out
+        let container = docker.run(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+        );
+        let id = container.id().to_owned();
+
+        let before_drop = Instant::now();
+        drop(container);
+
+        assert_that(&before_drop.elapsed()).is_less_than(Duration::from_secs(5));
+        let inspect = Command::new("docker")
+            .arg("inspect")
+            .arg(&id)
+            .output()
+            .unwrap();
+        assert!(!inspect.status.success(), "container {} still exists", id);
+    }
+
+    #[test]
+    fn cli_drop_should_only_remove_containers_with_remove_policy() {
+        let docker = Cli::default();
+        let exists = |id: &str| {
+            Command::new("docker")
+                .arg("inspect")
+                .arg(id)
+                .output()
+                .unwrap()
+                .status
+                .success()
+        };
+        let sleep = || {
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()])
+        };
+
+        let removed = docker.run_with_args(
+            sleep(),
+            RunArgs::default().with_removal(RemovalPolicy::Remove),
+        );
+        let kept = docker.run_with_args(
+            sleep(),
+            RunArgs::default().with_removal(RemovalPolicy::Keep),
+        );
+        let (removed_id, kept_id) = (removed.id().to_owned(), kept.id().to_owned());
+        drop(removed);
+        drop(kept);
+
+        let kept_exists = exists(&kept_id);
+        docker.rm(&kept_id);
+        assert!(
+            !exists(&removed_id),
+            "container {} still exists",
+            removed_id
+        );
+        assert!(kept_exists, "container {} was removed", kept_id);
+    }
+
+    #[test]
+    fn cli_drop_should_keep_container_with_keep_on_failure_policy_if_thread_panics() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        let result = std::thread::spawn(move || {
+            let docker = Cli::default();
+            let container = docker.run_with_args(
+                GenericImage::new("alpine:3.13")
+                    .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+                RunArgs::default().with_removal(RemovalPolicy::KeepOnFailure),
+            );
+            sender.send(container.id().to_owned()).unwrap();
+
+            panic!("test failed")
+        })
+        .join();
+        let id = receiver.recv().unwrap();
+
+        let docker = Cli::default();
+        let kept = docker
+            .inner
+            .command()
+            .arg("inspect")
+            .arg(&id)
+            .output()
+            .unwrap();
+        docker.rm(&id);
+        assert!(result.is_err());
+        assert!(kept.status.success(), "container {} was removed", id);
+    }
+
+    #[test]
+    #[should_panic(expected = "did not meet condition message \"never\" on stdout")]
+    fn cli_wait_for_should_panic_if_condition_is_not_met_in_time() {
+        let docker = Cli::default();
+        let container = docker.run_without_wait(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            RunArgs::default().with_startup_timeout(Duration::from_secs(1)),
+        );
+
+        container.wait_for(WaitFor::message_on_stdout("never"));
+    }
+
+    #[test]
+    #[should_panic(expected = "startup timeout exceeded")]
+    fn cli_run_should_respect_startup_timeout_of_image() {
+        let docker = Cli::default();
+
+        docker.run(NeverReady::default());
+    }
+
+    #[test]
+    fn cli_run_should_prefer_startup_timeout_of_run_args_over_image() {
+        let docker = Cli::default();
+        let image = NeverReady {
+            startup_timeout: Duration::from_secs(3600),
+        };
+        let started = std::time::Instant::now();
+
+        let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            docker.run_with_args(
+                image,
+                RunArgs::default().with_startup_timeout(Duration::from_secs(1)),
+            )
+        }))
+        .expect_err("container should not become ready");
+
+        assert!(panic
+            .downcast_ref::<String>()
+            .unwrap()
+            .contains("startup timeout exceeded"));
+        assert!(started.elapsed() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn cli_run_should_replace_named_container_that_did_not_become_ready() {
+        let state =
+            std::env::temp_dir().join(format!("testcontainers-cli-retry-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&state);
+        std::fs::create_dir_all(&state).unwrap();
+
+        // the first container exits without logging, its replacement finds the marker it left
+        let image = GenericImage::new("alpine:3.13")
+            .with_args(vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "if [ -f /state/failed ]; then echo ready; sleep infinity; else touch /state/failed; fi"
+                    .to_owned(),
+            ])
+            .with_wait_for(WaitFor::message_on_stdout("ready"));
+        let docker = Cli::default();
+        let container = docker.run_with_args(
+            image,
+            RunArgs::default()
+                .with_name(format!("cli-retried-{}", std::process::id()))
+                .with_bind_mount(BindMount::new(state.display().to_string(), "/state"))
+                .with_start_retries(1),
+        );
+
+        assert!(state.join("failed").exists());
+        assert_eq!(
+            container
+                .startup_report()
+                .phases()
+                .iter()
+                .filter(|(phase, _)| *phase == StartupPhase::Start)
+                .count(),
+            2
+        );
+        let _ = std::fs::remove_dir_all(&state);
+    }
+
+    #[test]
+    fn cli_run_should_report_phases_of_startup_in_order() {
+        let docker = Cli::default();
+        let image = || {
+            GenericImage::new("alpine:3.13")
+                .with_args(vec![
+                    "sh".to_owned(),
+                    "-c".to_owned(),
+                    "sleep 1 && echo ready && sleep infinity".to_owned(),
+                ])
+                .with_wait_for(WaitFor::message_on_stdout("ready"))
+        };
+        // the image is only pulled by the first test that runs it
+        let phases = |container: &Container<'_, GenericImage>| {
+            container
+                .startup_report()
+                .phases()
+                .iter()
+                .map(|(phase, _)| *phase)
+                .filter(|phase| *phase != StartupPhase::Pull)
+                .collect::<Vec<_>>()
+        };
+
+        let run = docker.run(image());
+        // retries need a created container to remove it if starting fails
+        let created = docker.run_with_args(image(), RunArgs::default().with_start_retries(1));
+
+        assert_eq!(
+            phases(&run),
+            vec![StartupPhase::CreateAndStart, StartupPhase::Ready]
+        );
+        assert_eq!(
+            phases(&created),
+            vec![
+                StartupPhase::Create,
+                StartupPhase::Start,
+                StartupPhase::Ready
+            ]
+        );
+        assert!(run.startup_report().duration(StartupPhase::Ready).unwrap() >= ONE_SECOND);
+        assert!(created.startup_report().total() >= ONE_SECOND);
+    }
+
+    #[test]
+    fn cli_run_should_report_pull_of_missing_image() {
+        let docker = Cli::default();
+        let image = || {
+            GenericImage::new("alpine:3.14")
+                .with_args(vec![
+                    "sh".to_owned(),
+                    "-c".to_owned(),
+                    "echo ready && sleep infinity".to_owned(),
+                ])
+                .with_wait_for(WaitFor::message_on_stdout("ready"))
+        };
+        // no other test uses this tag, hence it is not pulled again while this test runs
+        let _ = docker
+            .inner
+            .command()
+            .args(["image", "rm", "-f", "alpine:3.14"])
+            .output();
+
+        let pulled = docker.run(image());
+        let present = docker.run(image());
+
+        assert_eq!(pulled.startup_report().phases()[0].0, StartupPhase::Pull);
+        assert_eq!(present.startup_report().duration(StartupPhase::Pull), None);
+    }
+
+    #[test]
+    fn cli_run_should_recheck_readiness_after_exec_restarted_the_service() {
+        let docker = Cli::default();
+        let container = docker.run(RestartingWebServer);
+
+        let page = reqwest::blocking::get(format!(
+            "http://localhost:{}/",
+            container.get_host_port(8080)
+        ))
+        .and_then(|response| response.text())
+        .expect("server to be ready again");
+
+        assert_eq!(page, "ok\n");
+    }
+
+    #[test]
+    fn cli_run_should_attach_full_logs_if_container_does_not_become_ready_in_time() {
+        let docker = Cli::default();
+        let image = GenericImage::new("alpine:3.13")
+            .with_args(vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "for i in $(seq 1 200); do echo line $i; done; sleep infinity".to_owned(),
+            ])
+            .with_wait_for(WaitFor::message_on_stdout("never"));
+
+        let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            docker.run_with_args(
+                image,
+                RunArgs::default()
+                    .with_startup_timeout(Duration::from_secs(2))
+                    .with_full_logs_on_timeout(),
+            )
+        }))
+        .expect_err("container should not become ready");
+        let message = panic.downcast_ref::<String>().unwrap();
+
+        assert!(message.contains("startup timeout exceeded, logs of the container:\nline 1\n"));
+        // more lines than the recent logs that other errors include
+        assert!(message.contains("\nline 200\n"));
+    }
+
+    #[test]
+    fn cli_wait_for_line_count_should_be_met_at_threshold() {
+        let docker = Cli::default();
+        let container = docker.run_without_wait(
+            GenericImage::new("alpine:3.13").with_args(vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "for i in 1 2 3; do echo $i; done; sleep infinity".to_owned(),
+            ]),
+            RunArgs::default(),
+        );
+
+        container.wait_for(WaitFor::lines_on_stdout(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "did not meet condition 4 lines on stdout")]
+    fn cli_wait_for_line_count_should_time_out_below_threshold() {
+        let docker = Cli::default();
+        let container = docker.run_without_wait(
+            GenericImage::new("alpine:3.13").with_args(vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "for i in 1 2 3; do echo $i; done; sleep infinity".to_owned(),
+            ]),
+            RunArgs::default(),
+        );
+
+        container.wait_for(WaitFor::LineCount {
+            source: LogSource::StdOut,
+            count: 4,
+            timeout: Some(Duration::from_secs(2)),
+        });
+    }
+
+    #[test]
+    fn cli_wait_for_state_should_be_met_once_process_runs() {
+        let docker = Cli::default();
+        let container = docker.run_without_wait(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            RunArgs::default(),
+        );
+
+        container.wait_for(WaitFor::state("main process to run", |state| {
+            state.running() && state.pid() > 0
+        }));
+    }
+
+    #[test]
+    #[should_panic(expected = "did not meet condition main process to exit")]
+    fn cli_wait_for_state_should_panic_if_predicate_does_not_hold_in_time() {
+        let docker = Cli::default();
+        let container = docker.run_without_wait(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            RunArgs::default().with_startup_timeout(Duration::from_secs(1)),
+        );
+
+        container.wait_for(WaitFor::state("main process to exit", |state| {
+            state.status() == "exited"
+        }));
+    }
+
+    #[test]
+    fn cli_run_or_attach_should_let_only_one_of_racing_callers_create_the_container() {
+        let lease = format!("race-{}", std::process::id());
+        let image = || {
+            GenericImage::new("alpine:3.13")
+                .with_args(vec![
+                    "sh".to_owned(),
+                    "-c".to_owned(),
+                    "echo ready; sleep infinity".to_owned(),
+                ])
+                .with_wait_for(WaitFor::message_on_stdout("ready"))
+        };
+
+        // every caller uses its own client, like tests in different processes would
+        let callers = (0..2)
+            .map(|_| {
+                let lease = lease.clone();
+                let image = image();
+
+                std::thread::spawn(move || {
+                    let docker = Cli::default();
+                    let container = docker.run_or_attach(image, RunArgs::default(), &lease);
+
+                    container.id().to_owned()
+                })
+            })
+            .collect::<Vec<_>>();
+        let ids = callers
+            .into_iter()
+            .map(|caller| caller.join().unwrap())
+            .collect::<Vec<_>>();
+
+        let docker = Cli::default();
+        let container = docker.run_or_attach(image(), RunArgs::default(), &lease);
+        let leased = docker.inner.leased_container(&lease);
+        container.rm();
+
+        assert_eq!(ids[0], ids[1]);
+        assert_eq!(container.id(), ids[0]);
+        assert_eq!(leased.as_deref(), Some(container.id()));
+    }
+
+    #[test]
+    fn cli_run_or_attach_should_wait_for_commands_after_start_of_creator() {
+        let lease = format!("set-up-{}", std::process::id());
+
+        let callers = (0..2)
+            .map(|_| {
+                let lease = lease.clone();
+
+                std::thread::spawn(move || {
+                    let docker = Cli::default();
+                    let container =
+                        docker.run_or_attach(SetUpAfterStart, RunArgs::default(), &lease);
+
+                    container
+                        .exec(ExecCommand::shell("test -f /set-up"))
+                        .exit_code
+                })
+            })
+            .collect::<Vec<_>>();
+        let exit_codes = callers
+            .into_iter()
+            .map(|caller| caller.join().unwrap())
+            .collect::<Vec<_>>();
+
+        let docker = Cli::default();
+        docker
+            .run_or_attach(SetUpAfterStart, RunArgs::default(), &lease)
+            .rm();
+
+        assert_eq!(exit_codes, vec![0, 0]);
+    }
+
+    #[test]
+    fn cli_wait_for_file_should_fail_once_container_exited() {
+        let docker = Cli::default();
+        let container = docker.run_without_wait(
+            GenericImage::new("alpine:3.13").with_args(vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "exit 3".to_owned(),
+            ]),
+            RunArgs::default(),
+        );
+
+        let result = container.wait_for_file(LEASE_READY_FILE);
+
+        assert!(matches!(result, Err(WaitError::Exited(3))));
+    }
+
+    /// Waits until the first health check of the container finished.
+    fn wait_for_health_check(docker: &Cli, id: &str) {
+        let deadline = Instant::now() + Duration::from_secs(30);
+
+        while docker
+            .health(id)
+            .is_some_and(|health| health.status == "starting")
+        {
+            assert!(Instant::now() < deadline, "health check did not finish");
+            sleep(Duration::from_millis(100));
+        }
+    }
+
+    #[test]
+    fn cli_assert_healthy_should_pass_if_health_check_succeeds() {
+        let docker = Cli::default();
+        let container = docker.run_with_args(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            RunArgs::default()
+                .with_health_check(HealthCheck::new("true").with_interval(ONE_SECOND)),
+        );
+
+        wait_for_health_check(&docker, container.id());
+        container.assert_healthy();
+    }
+
+    #[test]
+    #[should_panic(expected = "exit code 1: probe failed")]
+    fn cli_assert_healthy_should_panic_with_output_of_failed_health_checks() {
+        let docker = Cli::default();
+        let container = docker.run_with_args(
+            GenericImage::new("alpine:3.13")
+                .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            RunArgs::default().with_health_check(
+                HealthCheck::new("echo probe failed; exit 1")
+                    .with_interval(ONE_SECOND)
+                    .with_retries(1),
+            ),
+        );
+
+        wait_for_health_check(&docker, container.id());
+        container.assert_healthy();
+    }
+
     #[test]
     fn should_wait_for_at_least_one_second_before_fetching_logs() {
         let _ = pretty_env_logger::try_init();
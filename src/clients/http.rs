@@ -1,20 +1,70 @@
+use super::{parse_api_version, ANNOTATIONS_API_VERSION, NETWORK_LABEL};
+#[cfg(feature = "chrono")]
+use crate::core::{logs::LogPosition, LogSource};
 use crate::{
-    core::{env, logs::LogStreamAsync, ports::Ports, ContainerAsync, DockerAsync, RunArgs},
+    core::{
+        copy::file_from_tar_archive,
+        env,
+        env::GetEnvValue,
+        health::{parse_health, Health},
+        logs::LogStreamAsync,
+        ports::{parse_exposed_ports, Ports},
+        retry::StartError,
+        ContainerAsync, CreateContainerBody, DockerAsync, ExecCommand, ExecHandle, ExecOutput,
+        Gpus, LogChunk, Protocol, RunArgs, StartupPhase, StartupReport,
+    },
     Image,
 };
 use async_trait::async_trait;
-use futures::{executor::block_on, stream::StreamExt, TryStreamExt};
+use futures::{
+    executor::block_on,
+    future::{self, FutureExt},
+    io::{AsyncRead, AsyncReadExt as _},
+    stream::{self, BoxStream, StreamExt},
+    TryStreamExt,
+};
+use hyper::{
+    client::HttpConnector,
+    header::{HeaderValue, CONNECTION, UPGRADE},
+    Body, Method, Request, Response, StatusCode,
+};
+#[cfg(feature = "tls")]
+use hyper_openssl::HttpsConnector;
+#[cfg(unix)]
+use hyperlocal::UnixConnector;
+#[cfg(feature = "tls")]
+use openssl::{
+    error::ErrorStack,
+    ssl::{SslConnector, SslFiletype, SslMethod},
+};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use shiplift::{
-    builder::ContainerOptionsBuilder,
     rep::{ContainerCreateInfo, ContainerDetails},
-    ContainerOptions, Docker, LogsOptions, NetworkCreateOptions, NetworkListOptions, PullOptions,
-    RmContainerOptions,
+    tty::TtyChunk,
+    ContainerOptions, Docker, Exec, ExecContainerOptions, LogsOptions, NetworkListOptions,
+    PullOptions, RegistryAuth, RmContainerOptions,
 };
 use std::{
-    fmt, io,
+    collections::HashMap,
+    error, fmt,
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
     sync::{Arc, RwLock},
+    time::{Duration, Instant},
 };
 
+#[cfg(unix)]
+const DOCKER_SOCKET: &str = "/var/run/docker.sock";
+#[cfg(unix)]
+const PODMAN_ROOTFUL_SOCKET: &str = "/run/podman/podman.sock";
+
+/// The endpoint of the daemon if `DOCKER_HOST` is not set.
+#[cfg(unix)]
+const DEFAULT_ENDPOINT: &str = "unix:///var/run/docker.sock";
+#[cfg(not(unix))]
+const DEFAULT_ENDPOINT: &str = "tcp://localhost:2375";
+
 /// A testcontainers client that uses HTTP to communicate with the docker daemon.
 ///
 /// This client provides an async-based interface.
@@ -28,15 +78,319 @@ pub struct Http {
 struct Client {
     command: env::Command,
     shiplift: Docker,
+    endpoint: Endpoint,
+    pull_policy: PullPolicy,
+    registry_auth: Option<(String, String)>,
+    pull_retries: u32,
     created_networks: RwLock<Vec<String>>,
 }
 
+/// The address of the daemon, used to explain connection failures, together with how requests that
+/// do not go through shiplift are sent to it.
+#[derive(Clone)]
+struct Endpoint {
+    address: String,
+    request_timeout: Option<Duration>,
+    transport: Transport,
+}
+
+impl Endpoint {
+    fn new(address: String, cert_path: Option<&Path>, request_timeout: Option<Duration>) -> Self {
+        Endpoint {
+            transport: Transport::new(&address, cert_path),
+            address,
+            request_timeout,
+        }
+    }
+}
+
+/// An HTTP client for the requests that do not go through shiplift, which connects to the daemon
+/// like shiplift does.
+#[derive(Clone)]
+enum Transport {
+    #[cfg(unix)]
+    Unix {
+        client: hyper::Client<UnixConnector>,
+        socket: String,
+    },
+    Tcp {
+        client: hyper::Client<HttpConnector>,
+        url: String,
+    },
+    #[cfg(feature = "tls")]
+    Tls {
+        client: hyper::Client<HttpsConnector<HttpConnector>>,
+        url: String,
+    },
+}
+
+impl Transport {
+    /// # Panics
+    ///
+    /// This function panics if the certificates to connect over TLS with cannot be loaded.
+    #[cfg_attr(not(feature = "tls"), allow(unused_variables))]
+    fn new(address: &str, cert_path: Option<&Path>) -> Self {
+        #[cfg(unix)]
+        if let Some(socket) = address.strip_prefix("unix://") {
+            return Transport::Unix {
+                client: hyper::Client::builder().build(UnixConnector),
+                socket: socket.to_owned(),
+            };
+        }
+
+        #[cfg(feature = "tls")]
+        if let Some(cert_path) = cert_path {
+            let connector = tls_connector(cert_path).unwrap_or_else(|e| {
+                panic!(
+                    "failed to load TLS certificates from {}: {}",
+                    cert_path.display(),
+                    e
+                )
+            });
+
+            return Transport::Tls {
+                client: hyper::Client::builder().build(connector),
+                url: address.replacen("tcp://", "https://", 1),
+            };
+        }
+
+        Transport::Tcp {
+            client: hyper::Client::new(),
+            url: address.replacen("tcp://", "http://", 1),
+        }
+    }
+
+    /// Builds a request for the given path with an optional JSON body.
+    fn request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<String>,
+    ) -> Result<Request<Body>, hyper::http::Error> {
+        let request = match self {
+            #[cfg(unix)]
+            Transport::Unix { socket, .. } => {
+                Request::builder().uri(hyper::Uri::from(hyperlocal::Uri::new(socket, path)))
+            }
+            Transport::Tcp { url, .. } => Request::builder().uri(format!("{}{}", url, path)),
+            #[cfg(feature = "tls")]
+            Transport::Tls { url, .. } => Request::builder().uri(format!("{}{}", url, path)),
+        }
+        .method(method);
+
+        match body {
+            Some(body) => request
+                .header(hyper::header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body)),
+            None => request.body(Body::empty()),
+        }
+    }
+
+    async fn send(&self, request: Request<Body>) -> hyper::Result<Response<Body>> {
+        match self {
+            #[cfg(unix)]
+            Transport::Unix { client, .. } => client.request(request).await,
+            Transport::Tcp { client, .. } => client.request(request).await,
+            #[cfg(feature = "tls")]
+            Transport::Tls { client, .. } => client.request(request).await,
+        }
+    }
+}
+
+/// Authenticates with `cert.pem` and `key.pem` and verifies the daemon with `ca.pem`, like
+/// shiplift does with the certificates in `DOCKER_CERT_PATH` if `DOCKER_TLS_VERIFY` is set.
+#[cfg(feature = "tls")]
+fn tls_connector(cert_path: &Path) -> Result<HttpsConnector<HttpConnector>, ErrorStack> {
+    let mut ssl = SslConnector::builder(SslMethod::tls())?;
+    ssl.set_certificate_file(cert_path.join("cert.pem"), SslFiletype::PEM)?;
+    ssl.set_private_key_file(cert_path.join("key.pem"), SslFiletype::PEM)?;
+    ssl.set_ca_file(cert_path.join("ca.pem"))?;
+
+    let mut http = HttpConnector::new();
+    http.enforce_http(false);
+
+    HttpsConnector::with_connector(http, ssl)
+}
+
+/// When the [`Http`] client pulls the image of a container.
+///
+/// [`Http`]: struct.Http.html
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PullPolicy {
+    /// Pulls the image only if it is not available locally, which is the default.
+    #[default]
+    Missing,
+    /// Pulls the image before every container, e.g. to pick up a new version of a moving tag.
+    Always,
+    /// Never pulls the image, containers of images that are not available locally fail to start.
+    Never,
+}
+
+/// Configures how an [`Http`] client connects to the daemon and pulls images.
+///
+/// Building the client does not connect to the daemon yet, see [`Http::ping`].
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use testcontainers::clients::{HttpBuilder, PullPolicy};
+///
+/// let docker = HttpBuilder::default()
+///     .with_endpoint("tcp://localhost:2375")
+///     .with_request_timeout(Duration::from_secs(30))
+///     .with_pull_policy(PullPolicy::Always)
+///     .with_pull_retries(3)
+///     .build();
+/// ```
+///
+/// [`Http`]: struct.Http.html
+/// [`Http::ping`]: struct.Http.html#method.ping
+#[derive(Debug, Default)]
+pub struct HttpBuilder {
+    endpoint: Option<String>,
+    cert_path: Option<PathBuf>,
+    request_timeout: Option<Duration>,
+    pull_policy: PullPolicy,
+    registry_auth: Option<(String, String)>,
+    pull_retries: u32,
+}
+
+impl HttpBuilder {
+    /// Connects to the daemon at the given address instead of the one configured through
+    /// `DOCKER_HOST`, e.g. `unix:///var/run/docker.sock` or `tcp://localhost:2375`.
+    pub fn with_endpoint<E: Into<String>>(self, endpoint: E) -> Self {
+        HttpBuilder {
+            endpoint: Some(endpoint.into()),
+            ..self
+        }
+    }
+
+    /// Connects to a `tcp://` endpoint over TLS with the certificates in the given directory, like
+    /// `DOCKER_CERT_PATH` does for the docker CLI: `ca.pem` verifies the daemon, `cert.pem` and
+    /// `key.pem` authenticate the client. Defaults to `DOCKER_CERT_PATH` if it is set.
+    ///
+    /// shiplift only reads the certificates from the environment, so unless `DOCKER_CERT_PATH`
+    /// and `DOCKER_TLS_VERIFY` already point at them, both are changed while the client is built.
+    /// This is not safe if other threads read the environment at the same time outside of this
+    /// crate; set the variables instead of calling this in that case.
+    #[cfg(feature = "tls")]
+    pub fn with_tls<P: Into<PathBuf>>(self, cert_path: P) -> Self {
+        HttpBuilder {
+            cert_path: Some(cert_path.into()),
+            ..self
+        }
+    }
+
+    /// Gives up on requests to the daemon that are not answered in time, e.g. because the daemon
+    /// hangs. Applies to pinging the daemon, creating containers and networks and inspecting the
+    /// health of containers, but not to pulling images or streaming logs, which take as long as
+    /// they take. Requests are not bounded by default.
+    pub fn with_request_timeout(self, request_timeout: Duration) -> Self {
+        HttpBuilder {
+            request_timeout: Some(request_timeout),
+            ..self
+        }
+    }
+
+    pub fn with_pull_policy(self, pull_policy: PullPolicy) -> Self {
+        HttpBuilder {
+            pull_policy,
+            ..self
+        }
+    }
+
+    /// Authenticates against the registry with the given credentials when pulling images.
+    pub fn with_registry_auth<U: Into<String>, P: Into<String>>(
+        self,
+        username: U,
+        password: P,
+    ) -> Self {
+        HttpBuilder {
+            registry_auth: Some((username.into(), password.into())),
+            ..self
+        }
+    }
+
+    /// Retries pulling an image at most the given number of times, e.g. to ride out rate limits
+    /// of the registry. Pulls are not retried by default.
+    pub fn with_pull_retries(self, pull_retries: u32) -> Self {
+        HttpBuilder {
+            pull_retries,
+            ..self
+        }
+    }
+
+    /// Creates the client.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the configured endpoint is not a valid url or if the certificates
+    /// to connect over TLS with cannot be read.
+    pub fn build(self) -> Http {
+        let cert_path = self.cert_path.or_else(|| {
+            env::Os::get_env_value("DOCKER_CERT_PATH")
+                .filter(|_| cfg!(feature = "tls"))
+                .map(PathBuf::from)
+        });
+        let (shiplift, address) = match self.endpoint {
+            Some(endpoint) => (docker_at(&endpoint, cert_path.as_deref()), endpoint),
+            None => connect::<env::Os>(cert_path.as_deref()),
+        };
+        // certificates only apply to TCP endpoints
+        let cert_path = cert_path.filter(|_| !address.starts_with("unix://"));
+        let endpoint = Endpoint::new(address, cert_path.as_deref(), self.request_timeout);
+
+        Http {
+            inner: Arc::new(Client {
+                command: env::command::<env::Os>().unwrap_or_default(),
+                shiplift,
+                endpoint,
+                pull_policy: self.pull_policy,
+                registry_auth: self.registry_auth,
+                pull_retries: self.pull_retries,
+                created_networks: RwLock::new(Vec::new()),
+            }),
+        }
+    }
+}
+
 impl fmt::Debug for Http {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Http").finish()
     }
 }
 
+/// The error returned by [`Http::ping`] if the docker daemon cannot be reached.
+///
+/// [`Http::ping`]: struct.Http.html#method.ping
+#[derive(Debug)]
+pub struct PingError {
+    endpoint: String,
+    source: shiplift::Error,
+}
+
+impl PingError {
+    /// The address of the daemon that could not be reached.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+}
+
+impl fmt::Display for PingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to reach the docker daemon at {}: {}; make sure the daemon is running or set `DOCKER_HOST` to the address of a running daemon",
+            self.endpoint, self.source
+        )
+    }
+}
+
+impl error::Error for PingError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
 impl Default for Http {
     fn default() -> Self {
         Self::new()
@@ -45,6 +399,32 @@ impl Default for Http {
 
 // public API
 impl Http {
+    /// Checks that the docker daemon is reachable.
+    ///
+    /// Creating a client does not connect to the daemon, so calling this first turns a missing
+    /// daemon into a descriptive error instead of a panic in the middle of [`run`].
+    ///
+    /// [`run`]: struct.Http.html#method.run
+    pub async fn ping(&self) -> Result<(), PingError> {
+        let ping = self.inner.shiplift.ping();
+        let result = match self.inner.endpoint.request_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, ping)
+                .await
+                .unwrap_or_else(|_| {
+                    Err(shiplift::Error::IO(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("no response within {:?}", timeout),
+                    )))
+                }),
+            None => ping.await,
+        };
+
+        result.map(|_| ()).map_err(|source| PingError {
+            endpoint: self.inner.endpoint.address.clone(),
+            source,
+        })
+    }
+
     pub async fn run<I: Image + Send + Sync>(&self, image: I) -> ContainerAsync<'_, I> {
         self.run_with_args(image, RunArgs::default()).await
     }
@@ -54,137 +434,435 @@ impl Http {
         image: I,
         run_args: RunArgs,
     ) -> ContainerAsync<'_, I> {
-        let mut options_builder = ContainerOptions::builder(image.descriptor().as_str());
+        run_args.assert_valid_memory();
+        run_args.assert_logs_readable(&image);
 
-        // Create network and add it to container creation
-        if let Some(network) = run_args.network() {
-            options_builder.network_mode(network.as_str());
-            if self.create_network_if_not_exists(&network).await {
-                let mut guard = self
-                    .inner
-                    .created_networks
-                    .write()
-                    .expect("'failed to lock RwLock'");
-                guard.push(network);
+        let mut report = StartupReport::default();
+        let mut retries_left = run_args.start_retries();
+        let id = self
+            .start_container(&image, &run_args, &mut retries_left, &mut report)
+            .await;
+        let mut container = self.container(id, image, &run_args);
+
+        // a container that did not become ready is replaced as a whole
+        let mut waiting = Instant::now();
+        while let Err(e) = container.try_until_ready().await {
+            report.record_since(StartupPhase::Ready, waiting);
+            if retries_left == 0 {
+                panic!("container {} did not become ready: {}", container.id(), e)
             }
-        }
+            retries_left -= 1;
+
+            log::warn!(
+                "Container {} did not become ready, retrying: {}",
+                container.id(),
+                e
+            );
 
-        // name of the container
-        if let Some(name) = run_args.name() {
-            options_builder.name(name.as_str());
+            // the replacement might need the name and the host ports of the failed container
+            container.remove_for_replacement().await;
+            let id = self
+                .start_container(container.image(), &run_args, &mut retries_left, &mut report)
+                .await;
+            container.replace_with(id);
+            waiting = Instant::now();
         }
+        container.exec_after_start().await;
+        report.record_since(StartupPhase::Ready, waiting);
+        *container.startup_report_mut() = report;
 
-        // handle environment variables
-        let envs: Vec<String> = image
-            .env_vars()
-            .into_iter()
-            .map(|(k, v)| format!("{}={}", k, v))
-            .collect();
+        container
+    }
 
-        // the fact .env and .volumes takes Vec<&str> instead of AsRef<str> is making
-        // this more difficult than it needs to be
-        let envs_str: Vec<&str> = envs.iter().map(|s| s.as_ref()).collect();
-        options_builder.env(envs_str);
+    /// Starts the container but returns right away instead of waiting for it to be ready.
+    ///
+    /// This allows checking the readiness through a custom protocol. Use
+    /// [`ContainerAsync::wait_until_ready`] or [`ContainerAsync::wait_for`] to wait later on.
+    ///
+    /// [`ContainerAsync::wait_until_ready`]: ../core/struct.ContainerAsync.html#method.wait_until_ready
+    /// [`ContainerAsync::wait_for`]: ../core/struct.ContainerAsync.html#method.wait_for
+    pub async fn run_without_wait<I: Image + Send + Sync>(
+        &self,
+        image: I,
+        run_args: RunArgs,
+    ) -> ContainerAsync<'_, I> {
+        run_args.assert_valid_memory();
+        run_args.assert_logs_readable(&image);
 
-        // volumes
-        let vols: Vec<String> = image
-            .volumes()
-            .into_iter()
-            .map(|(orig, dest)| format!("{}:{}", orig, dest))
-            .collect();
-        let vols_str: Vec<&str> = vols.iter().map(|s| s.as_ref()).collect();
-        options_builder.volumes(vols_str);
+        let mut report = StartupReport::default();
+        let mut retries_left = run_args.start_retries();
+        let id = self
+            .start_container(&image, &run_args, &mut retries_left, &mut report)
+            .await;
 
-        // entrypoint
-        if let Some(entrypoint) = image.entrypoint() {
-            options_builder.entrypoint(entrypoint.as_str());
-        }
+        let mut container = self.container(id, image, &run_args);
+        *container.startup_report_mut() = report;
 
-        // ports
-        if let Some(ports) = run_args.ports() {
-            // TODO support UDP?
-            for port in &ports {
-                // casting u16 to u32
-                options_builder.expose(port.internal as u32, "tcp", port.local as u32);
+        container
+    }
+
+    /// Removes the networks created through [`RunArgs::with_network`] that are no longer used by
+    /// any container, e.g. because a previous test run crashed before it could clean them up.
+    ///
+    /// Networks that were not created by testcontainers are never removed. Returns the names of
+    /// the removed networks.
+    ///
+    /// [`RunArgs::with_network`]: ../core/struct.RunArgs.html#method.with_network
+    pub async fn prune_networks(&self) -> Vec<String> {
+        let networks = self.inner.shiplift.networks();
+        let mut pruned = Vec::new();
+
+        for network in networks
+            .list(&NetworkListOptions::default())
+            .await
+            .unwrap_or_else(|e| panic!("failed to list docker networks: {}", e))
+        {
+            let created_by_testcontainers = network
+                .labels
+                .as_ref()
+                .and_then(|labels| labels.get(NETWORK_LABEL.0))
+                .is_some_and(|value| value == NETWORK_LABEL.1);
+
+            if !created_by_testcontainers {
+                continue;
             }
-        } else {
-            options_builder.publish_all_ports();
-        }
-
-        // create the container with options
-        let create_result = self.create_container(&options_builder).await;
-        let id = {
-            match create_result {
-                Ok(container) => container.id,
-                Err(shiplift::Error::Fault { code, .. }) if code == 404 => {
-                    {
-                        let mut options_builder = PullOptions::builder();
-                        options_builder.image(image.descriptor());
-                        let mut pulling =
-                            self.inner.shiplift.images().pull(&options_builder.build());
-                        while let Some(result) = pulling.next().await {
-                            if result.is_err() {
-                                result.unwrap();
-                            }
-                        }
-                    }
-                    self.create_container(&options_builder).await.unwrap().id
-                }
-                Err(err) => panic!("{}", err),
+
+            // listing networks does not report their containers
+            let in_use = match networks.get(&network.id).inspect().await {
+                Ok(details) => !details.containers.is_empty(),
+                Err(_) => continue,
+            };
+
+            // the network might have been removed or used in the meantime
+            if !in_use && networks.get(&network.id).delete().await.is_ok() {
+                pruned.push(network.name);
             }
-        };
+        }
 
+        // pruned networks must not be deleted again once the client is dropped
         self.inner
+            .created_networks
+            .write()
+            .expect("failed to lock RwLock")
+            .retain(|network| !pruned.contains(network));
+
+        pruned
+    }
+
+    /// Returns the ports the given image declares through `EXPOSE`, sorted by port.
+    ///
+    /// The image has to be available locally, it is not pulled.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the image cannot be inspected.
+    pub async fn image_exposed_ports(&self, descriptor: &str) -> Vec<(u16, Protocol)> {
+        let details = self
+            .inner
             .shiplift
-            .containers()
-            .get(&id)
-            .start()
+            .images()
+            .get(descriptor)
+            .inspect()
             .await
-            .unwrap();
-
-        let client = Http {
-            inner: self.inner.clone(),
-        };
+            .unwrap_or_else(|e| panic!("failed to inspect image {}: {}", descriptor, e));
 
-        ContainerAsync::new(id, client, image, self.inner.command).await
+        details
+            .config
+            .exposed_ports
+            .map(|exposed_ports| parse_exposed_ports(exposed_ports.keys()))
+            .unwrap_or_default()
     }
 }
 
 impl Http {
     fn new() -> Self {
-        Http {
-            inner: Arc::new(Client {
-                command: env::command::<env::Os>().unwrap_or_default(),
-                shiplift: Docker::new(),
-                created_networks: RwLock::new(Vec::new()),
-            }),
+        HttpBuilder::default().build()
+    }
+
+    /// Pulls the image, retrying as configured through [`HttpBuilder::with_pull_retries`].
+    ///
+    /// [`HttpBuilder::with_pull_retries`]: struct.HttpBuilder.html#method.with_pull_retries
+    async fn pull(&self, descriptor: &str) {
+        let mut attempt = 0;
+
+        loop {
+            let mut options_builder = PullOptions::builder();
+            options_builder.image(descriptor);
+            if let Some((username, password)) = &self.inner.registry_auth {
+                options_builder.auth(
+                    RegistryAuth::builder()
+                        .username(username)
+                        .password(password)
+                        .build(),
+                );
+            }
+
+            let mut pulling = self.inner.shiplift.images().pull(&options_builder.build());
+            let mut result = Ok(());
+            while let Some(chunk) = pulling.next().await {
+                if let Err(e) = chunk {
+                    result = Err(e);
+                    break;
+                }
+            }
+
+            match result {
+                Ok(()) => return,
+                Err(e) if attempt < self.inner.pull_retries => {
+                    attempt += 1;
+                    log::warn!(
+                        "Failed to pull image {}, retrying ({} of {}): {}",
+                        descriptor,
+                        attempt,
+                        self.inner.pull_retries,
+                        e
+                    );
+                }
+                Err(e) => panic!("failed to pull image {}: {}", descriptor, e),
+            }
         }
     }
 
-    async fn create_network_if_not_exists(&self, network: &str) -> bool {
-        if !network_exists(&self.inner.shiplift, network).await {
-            self.inner
-                .shiplift
-                .networks()
-                .create(&NetworkCreateOptions::builder(network).build())
-                .await
-                .unwrap();
+    /// Starts a container of the image and returns its id, retrying transient errors.
+    async fn start_container<I: Image>(
+        &self,
+        image: &I,
+        run_args: &RunArgs,
+        retries_left: &mut u32,
+        report: &mut StartupReport,
+    ) -> String {
+        self.create_network(run_args).await;
 
-            return true;
+        if self.inner.pull_policy == PullPolicy::Always {
+            let pulling = Instant::now();
+            self.pull(&image.descriptor()).await;
+            report.record_since(StartupPhase::Pull, pulling);
         }
 
-        false
+        let run_args = self.supported_run_args(run_args).await;
+        let path = create_path(run_args.name().as_deref());
+        let body = create_body(&container_options(image, &run_args), image, &run_args);
+
+        loop {
+            match self.try_start_container(image, &path, &body, report).await {
+                Err(e) if e.is_retryable() && *retries_left > 0 => {
+                    *retries_left -= 1;
+                    log::warn!("Failed to start container, retrying: {}", e);
+                }
+                result => {
+                    return result.unwrap_or_else(|e| panic!("failed to start container: {}", e))
+                }
+            }
+        }
     }
 
-    async fn create_container(
+    /// Creates and starts a container of the image and returns its id.
+    ///
+    /// A container that was created but failed to start is removed again.
+    async fn try_start_container<I: Image>(
         &self,
-        options_builder: &ContainerOptionsBuilder,
-    ) -> shiplift::Result<ContainerCreateInfo> {
-        self.inner
-            .shiplift
-            .containers()
-            .create(&options_builder.build())
+        image: &I,
+        path: &str,
+        body: &str,
+        report: &mut StartupReport,
+    ) -> Result<String, StartError> {
+        let mut creating = Instant::now();
+        let id = match self.create_container(path, body).await {
+            Ok(id) => id,
+            Err(e) if e.status == Some(404) && self.inner.pull_policy == PullPolicy::Missing => {
+                report.record_since(StartupPhase::Create, creating);
+
+                let pulling = Instant::now();
+                self.pull(&image.descriptor()).await;
+                report.record_since(StartupPhase::Pull, pulling);
+
+                creating = Instant::now();
+                self.create_container(path, body)
+                    .await
+                    .map_err(|e| StartError::new(e.to_string()))?
+            }
+            Err(e) if e.status == Some(404) => panic!(
+                "image {} is not available locally and the pull policy does not allow to pull it",
+                image.descriptor()
+            ),
+            Err(e) => return Err(StartError::new(e.to_string())),
+        };
+
+        let container = self.inner.shiplift.containers().get(&id);
+
+        for file in image.copy_to_sources() {
+            container
+                .copy_file_into(&file.target, &file.data)
+                .await
+                .unwrap_or_else(|e| panic!("failed to copy file to {}: {}", file.target, e));
+        }
+        report.record_since(StartupPhase::Create, creating);
+
+        let starting = Instant::now();
+        let started = container.start().await;
+        report.record_since(StartupPhase::Start, starting);
+
+        if let Err(e) = started {
+            // best effort, the error of starting the container is more relevant
+            let _ = container
+                .remove(
+                    RmContainerOptions::builder()
+                        .volumes(true)
+                        .force(true)
+                        .build(),
+                )
+                .await;
+
+            return Err(StartError::new(e.to_string()));
+        }
+
+        Ok(id)
+    }
+
+    /// Drops the options the daemon is too old for but that are safe to omit, i.e. annotations.
+    async fn supported_run_args(&self, run_args: &RunArgs) -> RunArgs {
+        if run_args.annotations().is_empty() {
+            return run_args.clone();
+        }
+
+        match self.api_version().await {
+            Some(version) if version >= ANNOTATIONS_API_VERSION => run_args.clone(),
+            version => {
+                log::warn!(
+                    "Dropping annotations because docker API version {:?} does not support them",
+                    version
+                );
+
+                run_args.clone().without_annotations()
+            }
+        }
+    }
+
+    /// Returns the API version of the daemon, which raw requests are handled with as they do not
+    /// ask for a specific one.
+    async fn api_version(&self) -> Option<(u32, u32)> {
+        let version = raw_request(&self.inner.endpoint, Method::GET, "/version", None)
             .await
+            .ok()?;
+        let version = serde_json::from_slice::<serde_json::Value>(&version).ok()?;
+
+        parse_api_version(version["ApiVersion"].as_str()?)
+    }
+
+    fn container<I: Image>(
+        &self,
+        id: String,
+        image: I,
+        run_args: &RunArgs,
+    ) -> ContainerAsync<'_, I> {
+        let client = Http {
+            inner: self.inner.clone(),
+        };
+        let log_file = run_args.log_file(&id);
+
+        ContainerAsync::new(
+            id,
+            client,
+            image,
+            run_args.removal(self.inner.command),
+            run_args.startup_timeout(),
+            run_args.progress_interval(),
+            run_args.full_logs_on_timeout(),
+            log_file,
+        )
+    }
+
+    async fn create_network(&self, run_args: &RunArgs) {
+        if run_args.existing_network() {
+            return;
+        }
+
+        if let Some(network) = run_args.network() {
+            if self.create_network_if_not_exists(&network, run_args).await {
+                let mut guard = self
+                    .inner
+                    .created_networks
+                    .write()
+                    .expect("failed to lock RwLock");
+
+                guard.push(network);
+            }
+        }
+    }
+
+    async fn create_network_if_not_exists(&self, network: &str, run_args: &RunArgs) -> bool {
+        if network_exists(&self.inner.shiplift, network).await {
+            return false;
+        }
+
+        // shiplift can only set the driver and the labels of a network
+        let mut options = serde_json::Map::new();
+        if let Some(mtu) = run_args.network_mtu() {
+            options.insert(
+                "com.docker.network.driver.mtu".to_owned(),
+                mtu.to_string().into(),
+            );
+        }
+        let body = serde_json::json!({
+            "Name": network,
+            "Labels": network_labels(),
+            "Internal": run_args.internal_network(),
+            "Options": options,
+        });
+
+        raw_request(
+            &self.inner.endpoint,
+            Method::POST,
+            "/networks/create",
+            Some(body.to_string()),
+        )
+        .await
+        .unwrap_or_else(|e| panic!("failed to create network {}: {}", network, e));
+
+        true
+    }
+
+    /// Creates the container with the given body and returns its id.
+    async fn create_container(&self, path: &str, body: &str) -> Result<String, DaemonError> {
+        let response = raw_request(
+            &self.inner.endpoint,
+            Method::POST,
+            path,
+            Some(body.to_owned()),
+        )
+        .await?;
+        let container =
+            serde_json::from_slice::<ContainerCreateInfo>(&response).map_err(|e| DaemonError {
+                status: None,
+                message: format!("failed to parse created container: {}", e),
+            })?;
+
+        Ok(container.id)
+    }
+
+    /// Returns the given number of the last lines, or `all`, the container logged to stdout and
+    /// stderr.
+    async fn logs_tail(&self, id: &str, tail: &str) -> String {
+        let options = LogsOptions::builder()
+            .stdout(true)
+            .stderr(true)
+            .tail(tail)
+            .build();
+        let container = self.inner.shiplift.containers().get(id);
+        let mut chunks = container.logs(&options);
+        let mut logs = Vec::new();
+
+        while let Some(chunk) = chunks.next().await {
+            match chunk {
+                Ok(chunk) => logs.extend(Vec::from(chunk)),
+                Err(e) => {
+                    log::warn!("Failed to read logs of container {}: {}", id, e);
+                    break;
+                }
+            }
+        }
+
+        String::from_utf8_lossy(&logs).into_owned()
     }
 
     fn logs(&self, container_id: String, options: LogsOptions) -> LogStreamAsync<'_> {
@@ -194,10 +872,9 @@ impl Http {
             .containers()
             .get(container_id)
             .logs(&options)
-            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+            .map_err(io::Error::other)
             .map(|chunk| {
-                let string = String::from_utf8(Vec::from(chunk?))
-                    .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+                let string = String::from_utf8(Vec::from(chunk?)).map_err(io::Error::other)?;
 
                 Ok(string)
             })
@@ -207,122 +884,1505 @@ impl Http {
     }
 }
 
-async fn network_exists(client: &Docker, network: &str) -> bool {
-    // There's no public builder for NetworkListOptions yet
-    // might need to add one in shiplift
-    // this is doing unnecessary stuff
-    let network_list_optons = NetworkListOptions::default();
-    let networks = client.networks().list(&network_list_optons).await.unwrap();
+/// An error of a request to the daemon, with the status code of the response if there is one.
+#[derive(Debug)]
+struct DaemonError {
+    status: Option<u16>,
+    message: String,
+}
+
+impl fmt::Display for DaemonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<shiplift::Error> for DaemonError {
+    fn from(e: shiplift::Error) -> Self {
+        let status = match &e {
+            shiplift::Error::Fault { code, .. } => Some(code.as_u16()),
+            _ => None,
+        };
+
+        DaemonError {
+            status,
+            message: e.to_string(),
+        }
+    }
+}
+
+/// Sends a request for the given path with an optional JSON body to the daemon at the endpoint and
+/// returns the body of the response.
+///
+/// shiplift only exposes the fields of responses it knows about, which excludes e.g. the health of
+/// containers, and only sends the fields of requests it knows about.
+async fn raw_request(
+    endpoint: &Endpoint,
+    method: Method,
+    path: &str,
+    body: Option<String>,
+) -> Result<Vec<u8>, DaemonError> {
+    let other = |message: String| DaemonError {
+        status: None,
+        message,
+    };
+
+    let response = async {
+        let request = endpoint
+            .transport
+            .request(method, path, body)
+            .map_err(|e| other(format!("invalid request for {}: {}", path, e)))?;
+        let response = endpoint
+            .transport
+            .send(request)
+            .await
+            .map_err(|e| other(format!("failed to send request for {}: {}", path, e)))?;
+
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(|e| other(format!("failed to read response to {}: {}", path, e)))?;
+
+        if status.is_success() {
+            Ok(body.to_vec())
+        } else {
+            Err(DaemonError {
+                status: Some(status.as_u16()),
+                message: format!(
+                    "{} for {}: {}",
+                    status,
+                    path,
+                    String::from_utf8_lossy(&body).trim()
+                ),
+            })
+        }
+    };
+
+    match endpoint.request_timeout {
+        Some(timeout) => tokio::time::timeout(timeout, response)
+            .await
+            .unwrap_or_else(|_| {
+                Err(other(format!(
+                    "no response to {} within {:?}",
+                    path, timeout
+                )))
+            }),
+        None => response.await,
+    }
+}
+
+/// Returns the options of the container that shiplift knows about, see [`create_body`] for the
+/// others.
+fn container_options<I: Image>(image: &I, run_args: &RunArgs) -> ContainerOptions {
+    let mut options_builder = ContainerOptions::builder(image.descriptor().as_str());
+
+    if let Some(network) = run_args.network() {
+        options_builder.network_mode(network.as_str());
+    }
+
+    if let Some(memory) = run_args.memory() {
+        options_builder.memory(memory);
+    }
+    if let Some(memory_swap) = run_args.memory_swap() {
+        options_builder.memory_swap(memory_swap);
+    }
+
+    // name of the container
+    if let Some(name) = run_args.name() {
+        options_builder.name(name.as_str());
+    }
+
+    if !run_args.labels().is_empty() {
+        options_builder.labels(
+            &run_args
+                .labels()
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect(),
+        );
+    }
+
+    if let Some(log_config) = run_args.log_config() {
+        options_builder.log_driver(log_config.driver.as_str());
+    }
+
+    // handle environment variables
+    let envs: Vec<String> = image
+        .env_vars()
+        .into_iter()
+        .chain(run_args.env_vars().iter().cloned())
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect();
+
+    // the fact .env and .volumes takes Vec<&str> instead of AsRef<str> is making
+    // this more difficult than it needs to be
+    let envs_str: Vec<&str> = envs.iter().map(|s| s.as_ref()).collect();
+    options_builder.env(envs_str);
+
+    // volumes
+    let vols: Vec<String> = image
+        .volumes()
+        .into_iter()
+        .map(|(orig, dest)| format!("{}:{}", orig, dest))
+        .chain(run_args.bind_mounts())
+        .collect();
+    let vols_str: Vec<&str> = vols.iter().map(|s| s.as_ref()).collect();
+    options_builder.volumes(vols_str);
+
+    let volumes_from = run_args.volumes_from();
+    if !volumes_from.is_empty() {
+        options_builder.volumes_from(volumes_from.iter().map(|s| s.as_ref()).collect());
+    }
+
+    // additional entries for /etc/hosts
+    let hosts: Vec<String> = image
+        .hosts()
+        .into_iter()
+        .map(|(hostname, address)| format!("{}:{}", hostname, address))
+        .collect();
+    if !hosts.is_empty() {
+        options_builder.extra_hosts(hosts.iter().map(|s| s.as_ref()).collect());
+    }
+
+    if image.privileged() {
+        options_builder.privileged(true);
+    }
+
+    if let Some(userns_mode) = run_args.userns_mode() {
+        options_builder.userns_mode(userns_mode.as_str());
+    }
+
+    // entrypoint
+    if let Some(entrypoint) = image.entrypoint() {
+        options_builder.entrypoint(entrypoint.as_str());
+    }
+
+    // command, an empty one keeps the default of the image
+    let cmd = run_args.cmd(image);
+    if !cmd.is_empty() {
+        options_builder.cmd(cmd.iter().map(String::as_str).collect());
+    }
+
+    // ports
+    for port in image.mapped_ports() {
+        options_builder.expose(port.internal as u32, "tcp", port.local as u32);
+    }
+    if let Some(ports) = run_args.ports() {
+        // TODO support UDP?
+        for port in &ports {
+            // casting u16 to u32
+            options_builder.expose(port.internal as u32, "tcp", port.local as u32);
+        }
+    } else {
+        for port in image.expose_ports() {
+            // host port 0 lets the daemon pick a random one
+            options_builder.expose(port as u32, "tcp", 0);
+        }
+        options_builder.publish_all_ports();
+    }
+
+    options_builder.build()
+}
+
+/// Returns the path of the request creating a container with the given name.
+fn create_path(name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!(
+            "/containers/create?name={}",
+            utf8_percent_encode(name, NON_ALPHANUMERIC)
+        ),
+        None => "/containers/create".to_owned(),
+    }
+}
+
+/// Returns the body of the request creating a container with the given options.
+///
+/// shiplift only serializes the options it knows about, the others are added to the body before
+/// it is changed by the create modifier, if there is one.
+fn create_body<I: Image>(options: &ContainerOptions, image: &I, run_args: &RunArgs) -> String {
+    let body = options
+        .serialize()
+        .unwrap_or_else(|e| panic!("failed to serialize container options: {}", e));
+    let mut body: CreateContainerBody = serde_json::from_str(&body)
+        .unwrap_or_else(|e| panic!("failed to parse container options: {}", e));
+
+    let host_config = &mut body["HostConfig"];
+    if let Some(oom_score_adj) = run_args.oom_score_adj() {
+        host_config["OomScoreAdj"] = oom_score_adj.into();
+    }
+    if run_args.oom_kill_disabled() {
+        host_config["OomKillDisable"] = true.into();
+    }
+    if let Some(gpus) = run_args.gpus() {
+        let count = match gpus {
+            Gpus::All => -1,
+            Gpus::Count(count) => i64::from(count),
+        };
+        host_config["DeviceRequests"] = serde_json::json!([{
+            "Driver": "",
+            "Count": count,
+            "Capabilities": [["gpu"]],
+        }]);
+    }
+    if let Some(isolation) = run_args.isolation() {
+        host_config["Isolation"] = isolation.as_str().into();
+    }
+    if !run_args.security_opts().is_empty() {
+        host_config["SecurityOpt"] = run_args
+            .security_opts()
+            .iter()
+            .map(|option| security_opt(option))
+            .collect::<Vec<_>>()
+            .into();
+    }
+    if !run_args.storage_opts().is_empty() {
+        host_config["StorageOpt"] = json_object(run_args.storage_opts());
+    }
+    if !run_args.annotations().is_empty() {
+        host_config["Annotations"] = json_object(run_args.annotations());
+    }
+    let tmpfs = image
+        .tmpfs()
+        .into_iter()
+        .map(|mount| match mount.split_once(':') {
+            Some((path, options)) => (path.to_owned(), options.to_owned()),
+            None => (mount, String::new()),
+        })
+        .collect::<Vec<_>>();
+    if !tmpfs.is_empty() {
+        host_config["Tmpfs"] = json_object(&tmpfs);
+    }
+    // shiplift only sets the type of the log driver
+    if let Some(log_config) = run_args.log_config() {
+        if !log_config.options.is_empty() {
+            host_config["LogConfig"]["Config"] = json_object(&log_config.options);
+        }
+    }
+
+    if let Some(health_check) = run_args.health_check() {
+        body["Healthcheck"] = health_check.to_body();
+    }
+
+    if let Some(modifier) = run_args.create_modifier() {
+        modifier.apply(&mut body);
+    }
+
+    body.to_string()
+}
+
+fn json_object(entries: &[(String, String)]) -> CreateContainerBody {
+    entries
+        .iter()
+        .map(|(key, value)| (key.clone(), value.as_str().into()))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+/// Replaces the path of a seccomp profile by its contents, as the daemon expects the profile
+/// itself, like `docker run --security-opt` does.
+fn security_opt(option: &str) -> String {
+    match option.strip_prefix("seccomp=") {
+        Some(profile) if profile != "unconfined" => {
+            let contents = std::fs::read_to_string(profile)
+                .unwrap_or_else(|e| panic!("failed to read seccomp profile {}: {}", profile, e));
+
+            format!("seccomp={}", contents)
+        }
+        _ => option.to_owned(),
+    }
+}
+
+/// Connects to the daemon configured through `DOCKER_HOST`.
+///
+/// If `DOCKER_HOST` is not set and Docker's default socket does not exist, we fall back to the
+/// Docker-compatible API of Podman. Returns the client together with the endpoint it talks to.
+fn connect<E: GetEnvValue>(cert_path: Option<&Path>) -> (Docker, String) {
+    #[cfg(unix)]
+    if let Some(socket) = fallback_socket::<E, _>(|path| path.exists()) {
+        log::debug!("Docker socket not found, connecting to {}", socket);
+
+        let endpoint = format!("unix://{}", socket.path());
+
+        return (Docker::unix(socket.path()), endpoint);
+    }
+
+    let endpoint = E::get_env_value("DOCKER_HOST").unwrap_or_else(|| DEFAULT_ENDPOINT.to_owned());
+
+    (docker_at(&endpoint, cert_path), endpoint)
+}
+
+/// Connects to the daemon at the given endpoint, which is either a unix socket or a url, over TLS
+/// if the directory of the certificates is given.
+fn docker_at(endpoint: &str, cert_path: Option<&Path>) -> Docker {
+    #[cfg(unix)]
+    if let Some(socket) = endpoint.strip_prefix("unix://") {
+        return Docker::unix(socket);
+    }
+
+    let docker = || {
+        Docker::host(
+            endpoint
+                .parse()
+                .unwrap_or_else(|e| panic!("invalid docker endpoint {}: {}", endpoint, e)),
+        )
+    };
+
+    match cert_path {
+        Some(cert_path) => with_tls_env(cert_path, docker),
+        None => docker(),
+    }
+}
+
+/// shiplift only reads the certificates from `DOCKER_CERT_PATH` and only verifies the daemon if
+/// `DOCKER_TLS_VERIFY` is set, hence both are set while the client is created and restored
+/// afterwards, unless they already point at the certificates.
+#[cfg(feature = "tls")]
+fn with_tls_env<F: FnOnce() -> Docker>(cert_path: &Path, docker: F) -> Docker {
+    for file in &["ca.pem", "cert.pem", "key.pem"] {
+        let file = cert_path.join(file);
+        assert!(
+            file.is_file(),
+            "TLS certificate {} not found",
+            file.display()
+        );
+    }
+
+    let _guard = env::lock();
+    let previous =
+        ["DOCKER_CERT_PATH", "DOCKER_TLS_VERIFY"].map(|name| (name, std::env::var_os(name)));
+
+    if previous[0].1.as_deref() == Some(cert_path.as_os_str()) && previous[1].1.is_some() {
+        return docker();
+    }
+
+    std::env::set_var("DOCKER_CERT_PATH", cert_path);
+    std::env::set_var("DOCKER_TLS_VERIFY", "1");
+    let docker = docker();
+
+    for (name, value) in previous {
+        match value {
+            Some(value) => std::env::set_var(name, value),
+            None => std::env::remove_var(name),
+        }
+    }
+
+    docker
+}
+
+/// Without the `tls` feature, shiplift is built without TLS support.
+#[cfg(not(feature = "tls"))]
+fn with_tls_env<F: FnOnce() -> Docker>(_: &Path, docker: F) -> Docker {
+    docker()
+}
+
+/// A socket of a daemon that is connected to if the default Docker socket does not exist.
+#[cfg(unix)]
+#[derive(Debug, PartialEq)]
+enum FallbackSocket {
+    RootlessDocker(String),
+    Podman(String),
+}
+
+#[cfg(unix)]
+impl FallbackSocket {
+    fn path(&self) -> &str {
+        match self {
+            FallbackSocket::RootlessDocker(path) | FallbackSocket::Podman(path) => path,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl fmt::Display for FallbackSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FallbackSocket::RootlessDocker(path) => write!(f, "rootless Docker at {}", path),
+            FallbackSocket::Podman(path) => write!(f, "Podman at {}", path),
+        }
+    }
+}
+
+/// Resolves the socket of a rootless Docker daemon or the Podman service if the default Docker
+/// socket does not exist and `DOCKER_HOST` is not set.
+///
+/// Rootless Docker listens on `$XDG_RUNTIME_DIR/docker.sock`, rootless Podman on
+/// `$XDG_RUNTIME_DIR/podman/podman.sock` and rootful Podman on `/run/podman/podman.sock`.
+#[cfg(unix)]
+fn fallback_socket<E, F>(socket_exists: F) -> Option<FallbackSocket>
+where
+    E: GetEnvValue,
+    F: Fn(&Path) -> bool,
+{
+    if E::get_env_value("DOCKER_HOST").is_some() || socket_exists(Path::new(DOCKER_SOCKET)) {
+        return None;
+    }
+
+    let runtime_dir = E::get_env_value("XDG_RUNTIME_DIR");
+    let rootless_docker = runtime_dir
+        .as_ref()
+        .map(|runtime_dir| FallbackSocket::RootlessDocker(format!("{}/docker.sock", runtime_dir)));
+    let rootless_podman = runtime_dir
+        .as_ref()
+        .map(|runtime_dir| FallbackSocket::Podman(format!("{}/podman/podman.sock", runtime_dir)));
+
+    rootless_docker
+        .into_iter()
+        .chain(rootless_podman)
+        .chain(Some(FallbackSocket::Podman(
+            PODMAN_ROOTFUL_SOCKET.to_owned(),
+        )))
+        .find(|socket| socket_exists(Path::new(socket.path())))
+}
+
+fn network_labels() -> HashMap<String, String> {
+    let mut labels = HashMap::new();
+    labels.insert(NETWORK_LABEL.0.to_owned(), NETWORK_LABEL.1.to_owned());
+
+    labels
+}
+
+async fn network_exists(client: &Docker, network: &str) -> bool {
+    // There's no public builder for NetworkListOptions yet
+    // might need to add one in shiplift
+    // this is doing unnecessary stuff
+    let network_list_optons = NetworkListOptions::default();
+    let networks = client.networks().list(&network_list_optons).await.unwrap();
+
+    networks.iter().any(|i| i.name == network)
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        match self.command {
+            env::Command::Remove => {
+                let guard = self.created_networks.read().expect("failed to lock RwLock");
+                for network in guard.iter() {
+                    block_on(async {
+                        self.shiplift
+                            .networks()
+                            .get(network)
+                            .delete()
+                            .await
+                            .unwrap()
+                    });
+                }
+            }
+            env::Command::Keep => {}
+        }
+    }
+}
+
+#[async_trait]
+impl DockerAsync for Http {
+    fn stdout_logs<'s>(&'s self, id: &str) -> LogStreamAsync<'s> {
+        self.logs(
+            id.to_owned(),
+            LogsOptions::builder().stdout(true).stderr(false).build(),
+        )
+    }
+
+    fn stderr_logs<'s>(&'s self, id: &str) -> LogStreamAsync<'s> {
+        self.logs(
+            id.to_owned(),
+            LogsOptions::builder().stdout(false).stderr(true).build(),
+        )
+    }
+
+    #[cfg(feature = "chrono")]
+    fn logs_since<'s>(
+        &'s self,
+        id: &str,
+        source: LogSource,
+        position: LogPosition,
+    ) -> LogStreamAsync<'s> {
+        self.logs(
+            id.to_owned(),
+            LogsOptions::builder()
+                .stdout(source == LogSource::StdOut)
+                .stderr(source == LogSource::StdErr)
+                .follow(false)
+                .timestamps(true)
+                .since(&position.timestamp())
+                .build(),
+        )
+    }
+
+    async fn ports(&self, id: &str) -> Ports {
+        self.inspect(id)
+            .await
+            .network_settings
+            .ports
+            .map(Ports::new)
+            .unwrap_or_default()
+    }
+
+    async fn inspect(&self, id: &str) -> ContainerDetails {
+        self.inner
+            .shiplift
+            .containers()
+            .get(id)
+            .inspect()
+            .await
+            .unwrap()
+    }
+
+    async fn rm(&self, id: &str) {
+        self.inner
+            .shiplift
+            .containers()
+            .get(id)
+            .remove(
+                RmContainerOptions::builder()
+                    .volumes(true)
+                    .force(true)
+                    .build(),
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn stop(&self, id: &str) {
+        self.inner
+            .shiplift
+            .containers()
+            .get(id)
+            .stop(Option::None)
+            .await
+            .unwrap();
+    }
+
+    async fn kill(&self, id: &str) {
+        self.inner
+            .shiplift
+            .containers()
+            .get(id)
+            .kill(Option::None)
+            .await
+            .unwrap();
+    }
+
+    async fn start(&self, id: &str) {
+        self.inner
+            .shiplift
+            .containers()
+            .get(id)
+            .start()
+            .await
+            .unwrap();
+    }
+
+    async fn pause(&self, id: &str) {
+        self.inner
+            .shiplift
+            .containers()
+            .get(id)
+            .pause()
+            .await
+            .unwrap_or_else(|e| panic!("failed to pause docker container {}: {}", id, e));
+    }
+
+    async fn unpause(&self, id: &str) {
+        self.inner
+            .shiplift
+            .containers()
+            .get(id)
+            .unpause()
+            .await
+            .unwrap_or_else(|e| panic!("failed to unpause docker container {}: {}", id, e));
+    }
+
+    async fn exec(&self, id: &str, cmd: ExecCommand) -> ExecOutput {
+        let options = ExecContainerOptions::builder()
+            .cmd(cmd.cmd.iter().map(String::as_str).collect())
+            .attach_stdout(true)
+            .attach_stderr(true)
+            .build();
+        let exec = Exec::create(&self.inner.shiplift, id, &options)
+            .await
+            .unwrap();
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let mut chunks = exec.start();
+
+        while let Some(chunk) = chunks.next().await {
+            match chunk.unwrap() {
+                TtyChunk::StdOut(bytes) => stdout.extend(bytes),
+                TtyChunk::StdErr(bytes) => stderr.extend(bytes),
+                TtyChunk::StdIn(_) => {}
+            }
+        }
+
+        let details = exec.inspect().await.unwrap();
+
+        ExecOutput {
+            // the exit code is only absent while the command is still running
+            exit_code: details.exit_code.map(|code| code as i64).unwrap_or(-1),
+            stdout: String::from_utf8_lossy(&stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        }
+    }
+
+    async fn write_logs(&self, id: &str, mut file: File) -> io::Result<()> {
+        let options = LogsOptions::builder().stdout(true).stderr(true).build();
+        let container = self.inner.shiplift.containers().get(id);
+        let mut chunks = container.logs(&options);
+
+        while let Some(chunk) = chunks.next().await {
+            let chunk = chunk.map_err(io::Error::other)?;
+
+            file.write_all(&Vec::from(chunk))?;
+        }
+
+        Ok(())
+    }
+
+    async fn copy_file_from(&self, id: &str, path: &str) -> io::Result<Vec<u8>> {
+        let archive = self
+            .inner
+            .shiplift
+            .containers()
+            .get(id)
+            .copy_from(Path::new(path))
+            .try_concat()
+            .await
+            .map_err(io::Error::other)?;
+
+        file_from_tar_archive(&archive)
+    }
+
+    fn exec_streaming<'s>(
+        &'s self,
+        id: &str,
+        cmd: ExecCommand,
+        handle: ExecHandle,
+    ) -> BoxStream<'s, LogChunk> {
+        let docker = &self.inner.shiplift;
+        let id = id.to_owned();
+
+        async move {
+            let options = ExecContainerOptions::builder()
+                .cmd(cmd.cmd.iter().map(String::as_str).collect())
+                .attach_stdout(true)
+                .attach_stderr(true)
+                .build();
+            let exec = Exec::create(docker, &id, &options)
+                .await
+                .unwrap_or_else(|e| panic!("failed to execute {:?} in {}: {}", cmd.cmd, id, e));
+
+            let command = format!("{:?} in {}", cmd.cmd, id);
+
+            // the daemon multiplexes both streams, shiplift splits them up again
+            let output = exec.start().filter_map(move |chunk| {
+                let chunk = chunk
+                    .unwrap_or_else(|e| panic!("failed to read the output of {}: {}", command, e));
+
+                future::ready(match chunk {
+                    TtyChunk::StdOut(bytes) => Some(LogChunk::StdOut(bytes)),
+                    TtyChunk::StdErr(bytes) => Some(LogChunk::StdErr(bytes)),
+                    TtyChunk::StdIn(_) => None,
+                })
+            });
+            let exit = stream::once(async move {
+                let details = exec.inspect().await.unwrap_or_else(|e| {
+                    panic!(
+                        "failed to inspect the exec of {:?} in {}: {}",
+                        cmd.cmd, id, e
+                    )
+                });
+                handle.set_exit_code(details.exit_code.map(|code| code as i64).unwrap_or(-1));
+
+                None
+            })
+            .filter_map(future::ready);
+
+            output.chain(exit)
+        }
+        .flatten_stream()
+        .boxed()
+    }
+
+    async fn health(&self, id: &str) -> Option<Health> {
+        let details = raw_request(
+            &self.inner.endpoint,
+            Method::GET,
+            &format!("/containers/{}/json", id),
+            None,
+        )
+        .await
+        .unwrap_or_else(|e| panic!("failed to inspect container {}: {}", id, e));
+        let details = serde_json::from_slice::<serde_json::Value>(&details)
+            .unwrap_or_else(|e| panic!("failed to parse details of container {}: {}", id, e));
+
+        parse_health(&details["State"])
+    }
+
+    async fn recent_logs(&self, id: &str, lines: usize) -> String {
+        self.logs_tail(id, &lines.to_string()).await
+    }
+
+    async fn all_logs(&self, id: &str) -> String {
+        self.logs_tail(id, "all").await
+    }
+
+    /// shiplift cannot attach to the stdin of an exec, hence the exec is started over a
+    /// connection that the daemon hijacks, which carries the input to the command and its output
+    /// back.
+    async fn exec_with_stdin(
+        &self,
+        id: &str,
+        cmd: ExecCommand,
+        input: &mut (dyn AsyncRead + Unpin + Send),
+    ) -> ExecOutput {
+        let endpoint = &self.inner.endpoint;
+        let body = serde_json::json!({
+            "AttachStdin": true,
+            "AttachStdout": true,
+            "AttachStderr": true,
+            "Cmd": cmd.cmd,
+        });
+        let created = raw_request(
+            endpoint,
+            Method::POST,
+            &format!("/containers/{}/exec", id),
+            Some(body.to_string()),
+        )
+        .await
+        .unwrap_or_else(|e| panic!("failed to execute {:?} in {}: {}", cmd.cmd, id, e));
+        let exec_id = serde_json::from_slice::<serde_json::Value>(&created)
+            .ok()
+            .and_then(|created| created["Id"].as_str().map(str::to_owned))
+            .unwrap_or_else(|| panic!("daemon did not return the id of the exec in {}", id));
+
+        let output = attach_exec(endpoint, &exec_id, input)
+            .await
+            .unwrap_or_else(|e| panic!("failed to attach to {:?} in {}: {}", cmd.cmd, id, e));
+        let (stdout, stderr) = demultiplex(&output);
+
+        let details = raw_request(
+            endpoint,
+            Method::GET,
+            &format!("/exec/{}/json", exec_id),
+            None,
+        )
+        .await
+        .unwrap_or_else(|e| panic!("failed to inspect the exec of {:?}: {}", cmd.cmd, e));
+        let details = serde_json::from_slice::<serde_json::Value>(&details)
+            .unwrap_or_else(|e| panic!("failed to parse the exec of {:?}: {}", cmd.cmd, e));
+
+        ExecOutput {
+            // the exit code is only absent while the command is still running
+            exit_code: details["ExitCode"].as_i64().unwrap_or(-1),
+            stdout: String::from_utf8_lossy(&stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        }
+    }
+}
+
+/// Starts the exec over a connection that the daemon hijacks, writes the input to the stdin of the
+/// command while reading its output and returns the output once the command closed it.
+///
+/// # Panics
+///
+/// This function panics if `input` cannot be read.
+async fn attach_exec(
+    endpoint: &Endpoint,
+    id: &str,
+    input: &mut (dyn AsyncRead + Unpin + Send),
+) -> io::Result<Vec<u8>> {
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    let path = format!("/exec/{}/start", id);
+    let mut request = endpoint
+        .transport
+        .request(
+            Method::POST,
+            &path,
+            Some(r#"{"Detach":false,"Tty":false}"#.to_owned()),
+        )
+        .map_err(io::Error::other)?;
+    request
+        .headers_mut()
+        .insert(CONNECTION, HeaderValue::from_static("Upgrade"));
+    request
+        .headers_mut()
+        .insert(UPGRADE, HeaderValue::from_static("tcp"));
+
+    let response = endpoint
+        .transport
+        .send(request)
+        .await
+        .map_err(io::Error::other)?;
+    if response.status() != StatusCode::SWITCHING_PROTOCOLS {
+        return Err(io::Error::other(format!(
+            "{} for {}",
+            response.status(),
+            path
+        )));
+    }
+
+    let connection = hyper::upgrade::on(response)
+        .await
+        .map_err(io::Error::other)?;
+    let (mut output, mut stdin) = tokio::io::split(connection);
+
+    let write = async {
+        let mut buffer = [0; 8192];
+
+        loop {
+            let length = input
+                .read(&mut buffer)
+                .await
+                .unwrap_or_else(|e| panic!("failed to read stdin of exec {}: {}", id, e));
+            if length == 0 {
+                break;
+            }
+
+            stdin.write_all(&buffer[..length]).await?;
+        }
+
+        // the command only sees the end of its stdin once the connection is closed for writing
+        stdin.shutdown().await
+    };
+    let read = async {
+        let mut bytes = Vec::new();
+        output.read_to_end(&mut bytes).await.map(|_| bytes)
+    };
+
+    let (written, read) = future::join(write, read).await;
+    // the command may exit without reading all of its input
+    if let Err(e) = written {
+        log::debug!("failed to write stdin of exec {}: {}", id, e);
+    }
+
+    read
+}
+
+/// Splits the output of an exec without a TTY into stdout and stderr. The daemon multiplexes both
+/// into frames with a header of the stream type and the big-endian length of the payload.
+fn demultiplex(mut output: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+
+    while output.len() >= 8 {
+        let length = u32::from_be_bytes([output[4], output[5], output[6], output[7]]) as usize;
+        let end = output.len().min(8 + length);
+
+        match output[0] {
+            1 => stdout.extend_from_slice(&output[8..end]),
+            2 => stderr.extend_from_slice(&output[8..end]),
+            _ => {}
+        }
+
+        output = &output[end..];
+    }
+
+    (stdout, stderr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::{AccessMode, BindMount, Gpus, HealthCheck, Isolation, Propagation, WaitFor},
+        images::{eventstore::EventStoreDb, generic::GenericImage, hello_world::HelloWorld},
+    };
+    use shiplift::rep::ContainerDetails;
+    use spectral::prelude::*;
+    use std::{
+        io::Read,
+        time::{Duration, Instant},
+    };
+
+    async fn inspect(client: &shiplift::Docker, id: &str) -> ContainerDetails {
+        client.containers().get(id).inspect().await.unwrap()
+    }
+
+    #[cfg(unix)]
+    struct FakeEnvRootless;
+
+    #[cfg(unix)]
+    impl GetEnvValue for FakeEnvRootless {
+        fn get_env_value(key: &str) -> Option<String> {
+            match key {
+                "XDG_RUNTIME_DIR" => Some("/run/user/1000".to_owned()),
+                _ => None,
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    struct FakeEnvDockerHost;
+
+    #[cfg(unix)]
+    impl GetEnvValue for FakeEnvDockerHost {
+        fn get_env_value(key: &str) -> Option<String> {
+            match key {
+                "DOCKER_HOST" => Some("tcp://localhost:2375".to_owned()),
+                "XDG_RUNTIME_DIR" => Some("/run/user/1000".to_owned()),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn should_resolve_rootless_docker_socket_if_docker_socket_is_absent() {
+        let socket = fallback_socket::<FakeEnvRootless, _>(|path| {
+            path == Path::new("/run/user/1000/docker.sock")
+                || path == Path::new("/run/user/1000/podman/podman.sock")
+        });
+
+        assert_eq!(
+            socket,
+            Some(FallbackSocket::RootlessDocker(
+                "/run/user/1000/docker.sock".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn should_resolve_rootless_podman_socket_if_docker_socket_is_absent() {
+        let socket = fallback_socket::<FakeEnvRootless, _>(|path| {
+            path == Path::new("/run/user/1000/podman/podman.sock")
+        });
+
+        assert_eq!(
+            socket,
+            Some(FallbackSocket::Podman(
+                "/run/user/1000/podman/podman.sock".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn should_resolve_rootful_podman_socket_if_rootless_socket_is_absent() {
+        let socket = fallback_socket::<FakeEnvRootless, _>(|path| {
+            path == Path::new("/run/podman/podman.sock")
+        });
+
+        assert_eq!(
+            socket,
+            Some(FallbackSocket::Podman("/run/podman/podman.sock".to_owned()))
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn should_prefer_docker_socket_over_fallback_sockets() {
+        let socket = fallback_socket::<FakeEnvRootless, _>(|_| true);
+
+        assert_eq!(socket, None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn should_not_resolve_fallback_socket_if_docker_host_is_set() {
+        let socket =
+            fallback_socket::<FakeEnvDockerHost, _>(|path| path != Path::new(DOCKER_SOCKET));
+
+        assert_eq!(socket, None);
+    }
+
+    /// Answers the first request to a unix socket with the given response and returns the socket
+    /// together with the request it received.
+    #[cfg(unix)]
+    fn serve_once(
+        name: &str,
+        response: &'static [u8],
+    ) -> (PathBuf, std::thread::JoinHandle<String>) {
+        let socket = std::env::temp_dir().join(format!(
+            "testcontainers-{}-{}.sock",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&socket);
+        let listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let length = stream.read(&mut request).unwrap();
+            stream.write_all(response).unwrap();
+
+            String::from_utf8_lossy(&request[..length]).into_owned()
+        });
+
+        (socket, server)
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn raw_request_should_return_body_of_response_over_unix_socket() {
+        let (socket, server) = serve_once(
+            "body",
+            b"HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n{\"Id\":\"abc\"}",
+        );
+
+        let endpoint = Endpoint::new(format!("unix://{}", socket.display()), None, None);
+        let body = raw_request(&endpoint, Method::GET, "/containers/abc/json", None)
+            .await
+            .unwrap();
+
+        assert_eq!(body, br#"{"Id":"abc"}"#);
+        assert!(server
+            .join()
+            .unwrap()
+            .starts_with("GET /containers/abc/json HTTP/1.1\r\n"));
+        let _ = std::fs::remove_file(&socket);
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn raw_request_should_return_status_and_message_of_failed_response() {
+        let (socket, server) = serve_once(
+            "conflict",
+            b"HTTP/1.1 409 Conflict\r\nContent-Length: 35\r\n\r\n{\"message\":\"name is already in use\"}",
+        );
+
+        let endpoint = Endpoint::new(format!("unix://{}", socket.display()), None, None);
+        let error = raw_request(
+            &endpoint,
+            Method::POST,
+            "/containers/create?name=web",
+            Some("{}".to_owned()),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error.status, Some(409));
+        assert_that!(error.message).contains("is already in use");
+        assert_that!(server.join().unwrap()).ends_with("\r\n\r\n{}");
+        let _ = std::fs::remove_file(&socket);
+    }
+
+    #[test]
+    fn demultiplex_should_split_frames_into_stdout_and_stderr() {
+        let output = [
+            &[1, 0, 0, 0, 0, 0, 0, 3][..],
+            b"foo",
+            &[2, 0, 0, 0, 0, 0, 0, 5],
+            b"error",
+            &[1, 0, 0, 0, 0, 0, 0, 3],
+            b"bar",
+        ]
+        .concat();
+
+        let (stdout, stderr) = demultiplex(&output);
+
+        assert_eq!(stdout, b"foobar");
+        assert_eq!(stderr, b"error");
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn attach_exec_should_stream_input_over_hijacked_connection() {
+        let socket =
+            std::env::temp_dir().join(format!("testcontainers-hijack-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket);
+        let listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+
+        // echoes the stdin of the exec on its stdout once the client closed it
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"}") {
+                let length = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..length]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 101 UPGRADED\r\nConnection: Upgrade\r\nUpgrade: tcp\r\n\r\n")
+                .unwrap();
+
+            let mut stdin = Vec::new();
+            stream.read_to_end(&mut stdin).unwrap();
+            stream
+                .write_all(&[1, 0, 0, 0, 0, 0, 0, stdin.len() as u8])
+                .unwrap();
+            stream.write_all(&stdin).unwrap();
+
+            String::from_utf8_lossy(&request).into_owned()
+        });
+
+        let endpoint = Endpoint::new(format!("unix://{}", socket.display()), None, None);
+        let output = attach_exec(&endpoint, "abc", &mut &b"SELECT 1;"[..])
+            .await
+            .unwrap();
+
+        assert_eq!(demultiplex(&output), (b"SELECT 1;".to_vec(), Vec::new()));
+        let request = server.join().unwrap().to_lowercase();
+        assert_that!(request).starts_with("post /exec/abc/start http/1.1\r\n");
+        assert_that!(request).contains("upgrade: tcp\r\n");
+        let _ = std::fs::remove_file(&socket);
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn raw_request_should_time_out_if_daemon_does_not_respond() {
+        let socket =
+            std::env::temp_dir().join(format!("testcontainers-silent-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket);
+        // connections are queued by the listener but never answered
+        let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+
+        let endpoint = Endpoint::new(
+            format!("unix://{}", socket.display()),
+            None,
+            Some(Duration::from_millis(100)),
+        );
+        let error = raw_request(&endpoint, Method::GET, "/version", None)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.message, "no response to /version within 100ms");
+        let _ = std::fs::remove_file(&socket);
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn ping_should_time_out_if_daemon_does_not_respond() {
+        let socket = std::env::temp_dir().join(format!(
+            "testcontainers-silent-ping-{}.sock",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&socket);
+        let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+
+        let docker = HttpBuilder::default()
+            .with_endpoint(format!("unix://{}", socket.display()))
+            .with_request_timeout(Duration::from_millis(100))
+            .build();
+        let error = docker.ping().await.unwrap_err();
+
+        assert_that!(error.to_string()).contains("no response within 100ms");
+        let _ = std::fs::remove_file(&socket);
+    }
+
+    #[test]
+    fn create_body_should_contain_fields_set_by_modifier() {
+        let run_args = RunArgs::default().with_create_modifier(|body| {
+            body["HostConfig"]["ShmSize"] = 1_073_741_824.into();
+        });
+        let mut options_builder = ContainerOptions::builder("hello-world");
+        options_builder.env(vec!["GREETING=hello"]);
+
+        let body = create_body(
+            &options_builder.build(),
+            &GenericImage::new("hello-world"),
+            &run_args,
+        );
+        let body = serde_json::from_str::<serde_json::Value>(&body).unwrap();
+
+        assert_eq!(body["Image"], "hello-world");
+        assert_eq!(body["Env"], serde_json::json!(["GREETING=hello"]));
+        assert_eq!(body["HostConfig"]["ShmSize"], 1_073_741_824);
+    }
+
+    #[test]
+    fn create_body_should_contain_options_shiplift_cannot_set() {
+        let image = GenericImage::new("hello-world");
+        let run_args = RunArgs::default()
+            .with_memory(64 * 1024 * 1024)
+            .with_oom_kill_disabled()
+            .with_oom_score_adj(-500)
+            .with_gpus(Gpus::Count(2))
+            .with_isolation(Isolation::Process)
+            .with_security_opt("apparmor=unconfined")
+            .with_storage_opt("size", "10G")
+            .with_annotation("org.example.team", "payments")
+            .with_log_driver("json-file", vec![("max-size", "10m")])
+            .with_health_check(HealthCheck::new("true").with_interval(Duration::from_secs(1)));
+
+        let body = create_body(&container_options(&image, &run_args), &image, &run_args);
+        let body = serde_json::from_str::<serde_json::Value>(&body).unwrap();
+        let host_config = &body["HostConfig"];
+
+        assert_eq!(host_config["Memory"], 64 * 1024 * 1024);
+        assert_eq!(host_config["OomKillDisable"], true);
+        assert_eq!(host_config["OomScoreAdj"], -500);
+        assert_eq!(
+            host_config["DeviceRequests"],
+            serde_json::json!([{ "Driver": "", "Count": 2, "Capabilities": [["gpu"]] }])
+        );
+        assert_eq!(host_config["Isolation"], "process");
+        assert_eq!(
+            host_config["SecurityOpt"],
+            serde_json::json!(["apparmor=unconfined"])
+        );
+        assert_eq!(
+            host_config["StorageOpt"],
+            serde_json::json!({ "size": "10G" })
+        );
+        assert_eq!(
+            host_config["Annotations"],
+            serde_json::json!({ "org.example.team": "payments" })
+        );
+        assert_eq!(
+            host_config["LogConfig"],
+            serde_json::json!({ "Type": "json-file", "Config": { "max-size": "10m" } })
+        );
+        assert_eq!(
+            body["Healthcheck"],
+            serde_json::json!({ "Test": ["CMD-SHELL", "true"], "Interval": 1_000_000_000u64 })
+        );
+    }
+
+    #[test]
+    fn create_path_should_encode_name() {
+        assert_eq!(create_path(None), "/containers/create");
+        assert_eq!(
+            create_path(Some("web&x=1")),
+            "/containers/create?name=web%26x%3D1"
+        );
+    }
+
+    #[test]
+    fn builder_should_default_to_pulling_missing_images_without_retries() {
+        let docker = HttpBuilder::default()
+            .with_endpoint("unix:///run/user/1000/docker.sock")
+            .build();
+
+        assert_eq!(
+            docker.inner.endpoint.address,
+            "unix:///run/user/1000/docker.sock"
+        );
+        assert_eq!(docker.inner.endpoint.request_timeout, None);
+        assert_eq!(docker.inner.pull_policy, PullPolicy::Missing);
+        assert_eq!(docker.inner.registry_auth, None);
+        assert_eq!(docker.inner.pull_retries, 0);
+    }
+
+    #[test]
+    fn builder_should_configure_pulling_of_images() {
+        let docker = HttpBuilder::default()
+            .with_endpoint("tcp://localhost:2375")
+            .with_pull_policy(PullPolicy::Always)
+            .with_registry_auth("alice", "secret")
+            .with_pull_retries(3)
+            .build();
+
+        assert_eq!(docker.inner.endpoint.address, "tcp://localhost:2375");
+        assert_eq!(docker.inner.pull_policy, PullPolicy::Always);
+        assert_eq!(
+            docker.inner.registry_auth,
+            Some(("alice".to_owned(), "secret".to_owned()))
+        );
+        assert_eq!(docker.inner.pull_retries, 3);
+    }
+
+    #[test]
+    fn builder_should_configure_request_timeout() {
+        let docker = HttpBuilder::default()
+            .with_endpoint("tcp://localhost:2375")
+            .with_request_timeout(Duration::from_secs(30))
+            .build();
 
-    networks.iter().any(|i| i.name == network)
-}
+        assert_eq!(
+            docker.inner.endpoint.request_timeout,
+            Some(Duration::from_secs(30))
+        );
+    }
 
-impl Drop for Client {
-    fn drop(&mut self) {
-        match self.command {
-            env::Command::Remove => {
-                let guard = self.created_networks.read().expect("failed to lock RwLock");
-                for network in guard.iter() {
-                    block_on(async {
-                        self.shiplift
-                            .networks()
-                            .get(network)
-                            .delete()
-                            .await
-                            .unwrap()
-                    });
-                }
-            }
-            env::Command::Keep => {}
-        }
+    #[test]
+    #[cfg(feature = "tls")]
+    fn builder_should_only_apply_certificates_to_tcp_endpoints() {
+        let docker = HttpBuilder::default()
+            .with_endpoint("unix:///var/run/docker.sock")
+            .with_tls("/nonexistent/certs")
+            .build();
+
+        assert!(matches!(
+            docker.inner.endpoint.transport,
+            Transport::Unix { .. }
+        ));
     }
-}
 
-#[async_trait]
-impl DockerAsync for Http {
-    fn stdout_logs<'s>(&'s self, id: &str) -> LogStreamAsync<'s> {
-        self.logs(
-            id.to_owned(),
-            LogsOptions::builder().stdout(true).stderr(false).build(),
-        )
+    #[test]
+    #[cfg(feature = "tls")]
+    #[should_panic(expected = "TLS certificate /nonexistent/certs/ca.pem not found")]
+    fn builder_should_reject_missing_certificates() {
+        HttpBuilder::default()
+            .with_endpoint("tcp://localhost:2376")
+            .with_tls("/nonexistent/certs")
+            .build();
     }
 
-    fn stderr_logs<'s>(&'s self, id: &str) -> LogStreamAsync<'s> {
-        self.logs(
-            id.to_owned(),
-            LogsOptions::builder().stdout(false).stderr(true).build(),
-        )
+    #[test]
+    #[should_panic(expected = "invalid docker endpoint")]
+    fn builder_should_reject_invalid_endpoint() {
+        HttpBuilder::default().with_endpoint("not a url").build();
     }
 
-    async fn ports(&self, id: &str) -> Ports {
-        self.inspect(id)
-            .await
-            .network_settings
-            .ports
-            .map(Ports::new)
-            .unwrap_or_default()
+    #[tokio::test(flavor = "multi_thread")]
+    #[should_panic(
+        expected = "is not available locally and the pull policy does not allow to pull it"
+    )]
+    async fn http_run_should_not_pull_image_if_pull_policy_is_never() {
+        let docker = HttpBuilder::default()
+            .with_pull_policy(PullPolicy::Never)
+            .build();
+
+        docker
+            .run(GenericImage::new("testcontainers/never-pulled:latest"))
+            .await;
     }
 
-    async fn inspect(&self, id: &str) -> ContainerDetails {
-        self.inner
-            .shiplift
-            .containers()
-            .get(id)
-            .inspect()
-            .await
-            .unwrap()
+    #[tokio::test]
+    async fn ping_should_describe_unreachable_daemon() {
+        let docker = HttpBuilder::default()
+            .with_endpoint("unix:///nonexistent/docker.sock")
+            .build();
+
+        let error = docker.ping().await.unwrap_err();
+
+        assert_eq!(error.endpoint(), "unix:///nonexistent/docker.sock");
+        assert_that!(error.to_string())
+            .contains("failed to reach the docker daemon at unix:///nonexistent/docker.sock");
+        assert_that!(error.to_string()).contains("DOCKER_HOST");
     }
 
-    async fn rm(&self, id: &str) {
-        self.inner
-            .shiplift
-            .containers()
-            .get(id)
-            .remove(
-                RmContainerOptions::builder()
-                    .volumes(true)
-                    .force(true)
-                    .build(),
+    #[tokio::test(flavor = "multi_thread")]
+    async fn http_run_command_should_include_bind_mounts() {
+        let docker = Http::new();
+        let source = std::env::temp_dir();
+        let container = docker
+            .run_with_args(
+                GenericImage::new("alpine:3.13")
+                    .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+                RunArgs::default().with_bind_mount(
+                    BindMount::new(source.to_string_lossy(), "/data")
+                        .with_access_mode(AccessMode::ReadOnly)
+                        .with_propagation(Propagation::Rprivate),
+                ),
             )
-            .await
-            .unwrap();
+            .await;
+
+        let container_details = inspect(&docker.inner.shiplift, container.id()).await;
+        let mount = container_details
+            .mounts
+            .iter()
+            .find(|mount| mount.destination == "/data")
+            .expect("bind mount is missing");
+
+        assert!(!mount.rw);
+        assert!(mount.mode.contains("rprivate"), "mode is {}", mount.mode);
     }
 
-    async fn stop(&self, id: &str) {
-        self.inner
-            .shiplift
-            .containers()
-            .get(id)
-            .stop(Option::None)
-            .await
-            .unwrap();
+    #[tokio::test(flavor = "multi_thread")]
+    async fn http_exec_with_stdin_should_pass_input_to_command() {
+        let docker = Http::new();
+        let container = docker
+            .run(
+                GenericImage::new("alpine:3.13")
+                    .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            )
+            .await;
+
+        let output = container
+            .exec_with_stdin(
+                ExecCommand::new(vec!["wc", "-l"]),
+                &b"one\ntwo\nthree\n"[..],
+            )
+            .await;
+
+        assert!(output.success(), "{}", output.stderr);
+        assert_eq!(output.stdout.trim(), "3");
     }
 
-    async fn start(&self, id: &str) {
-        self.inner
-            .shiplift
-            .containers()
-            .get(id)
-            .start()
-            .await
-            .unwrap();
+    #[tokio::test(flavor = "multi_thread")]
+    async fn http_exec_streaming_should_stream_output_while_command_runs() {
+        let docker = Http::new();
+        let container = docker
+            .run(
+                GenericImage::new("alpine:3.13")
+                    .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            )
+            .await;
+
+        let before_exec = Instant::now();
+        let (output, handle) = container.exec_streaming(ExecCommand::shell(
+            "for i in 1 2 3; do echo line $i; sleep 1; done; echo done >&2; exit 3",
+        ));
+        futures::pin_mut!(output);
+
+        assert_eq!(
+            output.next().await,
+            Some(LogChunk::StdOut(b"line 1\n".to_vec()))
+        );
+        assert_that!(before_exec.elapsed()).is_less_than(Duration::from_secs(2));
+        assert_eq!(handle.exit_code(), None);
+
+        let rest = output.collect::<Vec<_>>().await;
+        assert_eq!(
+            rest,
+            vec![
+                LogChunk::StdOut(b"line 2\n".to_vec()),
+                LogChunk::StdOut(b"line 3\n".to_vec()),
+                LogChunk::StdErr(b"done\n".to_vec()),
+            ]
+        );
+        assert_eq!(handle.exit_code(), Some(3));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::images::{generic::GenericImage, hello_world::HelloWorld};
-    use shiplift::rep::ContainerDetails;
-    use spectral::prelude::*;
+    #[tokio::test(flavor = "multi_thread")]
+    async fn http_kill_should_not_wait_for_graceful_shutdown() {
+        let docker = Http::new();
+        // `sleep` runs as PID 1 and therefore ignores `SIGTERM`, which makes `stop` time out
+        let container = docker
+            .run(
+                GenericImage::new("alpine:3.13")
+                    .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            )
+            .await;
 
-    async fn inspect(client: &shiplift::Docker, id: &str) -> ContainerDetails {
-        client.containers().get(id).inspect().await.unwrap()
+        let before_kill = Instant::now();
+        container.kill().await;
+
+        assert_that!(before_kill.elapsed()).is_less_than(Duration::from_secs(5));
+        assert_that!(
+            inspect(&docker.inner.shiplift, container.id())
+                .await
+                .state
+                .running
+        )
+        .is_equal_to(false);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn http_run_without_wait_should_skip_ready_conditions() {
+        let docker = Http::new();
+        let image = GenericImage::new("alpine:3.13")
+            .with_args(vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "sleep 3; echo ready; sleep infinity".to_owned(),
+            ])
+            .with_wait_for(WaitFor::message_on_stdout("ready"));
+
+        let before_run = Instant::now();
+        let container = docker.run_without_wait(image, RunArgs::default()).await;
+
+        assert_that!(before_run.elapsed()).is_less_than(Duration::from_secs(3));
+        assert_that!(
+            inspect(&docker.inner.shiplift, container.id())
+                .await
+                .state
+                .running
+        )
+        .is_equal_to(true);
+
+        container
+            .wait_for(WaitFor::message_on_stdout("ready"))
+            .await;
+        assert_that!(before_run.elapsed()).is_greater_than(Duration::from_secs(3));
     }
 
     #[tokio::test(flavor = "multi_thread")]
     async fn http_run_command_should_expose_all_ports_if_no_explicit_mapping_requested() {
-        let image = HelloWorld::default();
+        let image = HelloWorld;
         let docker = Http::new();
         let container = docker.run(image).await;
 
@@ -348,8 +2408,25 @@ mod tests {
         let container_details = inspect(&docker.inner.shiplift, container.id()).await;
 
         let port_bindings = container_details.host_config.port_bindings.unwrap();
-        assert_that!(&port_bindings).contains_key(&"456/tcp".into());
-        assert_that!(&port_bindings).contains_key(&"888/tcp".into());
+        assert_that!(&port_bindings).contains_key("456/tcp".to_owned());
+        assert_that!(&port_bindings).contains_key("888/tcp".to_owned());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn http_run_command_should_apply_create_modifier() {
+        let docker = Http::new();
+        let container = docker
+            .run_with_args(
+                GenericImage::new("hello-world"),
+                RunArgs::default().with_create_modifier(|body| {
+                    body["Hostname"] = "modified".into();
+                }),
+            )
+            .await;
+
+        let container_details = inspect(&docker.inner.shiplift, container.id()).await;
+
+        assert_eq!(container_details.config.hostname, "modified");
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -370,6 +2447,188 @@ mod tests {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn http_inspect_should_reflect_options_shiplift_cannot_set() {
+        let docker = Http::new();
+        let container = docker
+            .run_with_args(
+                GenericImage::new("alpine:3.13")
+                    .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+                RunArgs::default()
+                    .with_security_opt("no-new-privileges")
+                    .with_log_driver("json-file", vec![("max-size", "10m")])
+                    .with_health_check(HealthCheck::new("true").with_retries(5)),
+            )
+            .await;
+
+        let details = raw_request(
+            &docker.inner.endpoint,
+            Method::GET,
+            &format!("/containers/{}/json", container.id()),
+            None,
+        )
+        .await
+        .unwrap();
+        let details = serde_json::from_slice::<serde_json::Value>(&details).unwrap();
+
+        assert_eq!(
+            details["HostConfig"]["SecurityOpt"],
+            serde_json::json!(["no-new-privileges"])
+        );
+        assert_eq!(
+            details["HostConfig"]["LogConfig"]["Config"]["max-size"],
+            "10m"
+        );
+        assert_eq!(
+            details["Config"]["Healthcheck"],
+            serde_json::json!({ "Test": ["CMD-SHELL", "true"], "Retries": 5 })
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn http_inspect_should_reflect_oom_score_adj() {
+        let docker = Http::new();
+        let container = docker
+            .run_with_args(
+                GenericImage::new("alpine:3.13")
+                    .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+                RunArgs::default().with_oom_score_adj(500),
+            )
+            .await;
+
+        let details = raw_request(
+            &docker.inner.endpoint,
+            Method::GET,
+            &format!("/containers/{}/json", container.id()),
+            None,
+        )
+        .await
+        .unwrap();
+        let details = serde_json::from_slice::<serde_json::Value>(&details).unwrap();
+
+        assert_eq!(details["HostConfig"]["OomScoreAdj"], 500);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn http_inspect_should_reflect_annotations_if_supported() {
+        let docker = Http::new();
+        let container = docker
+            .run_with_args(
+                GenericImage::new("alpine:3.13")
+                    .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+                RunArgs::default().with_annotation("org.example.team", "payments"),
+            )
+            .await;
+
+        let details = raw_request(
+            &docker.inner.endpoint,
+            Method::GET,
+            &format!("/containers/{}/json", container.id()),
+            None,
+        )
+        .await
+        .unwrap();
+        let details = serde_json::from_slice::<serde_json::Value>(&details).unwrap();
+        let annotations = &details["HostConfig"]["Annotations"];
+
+        if docker.api_version().await >= Some(ANNOTATIONS_API_VERSION) {
+            assert_eq!(
+                annotations,
+                &serde_json::json!({ "org.example.team": "payments" })
+            );
+        } else {
+            assert!(annotations.get("org.example.team").is_none());
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn http_should_create_internal_network_with_mtu() {
+        let docker = Http::new();
+        let _container = docker
+            .run_with_args(
+                GenericImage::new("alpine:3.13")
+                    .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+                RunArgs::default()
+                    .with_network("http-internal-net")
+                    .with_network_mtu(1400)
+                    .with_internal_network(),
+            )
+            .await;
+
+        let network = docker
+            .inner
+            .shiplift
+            .networks()
+            .get("http-internal-net")
+            .inspect()
+            .await
+            .unwrap();
+
+        assert!(network.internal);
+        assert_eq!(
+            network
+                .options
+                .unwrap_or_default()
+                .get("com.docker.network.driver.mtu")
+                .map(String::as_str),
+            Some("1400")
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn http_run_should_replace_container_that_did_not_become_ready() {
+        let state =
+            std::env::temp_dir().join(format!("testcontainers-http-retry-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&state);
+        std::fs::create_dir_all(&state).unwrap();
+
+        // the first container exits without logging, its replacement finds the marker it left
+        let image = GenericImage::new("alpine:3.13")
+            .with_args(vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "if [ -f /state/failed ]; then echo ready; sleep infinity; else touch /state/failed; fi"
+                    .to_owned(),
+            ])
+            .with_wait_for(WaitFor::message_on_stdout("ready"));
+        let docker = Http::new();
+        let container = docker
+            .run_with_args(
+                image,
+                RunArgs::default()
+                    .with_name(format!("http-retried-{}", std::process::id()))
+                    .with_bind_mount(BindMount::new(state.display().to_string(), "/state"))
+                    .with_start_retries(1),
+            )
+            .await;
+
+        assert!(state.join("failed").exists());
+        assert_eq!(
+            container
+                .startup_report()
+                .phases()
+                .iter()
+                .filter(|(phase, _)| *phase == StartupPhase::Start)
+                .count(),
+            2
+        );
+        let _ = std::fs::remove_dir_all(&state);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[cfg(feature = "chrono")]
+    async fn http_container_should_be_started_after_it_was_created() {
+        let docker = Http::new();
+        let container = docker
+            .run(
+                GenericImage::new("alpine:3.13")
+                    .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            )
+            .await;
+
+        assert!(container.started_at().await >= container.created_at().await);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn http_run_command_should_include_name() {
         let image = GenericImage::new("hello-world");
@@ -392,17 +2651,11 @@ mod tests {
 
             // creating the first container creates the network
             let _container1 = docker
-                .run_with_args(
-                    HelloWorld::default(),
-                    RunArgs::default().with_network("awesome-net-2"),
-                )
+                .run_with_args(HelloWorld, RunArgs::default().with_network("awesome-net-2"))
                 .await;
             // creating a 2nd container doesn't fail because check if the network exists already
             let _container2 = docker
-                .run_with_args(
-                    HelloWorld::default(),
-                    RunArgs::default().with_network("awesome-net-2"),
-                )
+                .run_with_args(HelloWorld, RunArgs::default().with_network("awesome-net-2"))
                 .await;
 
             assert!(network_exists(&client, "awesome-net-2").await);
@@ -411,4 +2664,63 @@ mod tests {
         // client has been dropped, should clean up networks
         assert!(!network_exists(&client, "awesome-net-2").await)
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn http_should_prune_unused_networks_created_by_testcontainers() {
+        let docker = Http::new();
+        let client = &docker.inner.shiplift;
+        client
+            .networks()
+            .create(&shiplift::NetworkCreateOptions::builder("user-net-2").build())
+            .await
+            .unwrap();
+
+        assert!(
+            docker
+                .create_network_if_not_exists("pruned-net-2", &RunArgs::default())
+                .await
+        );
+
+        let pruned = docker.prune_networks().await;
+
+        assert_that!(pruned).contains("pruned-net-2".to_owned());
+        assert_that!(pruned).does_not_contain("user-net-2".to_owned());
+        assert!(!network_exists(client, "pruned-net-2").await);
+        assert!(network_exists(client, "user-net-2").await);
+
+        client.networks().get("user-net-2").delete().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn http_assert_healthy_should_pass_once_health_check_of_image_succeeded() {
+        let docker = Http::new();
+        let container = docker.run(EventStoreDb::default()).await;
+
+        // the image checks its health every 5 seconds
+        let deadline = Instant::now() + Duration::from_secs(60);
+        while docker
+            .health(container.id())
+            .await
+            .is_some_and(|health| health.status == "starting")
+        {
+            assert!(Instant::now() < deadline, "health check did not finish");
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+
+        container.assert_healthy().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[should_panic(expected = "has no health check")]
+    async fn http_assert_healthy_should_panic_if_container_has_no_health_check() {
+        let docker = Http::new();
+        let container = docker
+            .run(
+                GenericImage::new("alpine:3.13")
+                    .with_args(vec!["sleep".to_owned(), "infinity".to_owned()]),
+            )
+            .await;
+
+        container.assert_healthy().await;
+    }
 }
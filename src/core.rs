@@ -1,18 +1,38 @@
-pub(crate) use self::docker::Docker;
+pub(crate) use self::{connect::encode_url_component, docker::Docker, exec::shell_quote};
 pub use self::{
+    connect::ConnectionString,
     container::Container,
     container_async::ContainerAsync,
-    docker::RunArgs,
-    image::{Image, Port, WaitFor},
+    copy::CopyToContainer,
+    docker::{
+        AccessMode, BindMount, Consistency, CreateContainerBody, Gpus, HealthCheck, Isolation,
+        Propagation, RemovalPolicy, RunArgs, DEFAULT_STARTUP_TIMEOUT,
+    },
+    exec::{ExecCommand, ExecHandle, ExecOutput, LogChunk},
+    image::{ContainerState, ContainerStatus, Image, LogSource, Port, WaitFor},
+    ports::Protocol,
+    startup::{StartupPhase, StartupReport},
+    wait::{HttpWaitStrategy, StateWaitStrategy},
 };
 
+#[cfg(feature = "chrono")]
+pub use self::logs::LogPosition;
+
+mod connect;
 mod container;
 mod container_async;
 mod docker;
 pub mod env;
+mod exec;
 mod image;
 
+pub(crate) mod copy;
+pub(crate) mod health;
 pub(crate) mod logs;
 pub(crate) mod ports;
+pub(crate) mod progress;
+pub(crate) mod retry;
+pub(crate) mod startup;
+pub(crate) mod wait;
 
 pub(crate) use container_async::DockerAsync;
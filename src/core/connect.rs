@@ -0,0 +1,69 @@
+use crate::{
+    clients::{Cli, Http},
+    core::{Container, ContainerAsync, Image},
+};
+use async_trait::async_trait;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+/// Characters that need to be encoded in the user info and path of a URL, which are all but the
+/// unreserved ones.
+const URL_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// An image that clients connect to through a connection string, e.g. a database.
+///
+/// This allows starting a container and retrieving its connection string in one call. The
+/// container is removed once the returned handle is dropped, hence it has to be kept alive for as
+/// long as the connection is used:
+///
+/// ```rust,no_run
+/// use testcontainers::{core::ConnectionString, images::redis::Redis, *};
+///
+/// let docker = clients::Cli::default();
+/// let (_container, url) = Redis::default().start_blocking(&docker);
+///
+/// let client = redis::Client::open(url.as_str()).unwrap();
+/// ```
+#[async_trait]
+pub trait ConnectionString: Image + Send + Sync + Sized {
+    /// The port in the container that clients connect to.
+    fn connection_port(&self) -> u16;
+
+    /// Returns the connection string, given the host port mapped to the [`connection_port`].
+    ///
+    /// [`connection_port`]: trait.ConnectionString.html#tymethod.connection_port
+    fn connection_string(&self, host_port: u16) -> String;
+
+    /// Starts a container through the async client and returns it together with its connection
+    /// string once it is ready.
+    async fn start<'d>(self, docker: &'d Http) -> (ContainerAsync<'d, Self>, String)
+    where
+        Self: 'd,
+    {
+        let container = docker.run(self).await;
+        let host_port = container
+            .get_host_port(container.image().connection_port())
+            .await;
+        let connection_string = container.image().connection_string(host_port);
+
+        (container, connection_string)
+    }
+
+    /// Starts a container through the blocking client and returns it together with its connection
+    /// string once it is ready.
+    fn start_blocking(self, docker: &Cli) -> (Container<'_, Self>, String) {
+        let container = docker.run(self);
+        let host_port = container.get_host_port(container.image().connection_port());
+        let connection_string = container.image().connection_string(host_port);
+
+        (container, connection_string)
+    }
+}
+
+/// Percent-encodes a user, password or database name for use in a connection string.
+pub(crate) fn encode_url_component(component: &str) -> String {
+    utf8_percent_encode(component, URL_COMPONENT).to_string()
+}
@@ -1,26 +1,39 @@
 use crate::{
-    core::{docker::Docker, env::Command, image::WaitFor},
+    core::{
+        docker::{Docker, DEFAULT_STARTUP_TIMEOUT},
+        health::{describe_unhealthy, Health, RECENT_LOG_LINES},
+        image::{internal_port_by_name, LogSource, WaitFor},
+        logs::{create_log_file, earliest, WaitError},
+        progress::ReadyProgress,
+        startup::StartupReport,
+        ContainerState, ContainerStatus, ExecCommand, ExecOutput, RemovalPolicy, StateWaitStrategy,
+    },
     Image,
 };
-use std::{fmt, marker::PhantomData, net::IpAddr, str::FromStr};
+use std::{
+    fmt,
+    io::Read,
+    marker::PhantomData,
+    net::IpAddr,
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 /// Represents a running docker container.
 ///
 /// Containers have a [`custom destructor`][drop_impl] that removes them as soon as they go out of scope:
 ///
-/// ```rust
-/// use testcontainers::*;
-/// #[test]
-/// fn a_test() {
-///     let docker = clients::Cli::default();
+/// ```rust,no_run
+/// use testcontainers::{images::hello_world::HelloWorld, *};
 ///
-///     {
-///         let container = docker.run(MyImage::default());
+/// let docker = clients::Cli::default();
 ///
-///         // Docker container is stopped/removed at the end of this scope.
-///     }
-/// }
+/// {
+///     let container = docker.run(HelloWorld::default());
 ///
+///     // Docker container is stopped/removed at the end of this scope.
+/// }
 /// ```
 ///
 /// [drop_impl]: struct.Container.html#impl-Drop
@@ -28,7 +41,16 @@ pub struct Container<'d, I> {
     id: String,
     docker_client: Box<dyn Docker>,
     image: I,
-    command: Command,
+    removal: RemovalPolicy,
+    startup_timeout: Duration,
+    progress_interval: Duration,
+    full_logs_on_timeout: bool,
+    log_file: Option<PathBuf>,
+    startup_report: StartupReport,
+    /// Whether the container was removed to be replaced, see [`remove_for_replacement`].
+    ///
+    /// [`remove_for_replacement`]: struct.Container.html#method.remove_for_replacement
+    removed: bool,
 
     /// Tracks the lifetime of the client to make sure the container is dropped before the client.
     client_lifetime: PhantomData<&'d ()>,
@@ -42,7 +64,7 @@ where
         f.debug_struct("Container")
             .field("id", &self.id)
             .field("image", &self.image)
-            .field("command", &self.command)
+            .field("removal", &self.removal)
             .finish()
     }
 }
@@ -53,26 +75,79 @@ where
 {
     /// Constructs a new container given an id, a docker client and the image.
     ///
-    /// This function will block the current thread (if [`wait_until_ready`] is implemented correctly) until the container is actually ready to be used.
+    /// This does not wait for the container to be ready, see [`wait_until_ready`].
+    /// If no `startup_timeout` is given, the one declared by the image is used, and
+    /// [`DEFAULT_STARTUP_TIMEOUT`] if the image declares none.
     ///
-    /// [`wait_until_ready`]: trait.Image.html#tymethod.wait_until_ready
+    /// [`wait_until_ready`]: struct.Container.html#method.wait_until_ready
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         id: String,
         docker_client: impl Docker + 'static,
         image: I,
-        command: Command,
+        removal: RemovalPolicy,
+        startup_timeout: Option<Duration>,
+        progress_interval: Duration,
+        full_logs_on_timeout: bool,
+        log_file: Option<PathBuf>,
     ) -> Self {
-        let container = Container {
+        let startup_timeout = startup_timeout
+            .or_else(|| image.startup_timeout())
+            .unwrap_or(DEFAULT_STARTUP_TIMEOUT);
+
+        Container {
             id,
             docker_client: Box::new(docker_client),
             image,
-            command,
+            removal,
+            startup_timeout,
+            progress_interval,
+            full_logs_on_timeout,
+            log_file,
+            startup_report: StartupReport::default(),
+            removed: false,
             client_lifetime: PhantomData,
-        };
+        }
+    }
+
+    /// Blocks until the [`ready_conditions`] of the image are met and runs the commands the
+    /// image wants to [`exec_after_start`].
+    ///
+    /// Containers started through [`run`] already did this, which is why this is only needed for
+    /// containers started through [`run_without_wait`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the container does not become ready within the startup timeout.
+    ///
+    /// [`ready_conditions`]: trait.Image.html#tymethod.ready_conditions
+    /// [`exec_after_start`]: trait.Image.html#method.exec_after_start
+    /// [`run`]: ../clients/struct.Cli.html#method.run
+    /// [`run_without_wait`]: ../clients/struct.Cli.html#method.run_without_wait
+    pub fn wait_until_ready(&self) {
+        if let Err(e) = self.try_until_ready() {
+            panic!("container {} did not become ready: {}", self.id, e)
+        }
+        self.exec_after_start();
+    }
 
-        container.block_until_ready();
+    /// Blocks until the given condition is met, e.g. to check the readiness of a container started
+    /// through [`run_without_wait`] step by step.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the condition is not met within the startup timeout.
+    ///
+    /// [`run_without_wait`]: ../clients/struct.Cli.html#method.run_without_wait
+    pub fn wait_for(&self, condition: WaitFor) {
+        let description = condition.to_string();
 
-        container
+        if let Err(e) = self.block_until_ready(vec![condition]) {
+            panic!(
+                "container {} did not meet condition {}: {}",
+                self.id, description, e
+            )
+        }
     }
 
     /// Returns a reference to the [`Image`] of this container.
@@ -86,29 +161,184 @@ where
         &self.image
     }
 
-    fn block_until_ready(&self) {
+    /// Panics unless the health check of this container currently reports it as healthy, e.g. to
+    /// assert that a container is still in a good state after the test.
+    ///
+    /// The health check is configured by the image or through [`RunArgs::with_health_check`].
+    /// This method does not wait while the health check is still starting.
+    ///
+    /// # Panics
+    ///
+    /// This method panics with the output of the last health checks and the recent logs of the
+    /// container if it is not healthy or has no health check.
+    ///
+    /// [`RunArgs::with_health_check`]: struct.RunArgs.html#method.with_health_check
+    pub fn assert_healthy(&self) {
+        let health = self.docker_client.health(&self.id);
+
+        if health.as_ref().is_some_and(Health::is_healthy) {
+            return;
+        }
+
+        let logs = self.docker_client.recent_logs(&self.id, RECENT_LOG_LINES);
+
+        panic!("{}", describe_unhealthy(&self.id, health.as_ref(), &logs))
+    }
+
+    /// Returns the mapped host port for a port that the [`Image`] declares under the given name.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the image does not declare a port with the given name or if the
+    /// port is not mapped.
+    ///
+    /// [`Image`]: trait.Image.html
+    pub fn get_host_port_by_name(&self, name: &str) -> u16 {
+        self.get_host_port(internal_port_by_name(&self.image, name))
+    }
+
+    /// Blocks until the [`ready_conditions`] of the image are met, without running the commands
+    /// the image wants to [`exec_after_start`].
+    ///
+    /// [`ready_conditions`]: trait.Image.html#tymethod.ready_conditions
+    /// [`exec_after_start`]: trait.Image.html#method.exec_after_start
+    pub(crate) fn try_until_ready(&self) -> Result<(), WaitError> {
+        self.block_until_ready(self.image.ready_conditions())
+    }
+
+    /// Blocks until the file at the absolute path exists in the container, e.g. a marker that
+    /// another process copies into it once it is set up. Gives up once the container exited or
+    /// the startup timeout elapsed.
+    pub(crate) fn wait_for_file(&self, path: &str) -> Result<(), WaitError> {
+        let deadline = Instant::now() + self.startup_timeout;
+
+        while self.docker_client.copy_file_from(&self.id, path).is_err() {
+            let state = ContainerState::from_details(self.docker_client.inspect(&self.id));
+            if matches!(
+                state.container_status(),
+                ContainerStatus::Exited | ContainerStatus::Dead
+            ) {
+                return Err(WaitError::Exited(state.exit_code()));
+            }
+            if Instant::now() >= deadline {
+                return Err(WaitError::Timeout);
+            }
+
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        Ok(())
+    }
+
+    /// Removes this container before it is replaced through [`replace_with`], e.g. to free its
+    /// name and host ports for the replacement. Dropping the container does not remove it again.
+    ///
+    /// [`replace_with`]: struct.Container.html#method.replace_with
+    pub(crate) fn remove_for_replacement(&mut self) {
+        self.rm();
+        self.removed = true;
+    }
+
+    /// Continues with the container with the given id, e.g. to retry a container that did not
+    /// become ready.
+    pub(crate) fn replace_with(&mut self, id: String) {
+        self.id = id;
+        self.removed = false;
+    }
+
+    fn block_until_ready(&self, conditions: Vec<WaitFor>) -> Result<(), WaitError> {
+        match self.block_until_conditions_met(conditions) {
+            Err(WaitError::Timeout) if self.full_logs_on_timeout => Err(
+                WaitError::TimeoutWithLogs(self.docker_client.all_logs(&self.id)),
+            ),
+            result => result,
+        }
+    }
+
+    fn block_until_conditions_met(&self, conditions: Vec<WaitFor>) -> Result<(), WaitError> {
         log::debug!("Waiting for container {} to be ready", self.id);
 
-        for condition in self.image.ready_conditions() {
+        let deadline = Some(Instant::now() + self.startup_timeout);
+        let progress = ReadyProgress::start(&self.id, self.progress_interval);
+
+        for condition in conditions {
+            progress.pending(&condition);
+
             match condition {
                 WaitFor::StdOutMessage { message } => self
                     .docker_client
                     .stdout_logs(&self.id)
-                    .wait_for_message(&message)
-                    .unwrap(),
+                    .wait_for_messages_until(&[message], deadline)?,
                 WaitFor::StdErrMessage { message } => self
                     .docker_client
                     .stderr_logs(&self.id)
-                    .wait_for_message(&message)
-                    .unwrap(),
+                    .wait_for_messages_until(&[message], deadline)?,
+                WaitFor::OrderedMessages { source, messages } => {
+                    let logs = match source {
+                        LogSource::StdOut => self.docker_client.stdout_logs(&self.id),
+                        LogSource::StdErr => self.docker_client.stderr_logs(&self.id),
+                    };
+
+                    logs.wait_for_messages_until(&messages, deadline)?
+                }
+                WaitFor::LineCount {
+                    source,
+                    count,
+                    timeout,
+                } => {
+                    let logs = match source {
+                        LogSource::StdOut => self.docker_client.stdout_logs(&self.id),
+                        LogSource::StdErr => self.docker_client.stderr_logs(&self.id),
+                    };
+
+                    logs.wait_for_line_count_until(count, earliest(deadline, timeout))?
+                }
                 WaitFor::Duration { length } => {
                     std::thread::sleep(length);
                 }
+                WaitFor::Http(strategy) => {
+                    strategy.wait_until_ready(self.get_host_port(strategy.port()), deadline)?
+                }
+                WaitFor::State(strategy) => strategy.wait_until_ready(
+                    || ContainerState::from_details(self.docker_client.inspect(&self.id)),
+                    deadline,
+                )?,
                 WaitFor::Nothing => {}
             }
         }
 
         log::debug!("Container {} is now ready!", self.id);
+
+        Ok(())
+    }
+
+    pub(crate) fn exec_after_start(&self) {
+        let state = ContainerState::from_details(self.docker_client.inspect(&self.id));
+
+        let commands = self.image.exec_after_start(state);
+        let recheck = !commands.is_empty() && self.image.recheck_readiness_after_exec();
+
+        for cmd in commands {
+            let output = self.exec(cmd.clone());
+
+            assert!(
+                output.success(),
+                "command {:?} failed in container {} with exit code {}: {}",
+                cmd.cmd,
+                self.id,
+                output.exit_code,
+                output.stderr
+            );
+        }
+
+        if recheck {
+            if let Err(e) = self.try_until_ready() {
+                panic!(
+                    "container {} did not become ready again after exec: {}",
+                    self.id, e
+                )
+            }
+        }
     }
 }
 
@@ -118,6 +348,47 @@ impl<'d, I> Container<'d, I> {
         &self.id
     }
 
+    /// Returns how long the phases of starting this container took, e.g. to track the startup
+    /// performance of an image over time.
+    ///
+    /// Only [`run`] and its variants record the phases. Waiting for a container started without
+    /// waiting is not recorded, as the time between starting and waiting is up to the caller.
+    ///
+    /// [`run`]: ../clients/struct.Cli.html#method.run
+    pub fn startup_report(&self) -> &StartupReport {
+        &self.startup_report
+    }
+
+    pub(crate) fn startup_report_mut(&mut self) -> &mut StartupReport {
+        &mut self.startup_report
+    }
+
+    /// Returns the lines the container logged to stdout up to and including the first one that
+    /// contains the message, e.g. to parse a startup banner.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the logs end before the message appeared.
+    pub(crate) fn stdout_lines_until(&self, message: &str) -> Vec<String> {
+        self.docker_client
+            .stdout_logs(&self.id)
+            .lines_until(message)
+            .unwrap_or_else(|e| panic!("failed to read logs of container {}: {}", self.id, e))
+    }
+
+    /// Returns the lines the container logged to stderr up to and including the first one that
+    /// contains the message.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the logs end before the message appeared.
+    pub(crate) fn stderr_lines_until(&self, message: &str) -> Vec<String> {
+        self.docker_client
+            .stderr_logs(&self.id)
+            .lines_until(message)
+            .unwrap_or_else(|e| panic!("failed to read logs of container {}: {}", self.id, e))
+    }
+
     /// Returns the mapped host port for an internal port of this docker container.
     ///
     /// This method does **not** magically expose the given port, it simply performs a mapping on
@@ -140,6 +411,23 @@ impl<'d, I> Container<'d, I> {
             })
     }
 
+    /// Returns the mapped host port for an internal UDP port of this docker container.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the given UDP port is not mapped.
+    pub fn get_host_port_udp(&self, internal_port: u16) -> u16 {
+        self.docker_client
+            .ports(&self.id)
+            .map_to_host_port_udp(internal_port)
+            .unwrap_or_else(|| {
+                panic!(
+                    "container {} does not expose UDP port {}",
+                    self.id, internal_port
+                )
+            })
+    }
+
     /// Returns the bridge ip address of docker container as specified in NetworkSettings.IPAddress
     pub fn get_bridge_ip_address(&self) -> IpAddr {
         IpAddr::from_str(
@@ -152,34 +440,183 @@ impl<'d, I> Container<'d, I> {
         .unwrap_or_else(|_| panic!("container {} has missing or invalid bridge IP", self.id))
     }
 
+    /// Returns the time this container was created at, as reported by the daemon.
+    #[cfg(feature = "chrono")]
+    pub fn created_at(&self) -> chrono::DateTime<chrono::Utc> {
+        self.docker_client.inspect(&self.id).created
+    }
+
+    /// Returns the time this container was last started at, as reported by the daemon.
+    #[cfg(feature = "chrono")]
+    pub fn started_at(&self) -> chrono::DateTime<chrono::Utc> {
+        self.docker_client.inspect(&self.id).state.started_at
+    }
+
     pub fn stop(&self) {
         log::debug!("Stopping docker container {}", self.id);
 
         self.docker_client.stop(&self.id)
     }
 
+    /// Stops this container immediately through `SIGKILL`, without giving it the chance to shut
+    /// down gracefully like [`stop`] does, e.g. to simulate a crash.
+    ///
+    /// [`stop`]: struct.Container.html#method.stop
+    pub fn kill(&self) {
+        log::debug!("Killing docker container {}", self.id);
+
+        self.docker_client.kill(&self.id)
+    }
+
     pub fn start(&self) {
         self.docker_client.start(&self.id);
     }
 
+    /// Suspends all processes of this container, e.g. to simulate a service that stops
+    /// responding without closing its connections.
+    pub fn pause(&self) {
+        log::debug!("Pausing docker container {}", self.id);
+
+        self.docker_client.pause(&self.id)
+    }
+
+    /// Resumes the processes of this container after [`pause`].
+    ///
+    /// [`pause`]: struct.Container.html#method.pause
+    pub fn unpause(&self) {
+        log::debug!("Unpausing docker container {}", self.id);
+
+        self.docker_client.unpause(&self.id)
+    }
+
+    /// Returns the current status of this container, e.g. to assert on the transitions caused by
+    /// [`pause`] or [`stop`].
+    ///
+    /// [`pause`]: struct.Container.html#method.pause
+    /// [`stop`]: struct.Container.html#method.stop
+    pub fn status(&self) -> ContainerStatus {
+        ContainerState::from_details(self.docker_client.inspect(&self.id)).container_status()
+    }
+
+    /// Blocks until this container has the given status, e.g. [`ContainerStatus::Exited`] once
+    /// its main process finished.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the container does not reach the status within the timeout.
+    ///
+    /// [`ContainerStatus::Exited`]: enum.ContainerStatus.html#variant.Exited
+    pub fn wait_for_status(&self, status: ContainerStatus, timeout: Duration) {
+        let expected = status.clone();
+        let strategy = StateWaitStrategy::new(format!("status {:?}", status), move |state| {
+            state.container_status() == expected
+        });
+
+        if strategy
+            .wait_until_ready(
+                || ContainerState::from_details(self.docker_client.inspect(&self.id)),
+                Some(Instant::now() + timeout),
+            )
+            .is_err()
+        {
+            panic!(
+                "container {} did not reach status {:?} within {:?}, it is {:?}",
+                self.id,
+                status,
+                timeout,
+                self.status()
+            )
+        }
+    }
+
     pub fn rm(&self) {
         log::debug!("Deleting docker container {}", self.id);
 
         self.docker_client.rm(&self.id)
     }
+
+    /// Writes the logs to the file configured through [`RunArgs::with_log_to_file`].
+    ///
+    /// This runs while dropping the container, possibly because of a panic, hence failures are
+    /// only logged.
+    ///
+    /// [`RunArgs::with_log_to_file`]: struct.RunArgs.html#method.with_log_to_file
+    fn write_log_file(&self) {
+        let path = match &self.log_file {
+            Some(path) => path,
+            None => return,
+        };
+
+        let result =
+            create_log_file(path).and_then(|file| self.docker_client.write_logs(&self.id, file));
+
+        if let Err(e) = result {
+            log::warn!(
+                "Failed to write logs of docker container {} to {}: {}",
+                self.id,
+                path.display(),
+                e
+            );
+        }
+    }
+
+    /// Returns the contents of the file at the given absolute path inside this container, e.g. a
+    /// configuration file the server generated on startup.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the file does not exist or cannot be copied.
+    pub fn copy_file_from(&self, path: &str) -> Vec<u8> {
+        self.docker_client
+            .copy_file_from(&self.id, path)
+            .unwrap_or_else(|e| panic!("failed to copy {} from container {}: {}", path, self.id, e))
+    }
+
+    /// Executes a command inside this container and returns its output once it has finished.
+    pub fn exec(&self, cmd: ExecCommand) -> ExecOutput {
+        log::debug!("Executing {:?} in docker container {}", cmd.cmd, self.id);
+
+        self.docker_client.exec(&self.id, cmd)
+    }
+
+    /// Executes a command inside this container with `input` attached to its stdin.
+    ///
+    /// This allows feeding scripts to interactive clients, e.g. `psql`. The input is streamed to
+    /// the command until the end of `input` is reached.
+    pub fn exec_with_stdin<R: Read + Send + 'static>(
+        &self,
+        cmd: ExecCommand,
+        input: R,
+    ) -> ExecOutput {
+        log::debug!(
+            "Executing {:?} with stdin in docker container {}",
+            cmd.cmd,
+            self.id
+        );
+
+        self.docker_client
+            .exec_with_stdin(&self.id, cmd, Box::new(input))
+    }
 }
 
 /// The destructor implementation for a Container.
 ///
-/// As soon as the container goes out of scope, the destructor will either only stop or delete the docker container, depending on the [`Command`] value.
+/// As soon as the container goes out of scope, the destructor will either keep or delete the docker container, depending on its [`RemovalPolicy`].
 ///
-/// Setting it to `keep` will stop container.
-/// Setting it to `remove` will remove it.
+/// By default, the policy follows the `TESTCONTAINERS` env variable: setting it to `keep` will keep the container, setting it to `remove` will remove it.
+///
+/// [`RemovalPolicy`]: enum.RemovalPolicy.html
 impl<'d, I> Drop for Container<'d, I> {
     fn drop(&mut self) {
-        match self.command {
-            Command::Keep => {}
-            Command::Remove => self.rm(),
+        if self.removed {
+            return;
+        }
+
+        // the logs are written regardless of whether the container is kept
+        self.write_log_file();
+
+        if self.removal.should_remove() {
+            self.rm()
         }
     }
 }
@@ -1,11 +1,31 @@
+#[cfg(feature = "chrono")]
+use crate::core::logs::LogPosition;
 use crate::{
-    core::{env, env::Command, logs::LogStreamAsync, ports::Ports, WaitFor},
+    core::{
+        health::{describe_unhealthy, Health, RECENT_LOG_LINES},
+        image::internal_port_by_name,
+        logs::{create_log_file, earliest, LogStreamAsync, WaitError},
+        ports::Ports,
+        progress::ReadyProgress,
+        startup::StartupReport,
+        ContainerState, ContainerStatus, ExecCommand, ExecHandle, ExecOutput, LogChunk, LogSource,
+        RemovalPolicy, StateWaitStrategy, WaitFor, DEFAULT_STARTUP_TIMEOUT,
+    },
     Image,
 };
 use async_trait::async_trait;
-use futures::{executor::block_on, FutureExt};
+use futures::{executor::block_on, io::AsyncRead, stream::BoxStream, FutureExt, Stream};
 use shiplift::rep::ContainerDetails;
-use std::{fmt, marker::PhantomData, net::IpAddr, str::FromStr};
+use std::{
+    fmt,
+    fs::File,
+    io,
+    marker::PhantomData,
+    net::IpAddr,
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 /// Represents a running docker container that has been started using an async client..
 ///
@@ -34,7 +54,16 @@ pub struct ContainerAsync<'d, I> {
     id: String,
     docker_client: Box<dyn DockerAsync>,
     image: I,
-    command: Command,
+    removal: RemovalPolicy,
+    startup_timeout: Duration,
+    progress_interval: Duration,
+    full_logs_on_timeout: bool,
+    log_file: Option<PathBuf>,
+    startup_report: StartupReport,
+    /// Whether the container was removed to be replaced, see [`remove_for_replacement`].
+    ///
+    /// [`remove_for_replacement`]: struct.ContainerAsync.html#method.remove_for_replacement
+    removed: bool,
 
     /// Tracks the lifetime of the client to make sure the container is dropped before the client.
     client_lifetime: PhantomData<&'d ()>,
@@ -46,6 +75,84 @@ impl<'d, I> ContainerAsync<'d, I> {
         &self.id
     }
 
+    /// Returns how long the phases of starting this container took, e.g. to track the startup
+    /// performance of an image over time.
+    ///
+    /// Only [`run`] and its variants record the phases. Waiting for a container started without
+    /// waiting is not recorded, as the time between starting and waiting is up to the caller.
+    ///
+    /// [`run`]: ../clients/struct.Http.html#method.run
+    pub fn startup_report(&self) -> &StartupReport {
+        &self.startup_report
+    }
+
+    pub(crate) fn startup_report_mut(&mut self) -> &mut StartupReport {
+        &mut self.startup_report
+    }
+
+    /// Returns a reference to the [`Image`] of this container.
+    ///
+    /// Access to this is useful to retrieve [`Image`] specific information such as authentication
+    /// details.
+    ///
+    /// [`Image`]: trait.Image.html
+    pub fn image(&self) -> &I {
+        &self.image
+    }
+
+    /// Returns the lines the container logged to stdout up to and including the first one that
+    /// contains the message, e.g. to parse a startup banner.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the logs end before the message appeared.
+    pub(crate) async fn stdout_lines_until(&self, message: &str) -> Vec<String> {
+        self.docker_client
+            .stdout_logs(&self.id)
+            .lines_until(message)
+            .await
+            .unwrap_or_else(|e| panic!("failed to read logs of container {}: {}", self.id, e))
+    }
+
+    /// Returns the lines the container logged to stderr up to and including the first one that
+    /// contains the message.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the logs end before the message appeared.
+    pub(crate) async fn stderr_lines_until(&self, message: &str) -> Vec<String> {
+        self.docker_client
+            .stderr_logs(&self.id)
+            .lines_until(message)
+            .await
+            .unwrap_or_else(|e| panic!("failed to read logs of container {}: {}", self.id, e))
+    }
+
+    /// Returns the lines the container logged to the given stream after the position, together
+    /// with the position to continue reading from.
+    ///
+    /// Only the logs written so far are returned, this method does not wait for more lines. Start
+    /// reading at [`LogPosition::start`] and pass the returned position to the next call to only
+    /// get the lines logged in between, even if the container was restarted in the meantime.
+    ///
+    /// [`LogPosition::start`]: struct.LogPosition.html#method.start
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the logs cannot be read.
+    #[cfg(feature = "chrono")]
+    pub async fn logs_since(
+        &self,
+        source: LogSource,
+        position: LogPosition,
+    ) -> (Vec<String>, LogPosition) {
+        self.docker_client
+            .logs_since(&self.id, source, position)
+            .lines_after(position)
+            .await
+            .unwrap_or_else(|e| panic!("failed to read logs of container {}: {}", self.id, e))
+    }
+
     /// Returns the mapped host port for an internal port of this docker container.
     ///
     /// This method does **not** magically expose the given port, it simply performs a mapping on
@@ -69,6 +176,24 @@ impl<'d, I> ContainerAsync<'d, I> {
             })
     }
 
+    /// Returns the mapped host port for an internal UDP port of this docker container.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the given UDP port is not mapped.
+    pub async fn get_host_port_udp(&self, internal_port: u16) -> u16 {
+        self.docker_client
+            .ports(&self.id)
+            .await
+            .map_to_host_port_udp(internal_port)
+            .unwrap_or_else(|| {
+                panic!(
+                    "container {} does not expose UDP port {}",
+                    self.id, internal_port
+                )
+            })
+    }
+
     /// Returns the bridge ip address of docker container as specified in NetworkSettings.IPAddress
     pub async fn get_bridge_ip_address(&self) -> IpAddr {
         self.docker_client
@@ -81,26 +206,210 @@ impl<'d, I> ContainerAsync<'d, I> {
             .await
     }
 
+    /// Returns the time this container was created at, as reported by the daemon.
+    #[cfg(feature = "chrono")]
+    pub async fn created_at(&self) -> chrono::DateTime<chrono::Utc> {
+        self.docker_client.inspect(&self.id).await.created
+    }
+
+    /// Returns the time this container was last started at, as reported by the daemon.
+    #[cfg(feature = "chrono")]
+    pub async fn started_at(&self) -> chrono::DateTime<chrono::Utc> {
+        self.docker_client.inspect(&self.id).await.state.started_at
+    }
+
     pub async fn start(&self) {
         self.docker_client.start(&self.id).await
     }
 
+    /// Suspends all processes of this container, e.g. to simulate a service that stops
+    /// responding without closing its connections.
+    pub async fn pause(&self) {
+        log::debug!("Pausing docker container {}", self.id);
+
+        self.docker_client.pause(&self.id).await
+    }
+
+    /// Resumes the processes of this container after [`pause`].
+    ///
+    /// [`pause`]: struct.ContainerAsync.html#method.pause
+    pub async fn unpause(&self) {
+        log::debug!("Unpausing docker container {}", self.id);
+
+        self.docker_client.unpause(&self.id).await
+    }
+
+    /// Returns the current status of this container, e.g. to assert on the transitions caused by
+    /// [`pause`] or [`stop`].
+    ///
+    /// [`pause`]: struct.ContainerAsync.html#method.pause
+    /// [`stop`]: struct.ContainerAsync.html#method.stop
+    pub async fn status(&self) -> ContainerStatus {
+        ContainerState::from_details(self.docker_client.inspect(&self.id).await).container_status()
+    }
+
+    /// Waits until this container has the given status, e.g. [`ContainerStatus::Exited`] once its
+    /// main process finished.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the container does not reach the status within the timeout.
+    ///
+    /// [`ContainerStatus::Exited`]: enum.ContainerStatus.html#variant.Exited
+    pub async fn wait_for_status(&self, status: ContainerStatus, timeout: Duration) {
+        let expected = status.clone();
+        let strategy = StateWaitStrategy::new(format!("status {:?}", status), move |state| {
+            state.container_status() == expected
+        });
+        let inspect =
+            || async { ContainerState::from_details(self.docker_client.inspect(&self.id).await) };
+
+        if strategy
+            .wait_until_ready_async(inspect, Some(Instant::now() + timeout))
+            .await
+            .is_err()
+        {
+            panic!(
+                "container {} did not reach status {:?} within {:?}, it is {:?}",
+                self.id,
+                status,
+                timeout,
+                self.status().await
+            )
+        }
+    }
+
     pub async fn stop(&self) {
         log::debug!("Stopping docker container {}", self.id);
 
         self.docker_client.stop(&self.id).await
     }
 
+    /// Stops this container immediately through `SIGKILL`, without giving it the chance to shut
+    /// down gracefully like [`stop`] does, e.g. to simulate a crash.
+    ///
+    /// [`stop`]: struct.ContainerAsync.html#method.stop
+    pub async fn kill(&self) {
+        log::debug!("Killing docker container {}", self.id);
+
+        self.docker_client.kill(&self.id).await
+    }
+
     pub async fn rm(self) {
         log::debug!("Deleting docker container {}", self.id);
 
         self.docker_client.rm(&self.id).await
     }
 
+    /// Returns the contents of the file at the given absolute path inside this container, e.g. a
+    /// configuration file the server generated on startup.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the file does not exist or cannot be copied.
+    pub async fn copy_file_from(&self, path: &str) -> Vec<u8> {
+        self.docker_client
+            .copy_file_from(&self.id, path)
+            .await
+            .unwrap_or_else(|e| panic!("failed to copy {} from container {}: {}", path, self.id, e))
+    }
+
+    /// Executes a command inside this container and returns its output once it has finished.
+    pub async fn exec(&self, cmd: ExecCommand) -> ExecOutput {
+        log::debug!("Executing {:?} in docker container {}", cmd.cmd, self.id);
+
+        self.docker_client.exec(&self.id, cmd).await
+    }
+
+    /// Executes a command inside this container and streams its stdout and stderr while it runs,
+    /// e.g. to follow the progress of a migration.
+    ///
+    /// The exit code is available through the returned [`ExecHandle`] once the stream ended.
+    ///
+    /// # Panics
+    ///
+    /// The stream panics if the command cannot be executed, or its output or exit code cannot be
+    /// read.
+    ///
+    /// [`ExecHandle`]: core/struct.ExecHandle.html
+    pub fn exec_streaming(
+        &self,
+        cmd: ExecCommand,
+    ) -> (impl Stream<Item = LogChunk> + Send + '_, ExecHandle) {
+        log::debug!(
+            "Executing {:?} with streamed output in docker container {}",
+            cmd.cmd,
+            self.id
+        );
+
+        let handle = ExecHandle::default();
+        let stream = self
+            .docker_client
+            .exec_streaming(&self.id, cmd, handle.clone());
+
+        (stream, handle)
+    }
+
+    /// Executes a command inside this container with `input` attached to its stdin.
+    ///
+    /// This allows feeding scripts to interactive clients, e.g. `psql`. The input is streamed to
+    /// the command until it ends, after which the stdin of the command is closed.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `input` cannot be read.
+    pub async fn exec_with_stdin<R>(&self, cmd: ExecCommand, mut input: R) -> ExecOutput
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        log::debug!(
+            "Executing {:?} with stdin in docker container {}",
+            cmd.cmd,
+            self.id
+        );
+
+        self.docker_client
+            .exec_with_stdin(&self.id, cmd, &mut input)
+            .await
+    }
+
     async fn drop_async(&self) {
-        match self.command {
-            env::Command::Remove => self.docker_client.rm(&self.id).await,
-            env::Command::Keep => {}
+        if self.removed {
+            return;
+        }
+
+        // the logs are written regardless of whether the container is kept
+        self.write_log_file().await;
+
+        if self.removal.should_remove() {
+            self.docker_client.rm(&self.id).await
+        }
+    }
+
+    /// Writes the logs to the file configured through [`RunArgs::with_log_to_file`].
+    ///
+    /// This runs while dropping the container, possibly because of a panic, hence failures are
+    /// only logged.
+    ///
+    /// [`RunArgs::with_log_to_file`]: struct.RunArgs.html#method.with_log_to_file
+    async fn write_log_file(&self) {
+        let path = match &self.log_file {
+            Some(path) => path,
+            None => return,
+        };
+
+        let result = match create_log_file(path) {
+            Ok(file) => self.docker_client.write_logs(&self.id, file).await,
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            log::warn!(
+                "Failed to write logs of docker container {} to {}: {}",
+                self.id,
+                path.display(),
+                e
+            );
         }
     }
 }
@@ -124,15 +433,52 @@ where
 #[async_trait]
 pub(crate) trait DockerAsync
 where
-    Self: Sync,
+    Self: Send + Sync,
 {
     fn stdout_logs<'s>(&'s self, id: &str) -> LogStreamAsync<'s>;
     fn stderr_logs<'s>(&'s self, id: &str) -> LogStreamAsync<'s>;
+    /// Returns the timestamped logs written so far to the stream, starting at the second of the
+    /// position.
+    #[cfg(feature = "chrono")]
+    fn logs_since<'s>(
+        &'s self,
+        id: &str,
+        source: LogSource,
+        position: LogPosition,
+    ) -> LogStreamAsync<'s>;
     async fn ports(&self, id: &str) -> Ports;
     async fn inspect(&self, id: &str) -> ContainerDetails;
     async fn rm(&self, id: &str);
     async fn stop(&self, id: &str);
+    async fn kill(&self, id: &str);
     async fn start(&self, id: &str);
+    async fn pause(&self, id: &str);
+    async fn unpause(&self, id: &str);
+    async fn exec(&self, id: &str, cmd: ExecCommand) -> ExecOutput;
+    async fn exec_with_stdin(
+        &self,
+        id: &str,
+        cmd: ExecCommand,
+        input: &mut (dyn AsyncRead + Unpin + Send),
+    ) -> ExecOutput;
+    /// Writes the complete stdout and stderr logs of the container to the file.
+    async fn write_logs(&self, id: &str, file: File) -> io::Result<()>;
+    /// Returns the contents of the file at the absolute path in the container.
+    async fn copy_file_from(&self, id: &str, path: &str) -> io::Result<Vec<u8>>;
+    /// Executes the command and streams its output, setting the exit code of the handle once the
+    /// stream ended.
+    fn exec_streaming<'s>(
+        &'s self,
+        id: &str,
+        cmd: ExecCommand,
+        handle: ExecHandle,
+    ) -> BoxStream<'s, LogChunk>;
+    /// Returns the health of the container, or `None` if it has no health check.
+    async fn health(&self, id: &str) -> Option<Health>;
+    /// Returns the last lines the container logged to stdout and stderr.
+    async fn recent_logs(&self, id: &str, lines: usize) -> String;
+    /// Returns everything the container logged to stdout and stderr.
+    async fn all_logs(&self, id: &str) -> String;
 }
 
 impl<'d, I> ContainerAsync<'d, I>
@@ -140,51 +486,250 @@ where
     I: Image,
 {
     /// Constructs a new container given an id, a docker client and the image.
-    /// ContainerAsync::new().await
-    pub(crate) async fn new(
+    ///
+    /// This does not wait for the container to be ready, see [`wait_until_ready`].
+    /// If no `startup_timeout` is given, the one declared by the image is used, and
+    /// [`DEFAULT_STARTUP_TIMEOUT`] if the image declares none.
+    ///
+    /// [`wait_until_ready`]: struct.ContainerAsync.html#method.wait_until_ready
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
         id: String,
         docker_client: impl DockerAsync + 'static,
         image: I,
-        command: env::Command,
+        removal: RemovalPolicy,
+        startup_timeout: Option<Duration>,
+        progress_interval: Duration,
+        full_logs_on_timeout: bool,
+        log_file: Option<PathBuf>,
     ) -> ContainerAsync<'d, I> {
-        let container = ContainerAsync {
+        let startup_timeout = startup_timeout
+            .or_else(|| image.startup_timeout())
+            .unwrap_or(DEFAULT_STARTUP_TIMEOUT);
+
+        ContainerAsync {
             id,
             docker_client: Box::new(docker_client),
             image,
-            command,
+            removal,
+            startup_timeout,
+            progress_interval,
+            full_logs_on_timeout,
+            log_file,
+            startup_report: StartupReport::default(),
+            removed: false,
             client_lifetime: PhantomData,
-        };
+        }
+    }
+
+    /// Waits until the [`ready_conditions`] of the image are met and runs the commands the image
+    /// wants to [`exec_after_start`].
+    ///
+    /// Containers started through [`run`] already did this, which is why this is only needed for
+    /// containers started through [`run_without_wait`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the container does not become ready within the startup timeout.
+    ///
+    /// [`ready_conditions`]: trait.Image.html#tymethod.ready_conditions
+    /// [`exec_after_start`]: trait.Image.html#method.exec_after_start
+    /// [`run`]: ../clients/struct.Http.html#method.run
+    /// [`run_without_wait`]: ../clients/struct.Http.html#method.run_without_wait
+    pub async fn wait_until_ready(&self) {
+        if let Err(e) = self.try_until_ready().await {
+            panic!("container {} did not become ready: {}", self.id, e)
+        }
+        self.exec_after_start().await;
+    }
+
+    /// Waits until the given condition is met, e.g. to check the readiness of a container started
+    /// through [`run_without_wait`] step by step.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the condition is not met within the startup timeout.
+    ///
+    /// [`run_without_wait`]: ../clients/struct.Http.html#method.run_without_wait
+    pub async fn wait_for(&self, condition: WaitFor) {
+        let description = condition.to_string();
+
+        if let Err(e) = self.block_until_ready(vec![condition]).await {
+            panic!(
+                "container {} did not meet condition {}: {}",
+                self.id, description, e
+            )
+        }
+    }
+
+    /// Panics unless the health check of this container currently reports it as healthy, e.g. to
+    /// assert that a container is still in a good state after the test.
+    ///
+    /// The health check is configured by the image or through [`RunArgs::with_health_check`].
+    /// This method does not wait while the health check is still starting.
+    ///
+    /// # Panics
+    ///
+    /// This method panics with the output of the last health checks and the recent logs of the
+    /// container if it is not healthy or has no health check.
+    ///
+    /// [`RunArgs::with_health_check`]: struct.RunArgs.html#method.with_health_check
+    pub async fn assert_healthy(&self) {
+        let health = self.docker_client.health(&self.id).await;
+
+        if health.as_ref().is_some_and(Health::is_healthy) {
+            return;
+        }
 
-        container.block_until_ready().await;
+        let logs = self
+            .docker_client
+            .recent_logs(&self.id, RECENT_LOG_LINES)
+            .await;
 
-        container
+        panic!("{}", describe_unhealthy(&self.id, health.as_ref(), &logs))
+    }
+
+    /// Returns the mapped host port for a port that the [`Image`] declares under the given name.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the image does not declare a port with the given name or if the
+    /// port is not mapped.
+    ///
+    /// [`Image`]: trait.Image.html
+    pub async fn get_host_port_by_name(&self, name: &str) -> u16 {
+        self.get_host_port(internal_port_by_name(&self.image, name))
+            .await
+    }
+
+    pub(crate) async fn try_until_ready(&self) -> Result<(), WaitError> {
+        self.block_until_ready(self.image.ready_conditions()).await
+    }
+
+    /// Removes this container before it is replaced through [`replace_with`], e.g. to free its
+    /// name and host ports for the replacement. Dropping the container does not remove it again.
+    ///
+    /// [`replace_with`]: struct.ContainerAsync.html#method.replace_with
+    pub(crate) async fn remove_for_replacement(&mut self) {
+        self.docker_client.rm(&self.id).await;
+        self.removed = true;
+    }
+
+    /// Continues with the container with the given id, e.g. to retry a container that did not
+    /// become ready.
+    pub(crate) fn replace_with(&mut self, id: String) {
+        self.id = id;
+        self.removed = false;
+    }
+
+    async fn block_until_ready(&self, conditions: Vec<WaitFor>) -> Result<(), WaitError> {
+        match self.block_until_conditions_met(conditions).await {
+            Err(WaitError::Timeout) if self.full_logs_on_timeout => Err(
+                WaitError::TimeoutWithLogs(self.docker_client.all_logs(&self.id).await),
+            ),
+            result => result,
+        }
     }
 
-    async fn block_until_ready(&self) {
+    async fn block_until_conditions_met(&self, conditions: Vec<WaitFor>) -> Result<(), WaitError> {
         log::debug!("Waiting for container {} to be ready", self.id);
 
-        for condition in self.image.ready_conditions() {
+        let deadline = Some(Instant::now() + self.startup_timeout);
+        let progress = ReadyProgress::start(&self.id, self.progress_interval);
+
+        for condition in conditions {
+            progress.pending(&condition);
+
             match condition {
-                WaitFor::StdOutMessage { message } => self
-                    .docker_client
-                    .stdout_logs(&self.id)
-                    .wait_for_message(&message)
-                    .await
-                    .unwrap(),
-                WaitFor::StdErrMessage { message } => self
-                    .docker_client
-                    .stderr_logs(&self.id)
-                    .wait_for_message(&message)
-                    .await
-                    .unwrap(),
+                WaitFor::StdOutMessage { message } => {
+                    self.docker_client
+                        .stdout_logs(&self.id)
+                        .wait_for_messages_until(&[message], deadline)
+                        .await?
+                }
+                WaitFor::StdErrMessage { message } => {
+                    self.docker_client
+                        .stderr_logs(&self.id)
+                        .wait_for_messages_until(&[message], deadline)
+                        .await?
+                }
+                WaitFor::OrderedMessages { source, messages } => {
+                    let logs = match source {
+                        LogSource::StdOut => self.docker_client.stdout_logs(&self.id),
+                        LogSource::StdErr => self.docker_client.stderr_logs(&self.id),
+                    };
+
+                    logs.wait_for_messages_until(&messages, deadline).await?
+                }
+                WaitFor::LineCount {
+                    source,
+                    count,
+                    timeout,
+                } => {
+                    let logs = match source {
+                        LogSource::StdOut => self.docker_client.stdout_logs(&self.id),
+                        LogSource::StdErr => self.docker_client.stderr_logs(&self.id),
+                    };
+
+                    logs.wait_for_line_count_until(count, earliest(deadline, timeout))
+                        .await?
+                }
                 WaitFor::Duration { length } => {
                     tokio::time::sleep(length).await;
                 }
+                WaitFor::Http(strategy) => {
+                    let host_port = self.get_host_port(strategy.port()).await;
+
+                    strategy.wait_until_ready_async(host_port, deadline).await?
+                }
+                WaitFor::State(strategy) => {
+                    strategy
+                        .wait_until_ready_async(
+                            || async {
+                                ContainerState::from_details(
+                                    self.docker_client.inspect(&self.id).await,
+                                )
+                            },
+                            deadline,
+                        )
+                        .await?
+                }
                 WaitFor::Nothing => {}
             }
         }
 
         log::debug!("Container {} is now ready!", self.id);
+
+        Ok(())
+    }
+
+    pub(crate) async fn exec_after_start(&self) {
+        let state = ContainerState::from_details(self.docker_client.inspect(&self.id).await);
+
+        let commands = self.image.exec_after_start(state);
+        let recheck = !commands.is_empty() && self.image.recheck_readiness_after_exec();
+
+        for cmd in commands {
+            let output = self.exec(cmd.clone()).await;
+
+            assert!(
+                output.success(),
+                "command {:?} failed in container {} with exit code {}: {}",
+                cmd.cmd,
+                self.id,
+                output.exit_code,
+                output.stderr
+            );
+        }
+
+        if recheck {
+            if let Err(e) = self.block_until_ready(self.image.ready_conditions()).await {
+                panic!(
+                    "container {} did not become ready again after exec: {}",
+                    self.id, e
+                )
+            }
+        }
     }
 }
 
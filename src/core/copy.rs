@@ -0,0 +1,141 @@
+use std::{
+    io::{self, Read},
+    path::Path,
+};
+
+/// A file that is copied into a container after it has been created, but before it is started.
+///
+/// This allows images to provide configuration files without having to bind-mount anything from
+/// the host.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopyToContainer {
+    /// The absolute path of the file inside the container.
+    pub target: String,
+    pub data: Vec<u8>,
+    /// The permissions of the file, `0o644` by default.
+    pub mode: u32,
+}
+
+impl CopyToContainer {
+    pub fn new<D: Into<Vec<u8>>, T: Into<String>>(data: D, target: T) -> Self {
+        let target = target.into();
+
+        assert!(
+            target.starts_with('/'),
+            "target of a file copied into a container must be absolute, got {}",
+            target
+        );
+
+        CopyToContainer {
+            target,
+            data: data.into(),
+            mode: 0o644,
+        }
+    }
+
+    /// Sets the permissions of the file, e.g. `0o600` for private keys that servers refuse to
+    /// read otherwise.
+    pub fn with_mode(self, mode: u32) -> Self {
+        CopyToContainer { mode, ..self }
+    }
+}
+
+/// Packs the given files into a tar archive that is meant to be extracted at the root directory
+/// of a container.
+pub(crate) fn tar_archive(files: &[CopyToContainer]) -> Vec<u8> {
+    let mut archive = tar::Builder::new(Vec::new());
+
+    for file in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(file.data.len() as u64);
+        header.set_mode(file.mode);
+
+        let path = Path::new(&file.target)
+            .strip_prefix("/")
+            .expect("target is absolute");
+
+        archive
+            .append_data(&mut header, path, file.data.as_slice())
+            .expect("failed to append file to archive");
+    }
+
+    archive.into_inner().expect("failed to finish archive")
+}
+
+/// Returns the contents of the file in a tar archive as returned by the daemon when copying a
+/// single file out of a container.
+pub(crate) fn file_from_tar_archive(archive: &[u8]) -> io::Result<Vec<u8>> {
+    let mut archive = tar::Archive::new(archive);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+
+        if entry.header().entry_type().is_file() {
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+
+            return Ok(data);
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "archive does not contain a file",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_pack_files_relative_to_root() {
+        let archive = tar_archive(&[
+            CopyToContainer::new("hello", "/etc/app/one.conf"),
+            CopyToContainer::new("world", "/two.key").with_mode(0o600),
+        ]);
+
+        let mut archive = tar::Archive::new(archive.as_slice());
+        let files = archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let mut entry = entry.unwrap();
+                let path = entry.path().unwrap().to_string_lossy().into_owned();
+                let mode = entry.header().mode().unwrap();
+                let mut content = String::new();
+                entry.read_to_string(&mut content).unwrap();
+
+                (path, mode, content)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            files,
+            vec![
+                ("etc/app/one.conf".to_owned(), 0o644, "hello".to_owned()),
+                ("two.key".to_owned(), 0o600, "world".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "must be absolute")]
+    fn should_reject_relative_target() {
+        CopyToContainer::new("hello", "etc/app.conf");
+    }
+
+    #[test]
+    fn should_unpack_single_file() {
+        let archive = tar_archive(&[CopyToContainer::new("apiVersion: v1", "/k3s.yaml")]);
+
+        assert_eq!(
+            file_from_tar_archive(&archive).unwrap(),
+            b"apiVersion: v1".to_vec()
+        );
+        assert_eq!(
+            file_from_tar_archive(&tar_archive(&[])).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
@@ -1,17 +1,91 @@
-use crate::core::{logs::LogStream, ports::Ports, Port};
+use crate::core::{
+    env::{self, GetEnvValue},
+    health::Health,
+    logs::LogStream,
+    ports::Ports,
+    ExecCommand, ExecOutput, Image, Port, WaitFor,
+};
 use shiplift::rep::ContainerDetails;
+use std::{fmt, fs::File, io, io::Read, path::PathBuf, sync::Arc, time::Duration};
 
 /// Container run command arguments.
 /// `name` - run image instance with the given name (should be explicitly set to be seen by other containers created in the same docker network).
 /// `network` - run image instance on the given network.
+/// `existing_network` - attach to the network without creating or removing it.
+/// `network_mtu` - create the network with the given MTU, if it does not exist yet.
+/// `internal_network` - create the network without outbound access, if it does not exist yet.
 /// `ports` - run image instance with the given ports mapping (if explicit mappings is not defined, all image ports will be automatically exposed and mapped on random host ports).
+/// `memory` - run image instance with the given memory limit in bytes.
+/// `memory_swap` - run image instance with the given limit of memory and swap together in bytes (`-1` for unlimited swap).
+/// `oom_kill_disabled` - prevent the kernel from killing the image instance when it exceeds its memory limit.
+/// `oom_score_adj` - run image instance with the given OOM score adjustment (the higher the value, the more likely the kernel kills the container when running out of memory).
+/// `gpus` - run image instance with access to the given GPUs (requires the NVIDIA container toolkit on the host).
+/// `log_config` - run image instance with the given log driver and options (e.g. to limit the size of `json-file` logs).
+/// `startup_timeout` - fail if the image instance does not become ready within the given time (by default, `DEFAULT_STARTUP_TIMEOUT` unless the image declares its own).
+/// `cmd` - run image instance with the given command instead of, or appended to, the arguments of the image.
+/// `log_dir` - write the logs of the image instance to `<log_dir>/<name or id>.log` when it is removed.
+/// `env_vars` - run image instance with the given environment variables in addition to those of the image.
+/// `full_logs_on_timeout` - attach all logs of the image instance to the error if it does not become ready within the startup timeout.
+/// `progress_interval` - log which ready condition is still pending at the given interval while waiting for the image instance (by default, every 30 seconds).
+/// `volumes_from` - run image instance with the volumes of the given containers mounted at the same paths.
+/// `bind_mounts` - run image instance with the given directories or files of the host mounted into it.
+/// `isolation` - run image instance with the given isolation technology (only supported for Windows containers).
+/// `security_opts` - run image instance with the given security options, e.g. a custom seccomp or AppArmor profile.
+/// `userns_mode` - run image instance with the given user namespace mode, e.g. `host` to opt out of the user namespace remapping of the daemon.
+/// `storage_opts` - run image instance with the given options of the storage driver, e.g. to limit the size of its writable layer.
+/// `health_check` - run image instance with the given health check instead of the one of the image.
+/// `labels` - run image instance with the given docker labels, e.g. to find it through `docker ps --filter label=<key>=<value>`.
+/// `annotations` - run image instance with the given OCI annotations, which are dropped with a warning if the daemon is too old to store them.
+/// `create_modifier` - modify the request creating the image instance right before it is sent to the daemon (unstable, only supported by the Http client).
+/// `removal` - whether the image instance is removed when it is dropped (by default, as configured through the `TESTCONTAINERS` env variable).
+/// `start_retries` - replace the image instance by a new one if it fails to start or to become ready for a transient reason, at most the given number of times (by default, it is not retried).
 #[derive(Debug, Clone, Default)]
 pub struct RunArgs {
     name: Option<String>,
     network: Option<String>,
+    existing_network: bool,
+    network_mtu: Option<u32>,
+    internal_network: bool,
     ports: Option<Vec<Port>>,
+    memory: Option<u64>,
+    memory_swap: Option<i64>,
+    oom_kill_disabled: bool,
+    oom_score_adj: Option<i32>,
+    gpus: Option<Gpus>,
+    isolation: Option<Isolation>,
+    security_opts: Vec<String>,
+    userns_mode: Option<String>,
+    storage_opts: Vec<(String, String)>,
+    health_check: Option<HealthCheck>,
+    labels: Vec<(String, String)>,
+    annotations: Vec<(String, String)>,
+    log_config: Option<LogConfig>,
+    startup_timeout: Option<Duration>,
+    cmd: Option<Cmd>,
+    log_dir: Option<PathBuf>,
+    progress_interval: Option<Duration>,
+    full_logs_on_timeout: bool,
+    env_vars: Vec<(String, String)>,
+    volumes_from: Vec<(String, AccessMode)>,
+    bind_mounts: Vec<BindMount>,
+    start_retries: u32,
+    create_modifier: Option<CreateModifier>,
+    removal: Option<RemovalPolicy>,
 }
 
+const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The maximum time to wait for the [`ready_conditions`] of an image to be met, unless the image
+/// declares its own [`startup_timeout`] or one is set through [`RunArgs::with_startup_timeout`].
+///
+/// [`ready_conditions`]: trait.Image.html#tymethod.ready_conditions
+/// [`startup_timeout`]: trait.Image.html#method.startup_timeout
+/// [`RunArgs::with_startup_timeout`]: struct.RunArgs.html#method.with_startup_timeout
+pub const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The smallest memory limit the daemon accepts.
+const MIN_MEMORY: u64 = 6 * 1024 * 1024;
+
 /// Defines operations that we need to perform on docker containers and other entities.
 ///
 /// This trait is pub(crate) because it should not be used directly by users but only represents an internal abstraction that allows containers to be generic over the client they have been started with.
@@ -23,7 +97,27 @@ pub(crate) trait Docker {
     fn inspect(&self, id: &str) -> ContainerDetails;
     fn rm(&self, id: &str);
     fn stop(&self, id: &str);
+    fn kill(&self, id: &str);
     fn start(&self, id: &str);
+    fn pause(&self, id: &str);
+    fn unpause(&self, id: &str);
+    fn exec(&self, id: &str, cmd: ExecCommand) -> ExecOutput;
+    /// Writes the complete stdout and stderr logs of the container to the file.
+    fn write_logs(&self, id: &str, file: File) -> io::Result<()>;
+    /// Returns the contents of the file at the absolute path in the container.
+    fn copy_file_from(&self, id: &str, path: &str) -> io::Result<Vec<u8>>;
+    /// Returns the health of the container, or `None` if it has no health check.
+    fn health(&self, id: &str) -> Option<Health>;
+    /// Returns the last lines the container logged to stdout and stderr.
+    fn recent_logs(&self, id: &str, lines: usize) -> String;
+    /// Returns everything the container logged to stdout and stderr.
+    fn all_logs(&self, id: &str) -> String;
+    fn exec_with_stdin(
+        &self,
+        id: &str,
+        cmd: ExecCommand,
+        input: Box<dyn Read + Send>,
+    ) -> ExecOutput;
 }
 
 impl RunArgs {
@@ -34,9 +128,48 @@ impl RunArgs {
         }
     }
 
+    /// Runs the container on the given network. The network is created if it does not exist
+    /// yet, and removed once the client that created it is dropped.
     pub fn with_network<T: ToString>(self, network: T) -> Self {
         RunArgs {
             network: Some(network.to_string()),
+            existing_network: false,
+            ..self
+        }
+    }
+
+    /// Runs the container on the network with the given name or id, which is managed elsewhere,
+    /// e.g. by docker-compose. Unlike through [`with_network`], the network is never created nor
+    /// removed, so starting the container fails if it does not exist.
+    ///
+    /// [`with_network`]: struct.RunArgs.html#method.with_network
+    pub fn with_existing_network<T: ToString>(self, network: T) -> Self {
+        RunArgs {
+            network: Some(network.to_string()),
+            existing_network: true,
+            ..self
+        }
+    }
+
+    /// Creates the network passed through [`with_network`] with the given MTU, e.g. to test a
+    /// service behind a VPN. It has no effect if the network exists already.
+    ///
+    /// [`with_network`]: struct.RunArgs.html#method.with_network
+    pub fn with_network_mtu(self, mtu: u32) -> Self {
+        RunArgs {
+            network_mtu: Some(mtu),
+            ..self
+        }
+    }
+
+    /// Creates the network passed through [`with_network`] as internal network, like
+    /// `docker network create --internal`, so containers on it can only reach each other. It has
+    /// no effect if the network exists already.
+    ///
+    /// [`with_network`]: struct.RunArgs.html#method.with_network
+    pub fn with_internal_network(self) -> Self {
+        RunArgs {
+            internal_network: true,
             ..self
         }
     }
@@ -48,10 +181,336 @@ impl RunArgs {
         self
     }
 
+    /// Limits the memory of the container to the given number of bytes, like `docker run --memory`.
+    pub fn with_memory(self, bytes: u64) -> Self {
+        RunArgs {
+            memory: Some(bytes),
+            ..self
+        }
+    }
+
+    /// Limits memory and swap of the container together to the given number of bytes, like
+    /// `docker run --memory-swap`, e.g. twice the memory limit to allow as much swap as memory,
+    /// or `-1` for unlimited swap. Needs a memory limit set through [`with_memory`].
+    ///
+    /// [`with_memory`]: struct.RunArgs.html#method.with_memory
+    pub fn with_memory_swap(self, bytes: i64) -> Self {
+        RunArgs {
+            memory_swap: Some(bytes),
+            ..self
+        }
+    }
+
+    /// Prevents the kernel from killing the container when it exceeds its memory limit, like
+    /// `docker run --oom-kill-disable`, so it blocks until memory is freed instead. Needs a memory
+    /// limit set through [`with_memory`], as the container could exhaust the memory of the host
+    /// otherwise.
+    ///
+    /// [`with_memory`]: struct.RunArgs.html#method.with_memory
+    pub fn with_oom_kill_disabled(self) -> Self {
+        RunArgs {
+            oom_kill_disabled: true,
+            ..self
+        }
+    }
+
+    /// Sets the OOM score adjustment of the container.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the score is not within `-1000..=1000`.
+    pub fn with_oom_score_adj(self, oom_score_adj: i32) -> Self {
+        assert!(
+            (-1000..=1000).contains(&oom_score_adj),
+            "OOM score adjustment must be within -1000 and 1000, got {}",
+            oom_score_adj
+        );
+
+        RunArgs {
+            oom_score_adj: Some(oom_score_adj),
+            ..self
+        }
+    }
+
+    /// Gives the container access to GPUs of the host, like `docker run --gpus`.
+    pub fn with_gpus(self, gpus: Gpus) -> Self {
+        RunArgs {
+            gpus: Some(gpus),
+            ..self
+        }
+    }
+
+    /// Sets the isolation technology of a Windows container, like `docker run --isolation`.
+    pub fn with_isolation(self, isolation: Isolation) -> Self {
+        RunArgs {
+            isolation: Some(isolation),
+            ..self
+        }
+    }
+
+    /// Adds a security option, like `docker run --security-opt`, e.g. `seccomp=/path/to/profile.json`
+    /// or `apparmor=unconfined`.
+    pub fn with_security_opt<S: Into<String>>(mut self, security_opt: S) -> Self {
+        self.security_opts.push(security_opt.into());
+        self
+    }
+
+    /// Sets the user namespace mode of the container, like `docker run --userns`, e.g. `host` to
+    /// opt out of the user namespace remapping of the daemon for mounts that need host ownership.
+    pub fn with_userns_mode<M: Into<String>>(self, userns_mode: M) -> Self {
+        RunArgs {
+            userns_mode: Some(userns_mode.into()),
+            ..self
+        }
+    }
+
+    /// Adds an option of the storage driver, like `docker run --storage-opt`, e.g. `size` with
+    /// `10G` to limit the size of the writable layer of the container.
+    ///
+    /// Only some storage drivers support options, e.g. `overlay2` on XFS mounted with `pquota`.
+    /// Starting the container panics with the error of the daemon on others.
+    pub fn with_storage_opt<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.storage_opts.push((key.into(), value.into()));
+        self
+    }
+
+    /// Sets the health check of the container, like `docker run --health-cmd`, replacing the
+    /// one of the image, see [`Container::assert_healthy`].
+    ///
+    /// [`Container::assert_healthy`]: struct.Container.html#method.assert_healthy
+    pub fn with_health_check(self, health_check: HealthCheck) -> Self {
+        RunArgs {
+            health_check: Some(health_check),
+            ..self
+        }
+    }
+
+    /// Adds a docker label, like `docker run --label`, e.g. to find the container of a test run
+    /// through `docker ps --filter label=<key>=<value>`.
+    pub fn with_label<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.labels.push((key.into(), value.into()));
+        self
+    }
+
+    /// Adds an OCI annotation, like `docker run --annotation`, for tooling that reads the
+    /// annotations of the container runtime rather than docker labels.
+    ///
+    /// Annotations need a daemon with API version 1.43 or later. Older daemons cannot store them,
+    /// so they are dropped with a warning as they do not affect the container itself.
+    pub fn with_annotation<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.annotations.push((key.into(), value.into()));
+        self
+    }
+
+    /// Modifies the body of the request creating the container right before it is sent to the
+    /// daemon, e.g. to set a field of the Docker Engine API that none of the other methods set:
+    /// `body["HostConfig"]["ShmSize"] = 1_073_741_824.into()`.
+    ///
+    /// This is an escape hatch for rarely used options. It is unstable, as the body depends on
+    /// how the client translates the other options, and only supported by the Http client, as the
+    /// Cli client creates the container through `docker run`.
+    pub fn with_create_modifier<F>(self, modifier: F) -> Self
+    where
+        F: Fn(&mut CreateContainerBody) + Send + Sync + 'static,
+    {
+        RunArgs {
+            create_modifier: Some(CreateModifier(Arc::new(modifier))),
+            ..self
+        }
+    }
+
+    /// Sets the log driver of the container together with its options, e.g. `max-size` for `json-file`.
+    ///
+    /// Keep in mind that most drivers, most notably `none`, prevent the logs from being read back.
+    /// Images that wait for a log message cannot be run with the `none` driver.
+    pub fn with_log_driver<D, I, K, V>(self, driver: D, options: I) -> Self
+    where
+        D: Into<String>,
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        RunArgs {
+            log_config: Some(LogConfig {
+                driver: driver.into(),
+                options: options
+                    .into_iter()
+                    .map(|(key, value)| (key.into(), value.into()))
+                    .collect(),
+            }),
+            ..self
+        }
+    }
+
+    /// Sets the maximum time to wait for the [`ready_conditions`] of the image to be met, instead
+    /// of the [`startup_timeout`] of the image or [`DEFAULT_STARTUP_TIMEOUT`].
+    ///
+    /// [`ready_conditions`]: trait.Image.html#tymethod.ready_conditions
+    /// [`startup_timeout`]: trait.Image.html#method.startup_timeout
+    /// [`DEFAULT_STARTUP_TIMEOUT`]: constant.DEFAULT_STARTUP_TIMEOUT.html
+    pub fn with_startup_timeout(self, startup_timeout: Duration) -> Self {
+        RunArgs {
+            startup_timeout: Some(startup_timeout),
+            ..self
+        }
+    }
+
+    /// Attaches everything the container logged to the panic message if it does not become ready
+    /// within the startup timeout, e.g. to debug a failure on CI that does not keep the container.
+    /// Keep in mind that the message gets as long as the logs.
+    pub fn with_full_logs_on_timeout(self) -> Self {
+        RunArgs {
+            full_logs_on_timeout: true,
+            ..self
+        }
+    }
+
+    /// Sets how often to log which of the [`ready_conditions`] of the image is still pending while
+    /// waiting for the container to become ready.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the interval is zero.
+    ///
+    /// [`ready_conditions`]: trait.Image.html#tymethod.ready_conditions
+    pub fn with_progress_interval(self, progress_interval: Duration) -> Self {
+        assert!(
+            progress_interval > Duration::from_secs(0),
+            "progress interval must not be zero"
+        );
+
+        RunArgs {
+            progress_interval: Some(progress_interval),
+            ..self
+        }
+    }
+
+    /// Mounts the volumes of the given container at the same paths, e.g. to read the data another
+    /// container wrote.
+    pub fn with_volumes_from<T: ToString>(mut self, container_id: T, mode: AccessMode) -> Self {
+        self.volumes_from.push((container_id.to_string(), mode));
+        self
+    }
+
+    /// Mounts a directory or file of the host into the container, in addition to the volumes of
+    /// the image.
+    pub fn with_bind_mount(mut self, bind_mount: BindMount) -> Self {
+        self.bind_mounts.push(bind_mount);
+        self
+    }
+
+    /// Sets whether the container is removed when it is dropped, overriding the `TESTCONTAINERS`
+    /// env variable for this container only.
+    pub fn with_removal(self, removal: RemovalPolicy) -> Self {
+        RunArgs {
+            removal: Some(removal),
+            ..self
+        }
+    }
+
+    /// Removes a container that failed to start or to become ready and starts a new one instead,
+    /// at most the given number of times.
+    ///
+    /// Errors that are bound to happen again, e.g. because the image does not exist, are not
+    /// retried.
+    pub fn with_start_retries(self, start_retries: u32) -> Self {
+        RunArgs {
+            start_retries,
+            ..self
+        }
+    }
+
+    /// Replaces the arguments of the image with the given command.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if [`with_cmd_append`](Self::with_cmd_append) was called before.
+    pub fn with_cmd<I, S>(self, cmd: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        assert!(
+            !matches!(self.cmd, Some(Cmd::Append(_))),
+            "`with_cmd` and `with_cmd_append` are mutually exclusive"
+        );
+
+        RunArgs {
+            cmd: Some(Cmd::Replace(cmd.into_iter().map(Into::into).collect())),
+            ..self
+        }
+    }
+
+    /// Appends the given arguments to the arguments of the image, keeping its defaults.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if [`with_cmd`](Self::with_cmd) was called before.
+    pub fn with_cmd_append<I, S>(self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        assert!(
+            !matches!(self.cmd, Some(Cmd::Replace(_))),
+            "`with_cmd` and `with_cmd_append` are mutually exclusive"
+        );
+
+        RunArgs {
+            cmd: Some(Cmd::Append(args.into_iter().map(Into::into).collect())),
+            ..self
+        }
+    }
+
+    /// Writes the logs of the container to `<dir>/<name>.log` when it is dropped, before it is
+    /// removed or also if it is kept.
+    ///
+    /// The container is named after its id if no name is set through [`with_name`](Self::with_name).
+    /// Writing the logs is best-effort: failures are logged instead of failing the test, also when
+    /// the container is dropped because the test panicked.
+    pub fn with_log_to_file<P: Into<PathBuf>>(self, dir: P) -> Self {
+        RunArgs {
+            log_dir: Some(dir.into()),
+            ..self
+        }
+    }
+
+    /// Sets the given environment variables of the host in the container, skipping those that are
+    /// not set on the host.
+    ///
+    /// The values are read when this method is called.
+    pub fn with_env_passthrough<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for name in names {
+            let name = name.as_ref();
+
+            if let Some(value) = env::Os::get_env_value(name) {
+                self.env_vars.push((name.to_owned(), value));
+            }
+        }
+
+        self
+    }
+
     pub(crate) fn network(&self) -> Option<String> {
         self.network.clone()
     }
 
+    pub(crate) fn existing_network(&self) -> bool {
+        self.existing_network
+    }
+
+    pub(crate) fn network_mtu(&self) -> Option<u32> {
+        self.network_mtu
+    }
+
+    pub(crate) fn internal_network(&self) -> bool {
+        self.internal_network
+    }
+
     pub(crate) fn name(&self) -> Option<String> {
         self.name.clone()
     }
@@ -59,4 +518,469 @@ impl RunArgs {
     pub(crate) fn ports(&self) -> Option<Vec<Port>> {
         self.ports.clone()
     }
+
+    pub(crate) fn memory(&self) -> Option<u64> {
+        self.memory
+    }
+
+    pub(crate) fn memory_swap(&self) -> Option<i64> {
+        self.memory_swap
+    }
+
+    pub(crate) fn oom_kill_disabled(&self) -> bool {
+        self.oom_kill_disabled
+    }
+
+    pub(crate) fn oom_score_adj(&self) -> Option<i32> {
+        self.oom_score_adj
+    }
+
+    pub(crate) fn gpus(&self) -> Option<Gpus> {
+        self.gpus
+    }
+
+    pub(crate) fn isolation(&self) -> Option<Isolation> {
+        self.isolation
+    }
+
+    pub(crate) fn security_opts(&self) -> &[String] {
+        &self.security_opts
+    }
+
+    pub(crate) fn userns_mode(&self) -> Option<String> {
+        self.userns_mode.clone()
+    }
+
+    pub(crate) fn storage_opts(&self) -> &[(String, String)] {
+        &self.storage_opts
+    }
+
+    pub(crate) fn health_check(&self) -> Option<&HealthCheck> {
+        self.health_check.as_ref()
+    }
+
+    pub(crate) fn labels(&self) -> &[(String, String)] {
+        &self.labels
+    }
+
+    pub(crate) fn annotations(&self) -> &[(String, String)] {
+        &self.annotations
+    }
+
+    pub(crate) fn without_annotations(self) -> Self {
+        RunArgs {
+            annotations: Vec::new(),
+            ..self
+        }
+    }
+
+    pub(crate) fn create_modifier(&self) -> Option<&CreateModifier> {
+        self.create_modifier.as_ref()
+    }
+
+    pub(crate) fn log_config(&self) -> Option<LogConfig> {
+        self.log_config.clone()
+    }
+
+    pub(crate) fn full_logs_on_timeout(&self) -> bool {
+        self.full_logs_on_timeout
+    }
+
+    pub(crate) fn startup_timeout(&self) -> Option<Duration> {
+        self.startup_timeout
+    }
+
+    pub(crate) fn env_vars(&self) -> &[(String, String)] {
+        &self.env_vars
+    }
+
+    pub(crate) fn progress_interval(&self) -> Duration {
+        self.progress_interval.unwrap_or(DEFAULT_PROGRESS_INTERVAL)
+    }
+
+    /// Returns the containers to mount the volumes of, in the `<container>:<mode>` format of
+    /// docker.
+    pub(crate) fn volumes_from(&self) -> Vec<String> {
+        self.volumes_from
+            .iter()
+            .map(|(container, mode)| {
+                let mode = match mode {
+                    AccessMode::ReadWrite => "rw",
+                    AccessMode::ReadOnly => "ro",
+                };
+
+                format!("{}:{}", container, mode)
+            })
+            .collect()
+    }
+
+    /// Returns the bind mounts in the `<source>:<target>:<options>` format of docker.
+    pub(crate) fn bind_mounts(&self) -> Vec<String> {
+        self.bind_mounts.iter().map(BindMount::to_bind).collect()
+    }
+
+    pub(crate) fn start_retries(&self) -> u32 {
+        self.start_retries
+    }
+
+    /// Returns the removal policy of the container, falling back to the given command of the
+    /// client.
+    pub(crate) fn removal(&self, command: env::Command) -> RemovalPolicy {
+        self.removal.unwrap_or_else(|| command.into())
+    }
+
+    /// Returns the file the logs of the container with the given id are written to.
+    pub(crate) fn log_file(&self, id: &str) -> Option<PathBuf> {
+        let name = self.name.as_deref().unwrap_or(id);
+
+        self.log_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.log", name)))
+    }
+
+    /// Returns the command the container is started with, based on the arguments of the image.
+    pub(crate) fn cmd<I: Image>(&self, image: &I) -> Vec<String> {
+        match &self.cmd {
+            Some(Cmd::Replace(cmd)) => cmd.clone(),
+            Some(Cmd::Append(args)) => image.args().into_iter().chain(args.clone()).collect(),
+            None => image.args().into_iter().collect(),
+        }
+    }
+
+    /// Panics if the memory limits contradict each other, which the daemon would reject with a
+    /// message that does not name the offending option.
+    pub(crate) fn assert_valid_memory(&self) {
+        if let Some(memory) = self.memory {
+            assert!(
+                memory >= MIN_MEMORY,
+                "memory limit of {} bytes is below the minimum of {} bytes the daemon accepts",
+                memory,
+                MIN_MEMORY
+            );
+        }
+
+        if let Some(memory_swap) = self.memory_swap {
+            assert!(
+                memory_swap >= -1,
+                "memory swap limit must be at least -1 for unlimited swap, got {}",
+                memory_swap
+            );
+
+            let memory = self.memory.unwrap_or_else(|| {
+                panic!("`RunArgs::with_memory_swap` needs a memory limit set through `RunArgs::with_memory`")
+            });
+
+            assert!(
+                memory_swap == -1 || memory_swap as u64 >= memory,
+                "memory swap limit of {} bytes is below the memory limit of {} bytes, but it limits memory and swap together, e.g. pass {} to allow as much swap as memory",
+                memory_swap,
+                memory,
+                memory * 2
+            );
+        }
+
+        assert!(
+            !self.oom_kill_disabled || self.memory.is_some(),
+            "`RunArgs::with_oom_kill_disabled` needs a memory limit set through `RunArgs::with_memory`, the container could exhaust the memory of the host otherwise"
+        );
+    }
+
+    /// Panics if the log driver prevents the ready conditions of the image from being evaluated.
+    pub(crate) fn assert_logs_readable<I: Image>(&self, image: &I) {
+        let log_driver_disabled = self
+            .log_config
+            .as_ref()
+            .is_some_and(|config| config.driver == "none");
+        let waits_for_logs = image.ready_conditions().iter().any(|condition| {
+            matches!(
+                condition,
+                WaitFor::StdOutMessage { .. }
+                    | WaitFor::StdErrMessage { .. }
+                    | WaitFor::OrderedMessages { .. }
+                    | WaitFor::LineCount { .. }
+            )
+        });
+
+        assert!(
+            !(log_driver_disabled && waits_for_logs),
+            "image {} waits for a log message, which is never found with the `none` log driver",
+            image.descriptor()
+        );
+    }
+}
+
+/// The GPUs a container can access.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gpus {
+    All,
+    Count(u32),
+}
+
+/// The isolation technology of a Windows container.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Isolation {
+    /// The default of the daemon, `process` on Windows Server and `hyperv` on Windows 10 and 11.
+    Default,
+    /// Shares the kernel of the host.
+    Process,
+    /// Runs in a lightweight Hyper-V virtual machine.
+    Hyperv,
+}
+
+impl Isolation {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Isolation::Default => "default",
+            Isolation::Process => "process",
+            Isolation::Hyperv => "hyperv",
+        }
+    }
+}
+
+/// Whether a container may write to the volumes it mounts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccessMode {
+    ReadWrite,
+    ReadOnly,
+}
+
+/// A directory or file of the host mounted into a container, see [`RunArgs::with_bind_mount`].
+///
+/// [`RunArgs::with_bind_mount`]: struct.RunArgs.html#method.with_bind_mount
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindMount {
+    source: String,
+    target: String,
+    mode: AccessMode,
+    consistency: Option<Consistency>,
+    propagation: Option<Propagation>,
+}
+
+impl BindMount {
+    /// Mounts the given absolute path of the host at the given absolute path in the container,
+    /// writable by default.
+    pub fn new<S: Into<String>, T: Into<String>>(source: S, target: T) -> Self {
+        BindMount {
+            source: source.into(),
+            target: target.into(),
+            mode: AccessMode::ReadWrite,
+            consistency: None,
+            propagation: None,
+        }
+    }
+
+    pub fn with_access_mode(self, mode: AccessMode) -> Self {
+        BindMount { mode, ..self }
+    }
+
+    /// Sets how changes are synchronized between host and container, which only Docker Desktop
+    /// for macOS takes into account.
+    pub fn with_consistency(self, consistency: Consistency) -> Self {
+        BindMount {
+            consistency: Some(consistency),
+            ..self
+        }
+    }
+
+    /// Sets whether mounts below the source or target are replicated to the other side.
+    pub fn with_propagation(self, propagation: Propagation) -> Self {
+        BindMount {
+            propagation: Some(propagation),
+            ..self
+        }
+    }
+
+    fn to_bind(&self) -> String {
+        let mut options = vec![match self.mode {
+            AccessMode::ReadWrite => "rw",
+            AccessMode::ReadOnly => "ro",
+        }];
+        options.extend(self.consistency.map(|consistency| consistency.as_str()));
+        options.extend(self.propagation.map(|propagation| propagation.as_str()));
+
+        format!("{}:{}:{}", self.source, self.target, options.join(","))
+    }
+}
+
+/// A command the daemon periodically runs inside a container to determine its health, see
+/// [`RunArgs::with_health_check`].
+///
+/// [`RunArgs::with_health_check`]: struct.RunArgs.html#method.with_health_check
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthCheck {
+    cmd: String,
+    interval: Option<Duration>,
+    retries: Option<u32>,
+}
+
+impl HealthCheck {
+    /// Checks the health by running the given shell command, which exits with `0` if the
+    /// container is healthy.
+    pub fn new<C: Into<String>>(cmd: C) -> Self {
+        HealthCheck {
+            cmd: cmd.into(),
+            interval: None,
+            retries: None,
+        }
+    }
+
+    /// Sets the time between two checks, 30 seconds by default.
+    pub fn with_interval(self, interval: Duration) -> Self {
+        HealthCheck {
+            interval: Some(interval),
+            ..self
+        }
+    }
+
+    /// Sets the number of consecutive failed checks after which the container is unhealthy, 3 by
+    /// default.
+    pub fn with_retries(self, retries: u32) -> Self {
+        HealthCheck {
+            retries: Some(retries),
+            ..self
+        }
+    }
+
+    pub(crate) fn to_args(&self) -> Vec<String> {
+        let mut args = vec![format!("--health-cmd={}", self.cmd)];
+        args.extend(
+            self.interval
+                .map(|interval| format!("--health-interval={}ms", interval.as_millis())),
+        );
+        args.extend(
+            self.retries
+                .map(|retries| format!("--health-retries={}", retries)),
+        );
+
+        args
+    }
+
+    /// Returns the `Healthcheck` of the request creating a container, with durations in
+    /// nanoseconds as the Docker Engine API expects them.
+    pub(crate) fn to_body(&self) -> serde_json::Value {
+        let mut body = serde_json::json!({ "Test": ["CMD-SHELL", self.cmd] });
+        if let Some(interval) = self.interval {
+            body["Interval"] = (interval.as_nanos() as u64).into();
+        }
+        if let Some(retries) = self.retries {
+            body["Retries"] = retries.into();
+        }
+
+        body
+    }
+}
+
+/// The consistency of a bind mount on Docker Desktop for macOS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Consistency {
+    /// Host and container always see the same contents.
+    Consistent,
+    /// The host is authoritative, updates may be visible in the container with a delay.
+    Cached,
+    /// The container is authoritative, updates may be visible on the host with a delay.
+    Delegated,
+}
+
+impl Consistency {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Consistency::Consistent => "consistent",
+            Consistency::Cached => "cached",
+            Consistency::Delegated => "delegated",
+        }
+    }
+}
+
+/// The propagation of mounts below a bind mount, see
+/// <https://docs.docker.com/storage/bind-mounts/#configure-bind-propagation>.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Propagation {
+    Private,
+    Rprivate,
+    Shared,
+    Rshared,
+    Slave,
+    Rslave,
+}
+
+impl Propagation {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Propagation::Private => "private",
+            Propagation::Rprivate => "rprivate",
+            Propagation::Shared => "shared",
+            Propagation::Rshared => "rshared",
+            Propagation::Slave => "slave",
+            Propagation::Rslave => "rslave",
+        }
+    }
+}
+
+/// Whether a container is removed when it is dropped, see [`RunArgs::with_removal`].
+///
+/// [`RunArgs::with_removal`]: struct.RunArgs.html#method.with_removal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemovalPolicy {
+    /// Remove the container together with its volumes.
+    Remove,
+    /// Keep the container running, e.g. to inspect it after the test.
+    Keep,
+    /// Keep the container running if it is dropped while its thread panics, e.g. because an
+    /// assertion of the test failed, and remove it otherwise.
+    KeepOnFailure,
+}
+
+impl RemovalPolicy {
+    pub(crate) fn should_remove(self) -> bool {
+        match self {
+            RemovalPolicy::Remove => true,
+            RemovalPolicy::Keep => false,
+            RemovalPolicy::KeepOnFailure => !std::thread::panicking(),
+        }
+    }
+}
+
+impl From<env::Command> for RemovalPolicy {
+    fn from(command: env::Command) -> Self {
+        match command {
+            env::Command::Remove => RemovalPolicy::Remove,
+            env::Command::Keep => RemovalPolicy::Keep,
+        }
+    }
+}
+
+/// How the command of a container relates to the arguments of its image.
+#[derive(Debug, Clone, PartialEq)]
+enum Cmd {
+    Replace(Vec<String>),
+    Append(Vec<String>),
+}
+
+/// The JSON body of the request creating a container, see `POST /containers/create` in
+/// <https://docs.docker.com/engine/api/latest/>.
+pub type CreateContainerBody = serde_json::Value;
+
+/// Modifies the body of the request creating a container, see [`RunArgs::with_create_modifier`].
+///
+/// [`RunArgs::with_create_modifier`]: struct.RunArgs.html#method.with_create_modifier
+#[derive(Clone)]
+pub(crate) struct CreateModifier(Arc<dyn Fn(&mut CreateContainerBody) + Send + Sync>);
+
+impl CreateModifier {
+    pub fn apply(&self, body: &mut CreateContainerBody) {
+        (self.0)(body)
+    }
+}
+
+impl fmt::Debug for CreateModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CreateModifier")
+    }
+}
+
+/// The log driver of a container, see <https://docs.docker.com/config/containers/logging/configure/>.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LogConfig {
+    pub driver: String,
+    pub options: Vec<(String, String)>,
 }
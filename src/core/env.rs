@@ -1,4 +1,7 @@
-use std::str::FromStr;
+use std::{
+    str::FromStr,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
 
 /// Lookup and parse the command specified through the `TESTCONTAINERS` env variable.
 pub fn command<E>() -> Option<Command>
@@ -27,14 +30,25 @@ pub struct Os;
 
 impl GetEnvValue for Os {
     fn get_env_value(key: &str) -> Option<String> {
+        let _guard = lock();
+
         ::std::env::var(key).ok()
     }
 }
 
+/// Serializes reading the environment with changing it, which the `Http` client does while it
+/// hands TLS certificates to shiplift. Readers outside of this crate are not covered.
+static LOCK: Mutex<()> = Mutex::new(());
+
+pub(crate) fn lock() -> MutexGuard<'static, ()> {
+    LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 /// The commands available to the `TESTCONTAINERS` env variable.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
 pub enum Command {
     Keep,
+    #[default]
     Remove,
 }
 
@@ -53,12 +67,6 @@ impl FromStr for Command {
     }
 }
 
-impl Default for Command {
-    fn default() -> Self {
-        Command::Remove
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
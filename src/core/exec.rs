@@ -0,0 +1,128 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Represents a command that is executed inside a running container.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecCommand {
+    pub cmd: Vec<String>,
+}
+
+impl ExecCommand {
+    pub fn new<I, S>(cmd: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        ExecCommand {
+            cmd: cmd.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Creates a command that runs the given script through `sh -c`.
+    pub fn shell<S: Into<String>>(script: S) -> Self {
+        ExecCommand {
+            cmd: vec!["sh".to_owned(), "-c".to_owned(), script.into()],
+        }
+    }
+
+    /// Creates a command that checks the given shell condition every `interval` until it holds,
+    /// and fails if it did not hold within `timeout`. Relies on `timeout` being available inside
+    /// the container, as it is with coreutils and busybox.
+    pub(crate) fn shell_until<S: AsRef<str>>(
+        condition: S,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Self {
+        let script = format!(
+            "until {}; do sleep {}; done",
+            condition.as_ref(),
+            interval.as_secs_f64()
+        );
+
+        ExecCommand::shell(format!(
+            "timeout {} sh -c {}",
+            timeout.as_secs(),
+            shell_quote(&script)
+        ))
+    }
+}
+
+/// The result of executing an [`ExecCommand`] inside a container.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecOutput {
+    pub exit_code: i64,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl ExecOutput {
+    pub fn success(&self) -> bool {
+        self.exit_code == 0
+    }
+}
+
+/// A chunk of the output of a command streamed by [`ContainerAsync::exec_streaming`].
+///
+/// Chunks are passed on as the daemon sends them, they do not necessarily end at line breaks.
+///
+/// [`ContainerAsync::exec_streaming`]: struct.ContainerAsync.html#method.exec_streaming
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogChunk {
+    StdOut(Vec<u8>),
+    StdErr(Vec<u8>),
+}
+
+/// Provides the exit code of a command streamed by [`ContainerAsync::exec_streaming`].
+///
+/// [`ContainerAsync::exec_streaming`]: struct.ContainerAsync.html#method.exec_streaming
+#[derive(Debug, Clone, Default)]
+pub struct ExecHandle {
+    exit_code: Arc<Mutex<Option<i64>>>,
+}
+
+impl ExecHandle {
+    /// Returns the exit code of the command once its output stream ended, `None` before.
+    pub fn exit_code(&self) -> Option<i64> {
+        *self.exit_code.lock().expect("exit code to not be poisoned")
+    }
+
+    pub(crate) fn set_exit_code(&self, exit_code: i64) {
+        *self.exit_code.lock().expect("exit code to not be poisoned") = Some(exit_code);
+    }
+}
+
+/// Quotes `arg` for a POSIX shell unless it only consists of characters that need no quoting.
+pub(crate) fn shell_quote(arg: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_=:/.,@%+".contains(c);
+
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        arg.to_owned()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shell_until_should_bound_the_loop() {
+        let command = ExecCommand::shell_until(
+            "grep -q 'ready' /log",
+            Duration::from_millis(100),
+            Duration::from_secs(30),
+        );
+
+        assert_eq!(
+            command.cmd,
+            vec![
+                "sh",
+                "-c",
+                r"timeout 30 sh -c 'until grep -q '\''ready'\'' /log; do sleep 0.1; done'"
+            ]
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use serde::Deserialize;
+use std::fmt::Write;
+
+/// The number of log lines of a container that are included when it is not healthy.
+pub(crate) const RECENT_LOG_LINES: usize = 50;
+
+/// The health of a container as reported by the daemon, i.e. `State.Health` of `docker inspect`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct Health {
+    /// One of `starting`, `healthy` or `unhealthy`.
+    pub status: String,
+    /// The most recent checks, oldest first.
+    #[serde(default)]
+    pub log: Vec<HealthLog>,
+}
+
+/// The result of a single health check.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct HealthLog {
+    pub exit_code: i64,
+    pub output: String,
+}
+
+impl Health {
+    pub(crate) fn is_healthy(&self) -> bool {
+        self.status == "healthy"
+    }
+}
+
+/// Parses `State.Health` of the container, which is `null` or absent if the container has no
+/// health check.
+pub(crate) fn parse_health(state: &serde_json::Value) -> Option<Health> {
+    match state.get("Health") {
+        Some(health) if !health.is_null() => Some(
+            serde_json::from_value(health.clone())
+                .unwrap_or_else(|e| panic!("failed to parse health {}: {}", health, e)),
+        ),
+        _ => None,
+    }
+}
+
+/// Describes why the container is not healthy, including the output of its last health checks
+/// and its recent logs.
+pub(crate) fn describe_unhealthy(id: &str, health: Option<&Health>, logs: &str) -> String {
+    let mut description = match health {
+        Some(health) => {
+            let mut description = format!(
+                "container {} is not healthy but {}, last health checks:\n",
+                id, health.status
+            );
+            for check in &health.log {
+                let _ = writeln!(
+                    description,
+                    "  exit code {}: {}",
+                    check.exit_code,
+                    check.output.trim()
+                );
+            }
+
+            description
+        }
+        None => format!("container {} has no health check\n", id),
+    };
+
+    let _ = write!(description, "recent logs:\n{}", logs);
+
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_describe_output_of_failed_health_checks() {
+        let state = serde_json::json!({
+            "Status": "running",
+            "Health": {
+                "Status": "unhealthy",
+                "FailingStreak": 1,
+                "Log": [{
+                    "Start": "2023-11-20T10:00:00.000000000Z",
+                    "End": "2023-11-20T10:00:00.100000000Z",
+                    "ExitCode": 1,
+                    "Output": "probe failed\n"
+                }]
+            }
+        });
+        let health = parse_health(&state).unwrap();
+
+        assert!(!health.is_healthy());
+        assert_eq!(
+            describe_unhealthy("abc", Some(&health), "server started\n"),
+            "container abc is not healthy but unhealthy, last health checks:\n  exit code 1: probe failed\nrecent logs:\nserver started\n"
+        );
+        assert_eq!(
+            parse_health(&serde_json::json!({ "Status": "running", "Health": null })),
+            None
+        );
+    }
+}
@@ -1,4 +1,8 @@
-use std::{env::var, time::Duration};
+use crate::core::{
+    ports::Ports, CopyToContainer, ExecCommand, HttpWaitStrategy, StateWaitStrategy,
+};
+use shiplift::rep::ContainerDetails;
+use std::{collections::HashMap, env::var, fmt, time::Duration};
 
 /// Represents a docker image.
 ///
@@ -21,11 +25,11 @@ where
     /// There are a couple of things regarding the arguments of images:
     ///
     /// 1. Similar to the Default implementation of an Image, the Default instance
-    /// of its arguments should be meaningful!
+    ///    of its arguments should be meaningful!
     /// 2. Implementations should be conservative about which arguments they expose. Many times,
-    /// users will either go with the default arguments or just override one or two. When defining
-    /// the arguments of your image, consider that the whole purpose is to facilitate integration
-    /// testing. Only expose those that actually make sense for this case.
+    ///    users will either go with the default arguments or just override one or two. When defining
+    ///    the arguments of your image, consider that the whole purpose is to facilitate integration
+    ///    testing. Only expose those that actually make sense for this case.
     type Args;
 
     /// A type representing the environment variables for an Image.
@@ -33,11 +37,11 @@ where
     /// There are a couple of things regarding the arguments of images:
     ///
     /// 1. Similar to the Default implementation of an Image, the Default instance
-    /// of its environment variables should be meaningful!
+    ///    of its environment variables should be meaningful!
     /// 2. Implementations should be conservative about which environment variables they expose. Many times,
-    /// users will either go with the default ones or just override one or two. When defining
-    /// the environment variables of your image, consider that the whole purpose is to facilitate integration
-    /// testing. Only expose those that actually make sense for this case.
+    ///    users will either go with the default ones or just override one or two. When defining
+    ///    the environment variables of your image, consider that the whole purpose is to facilitate integration
+    ///    testing. Only expose those that actually make sense for this case.
     type EnvVars;
 
     /// A type representing the volumes for an Image.
@@ -45,11 +49,11 @@ where
     /// There are a couple of things regarding the arguments of images:
     ///
     /// 1. Similar to the Default implementation of an Image, the Default instance
-    /// of its volumes should be meaningful!
+    ///    of its volumes should be meaningful!
     /// 2. Implementations should be conservative about which volumes they expose. Many times,
-    /// users will either go with the default ones or just override one or two. When defining
-    /// the volumes of your image, consider that the whole purpose is to facilitate integration
-    /// testing. Only expose those that actually make sense for this case.
+    ///    users will either go with the default ones or just override one or two. When defining
+    ///    the volumes of your image, consider that the whole purpose is to facilitate integration
+    ///    testing. Only expose those that actually make sense for this case.
     type Volumes;
 
     /// A type representing the entrypoint for an Image.
@@ -96,6 +100,227 @@ where
     fn entrypoint(&self) -> Option<String> {
         None
     }
+
+    /// Returns the commands that need to be executed inside the container once it is ready.
+    ///
+    /// The commands are executed **in the order** they are returned, after all [`ready_conditions`]
+    /// have been met. Use this for initialization steps that cannot be expressed through arguments
+    /// or environment variables, like provisioning a database through its REST API.
+    /// Starting the container panics if one of the commands exits with a non-zero code.
+    ///
+    /// [`ready_conditions`]: trait.Image.html#tymethod.ready_conditions
+    fn exec_after_start(&self, _cs: ContainerState) -> Vec<ExecCommand> {
+        vec![]
+    }
+
+    /// Returns whether the [`ready_conditions`] need to be met again once the commands of
+    /// [`exec_after_start`] ran, e.g. because they restart the service to apply a config.
+    ///
+    /// The conditions are checked like on startup, with a fresh startup timeout. Messages logged
+    /// before the commands ran meet message conditions right away, which is why images that
+    /// recheck their readiness wait for HTTP or state conditions instead.
+    ///
+    /// [`ready_conditions`]: trait.Image.html#tymethod.ready_conditions
+    /// [`exec_after_start`]: trait.Image.html#method.exec_after_start
+    fn recheck_readiness_after_exec(&self) -> bool {
+        false
+    }
+
+    /// Returns the maximum time to wait for the [`ready_conditions`] of this image to be met.
+    ///
+    /// Slow images should declare a timeout, so users don't have to configure one every time.
+    /// A timeout set through [`RunArgs::with_startup_timeout`] takes precedence. Without either,
+    /// [`DEFAULT_STARTUP_TIMEOUT`] applies.
+    ///
+    /// [`ready_conditions`]: trait.Image.html#tymethod.ready_conditions
+    /// [`RunArgs::with_startup_timeout`]: struct.RunArgs.html#method.with_startup_timeout
+    /// [`DEFAULT_STARTUP_TIMEOUT`]: constant.DEFAULT_STARTUP_TIMEOUT.html
+    fn startup_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Returns the files that need to be copied into the container before it is started.
+    ///
+    /// Use this to provide configuration files the image only reads on startup.
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        vec![]
+    }
+
+    /// Returns names for the internal ports of this image, e.g. `"http" -> 8080`.
+    ///
+    /// Named ports can be resolved to their host port through [`get_host_port_by_name`].
+    ///
+    /// [`get_host_port_by_name`]: struct.Container.html#method.get_host_port_by_name
+    fn named_ports(&self) -> HashMap<String, u16> {
+        HashMap::new()
+    }
+
+    /// Returns additional entries for `/etc/hosts` of the container, mapping a hostname to an IP.
+    ///
+    /// The special address `host-gateway` resolves to the host, which allows the container to
+    /// reach servers started by the test itself.
+    fn hosts(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    /// Returns internal ports that are published in addition to the ports the image declares.
+    ///
+    /// Use this for ports a server only starts listening on at runtime. Like all other ports,
+    /// these are only published if no explicit port mapping is passed through [`RunArgs`].
+    ///
+    /// [`RunArgs`]: struct.RunArgs.html
+    fn expose_ports(&self) -> Vec<u16> {
+        vec![]
+    }
+
+    /// Returns ports that need to be published on a fixed host port, e.g. because the server
+    /// advertises its own port number to clients.
+    ///
+    /// These are always published, even if an explicit port mapping is passed through
+    /// [`RunArgs`]. Containers using the same host ports cannot run at the same time.
+    ///
+    /// [`RunArgs`]: struct.RunArgs.html
+    fn mapped_ports(&self) -> Vec<Port> {
+        vec![]
+    }
+
+    /// Returns whether the container needs to run in privileged mode, e.g. because it runs
+    /// containers itself.
+    fn privileged(&self) -> bool {
+        false
+    }
+
+    /// Returns the directories in the container that are mounted as `tmpfs`, e.g. `/run`.
+    fn tmpfs(&self) -> Vec<String> {
+        vec![]
+    }
+}
+
+/// Resolves the name of a port declared in [`Image::named_ports`] to the internal port.
+///
+/// # Panics
+///
+/// This function panics if the image does not declare a port with the given name.
+///
+/// [`Image::named_ports`]: trait.Image.html#method.named_ports
+pub(crate) fn internal_port_by_name<I: Image>(image: &I, name: &str) -> u16 {
+    image.named_ports().get(name).cloned().unwrap_or_else(|| {
+        panic!(
+            "image {} does not declare a port named {}",
+            image.descriptor(),
+            name
+        )
+    })
+}
+
+/// The state of a started container, as seen by [`Image::exec_after_start`] and
+/// [`WaitFor::State`].
+///
+/// [`Image::exec_after_start`]: trait.Image.html#method.exec_after_start
+/// [`WaitFor::State`]: enum.WaitFor.html#variant.State
+#[derive(Debug)]
+pub struct ContainerState {
+    ports: Ports,
+    status: String,
+    running: bool,
+    pid: u64,
+    exit_code: u64,
+}
+
+/// The lifecycle status of a container, as reported by the daemon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContainerStatus {
+    Created,
+    Running,
+    Paused,
+    Restarting,
+    Removing,
+    Exited,
+    Dead,
+    /// A status this version does not know, e.g. one introduced by a newer daemon, or one only
+    /// reported by Podman.
+    Unknown(String),
+}
+
+impl ContainerStatus {
+    pub(crate) fn parse(status: &str) -> Self {
+        match status {
+            "created" => ContainerStatus::Created,
+            "running" => ContainerStatus::Running,
+            "paused" => ContainerStatus::Paused,
+            "restarting" => ContainerStatus::Restarting,
+            "removing" => ContainerStatus::Removing,
+            "exited" => ContainerStatus::Exited,
+            "dead" => ContainerStatus::Dead,
+            other => ContainerStatus::Unknown(other.to_owned()),
+        }
+    }
+}
+
+impl ContainerState {
+    #[cfg(test)]
+    pub(crate) fn new(ports: Ports) -> Self {
+        Self {
+            ports,
+            status: String::new(),
+            running: false,
+            pid: 0,
+            exit_code: 0,
+        }
+    }
+
+    pub(crate) fn from_details(details: ContainerDetails) -> Self {
+        Self {
+            ports: details
+                .network_settings
+                .ports
+                .map(Ports::new)
+                .unwrap_or_default(),
+            status: details.state.status,
+            running: details.state.running,
+            pid: details.state.pid,
+            exit_code: details.state.exit_code,
+        }
+    }
+
+    /// Returns the status docker reports for the container, e.g. `running` or `exited`.
+    pub fn status(&self) -> &str {
+        &self.status
+    }
+
+    /// Returns the status docker reports for the container as [`ContainerStatus`].
+    ///
+    /// [`ContainerStatus`]: enum.ContainerStatus.html
+    pub fn container_status(&self) -> ContainerStatus {
+        ContainerStatus::parse(&self.status)
+    }
+
+    /// Returns whether the main process of the container is running.
+    pub fn running(&self) -> bool {
+        self.running
+    }
+
+    /// Returns the process id of the main process of the container on the host, which is 0 if
+    /// the process is not running.
+    pub fn pid(&self) -> u64 {
+        self.pid
+    }
+
+    /// Returns the exit code of the main process of the container, which is 0 while it runs.
+    pub fn exit_code(&self) -> u64 {
+        self.exit_code
+    }
+
+    /// Returns the mapped host port for an internal port of the container.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the given port is not mapped.
+    pub fn host_port(&self, internal_port: u16) -> u16 {
+        self.ports
+            .map_to_host_port(internal_port)
+            .unwrap_or_else(|| panic!("container does not expose port {}", internal_port))
+    }
 }
 
 /// Represents a port mapping between a local port and the internal port of a container.
@@ -116,6 +341,32 @@ pub enum WaitFor {
     StdErrMessage { message: String },
     /// Wait for a certain amount of time.
     Duration { length: Duration },
+    /// Wait for an HTTP endpoint of the container to respond as expected.
+    Http(HttpWaitStrategy),
+    /// Wait for several messages to appear in the given order on one stream of the container's
+    /// logs, e.g. for the phases of a staged startup.
+    OrderedMessages {
+        source: LogSource,
+        messages: Vec<String>,
+    },
+    /// Wait for one stream of the container's logs to contain at least the given number of lines,
+    /// e.g. for tools that report their progress line by line.
+    ///
+    /// The `timeout` bounds this condition in addition to the startup timeout of the container.
+    LineCount {
+        source: LogSource,
+        count: usize,
+        timeout: Option<Duration>,
+    },
+    /// Wait for the inspected state of the container to satisfy a custom predicate.
+    State(StateWaitStrategy),
+}
+
+/// The stream of the container's logs a [`WaitFor`] condition looks at.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LogSource {
+    StdOut,
+    StdErr,
 }
 
 impl WaitFor {
@@ -131,6 +382,60 @@ impl WaitFor {
         }
     }
 
+    pub fn messages_in_order_on_stdout<I, S>(messages: I) -> WaitFor
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        WaitFor::OrderedMessages {
+            source: LogSource::StdOut,
+            messages: messages.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn messages_in_order_on_stderr<I, S>(messages: I) -> WaitFor
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        WaitFor::OrderedMessages {
+            source: LogSource::StdErr,
+            messages: messages.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn lines_on_stdout(count: usize) -> WaitFor {
+        WaitFor::LineCount {
+            source: LogSource::StdOut,
+            count,
+            timeout: None,
+        }
+    }
+
+    pub fn lines_on_stderr(count: usize) -> WaitFor {
+        WaitFor::LineCount {
+            source: LogSource::StdErr,
+            count,
+            timeout: None,
+        }
+    }
+
+    pub fn http(strategy: HttpWaitStrategy) -> WaitFor {
+        WaitFor::Http(strategy)
+    }
+
+    /// Waits for the given predicate to hold for the inspected state of the container, see
+    /// [`StateWaitStrategy::new`].
+    ///
+    /// [`StateWaitStrategy::new`]: struct.StateWaitStrategy.html#method.new
+    pub fn state<D, F>(description: D, predicate: F) -> WaitFor
+    where
+        D: Into<String>,
+        F: Fn(&ContainerState) -> bool + Send + Sync + 'static,
+    {
+        WaitFor::State(StateWaitStrategy::new(description, predicate))
+    }
+
     pub fn seconds(length: u64) -> WaitFor {
         WaitFor::Duration {
             length: Duration::from_secs(length),
@@ -157,8 +462,132 @@ impl WaitFor {
     }
 }
 
+impl fmt::Display for WaitFor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitFor::Nothing => write!(f, "nothing"),
+            WaitFor::StdOutMessage { message } => write!(f, "message {:?} on stdout", message),
+            WaitFor::StdErrMessage { message } => write!(f, "message {:?} on stderr", message),
+            WaitFor::Duration { length } => write!(f, "{:?} to pass", length),
+            WaitFor::Http(strategy) => write!(f, "{}", strategy),
+            WaitFor::State(strategy) => write!(f, "{}", strategy),
+            WaitFor::OrderedMessages { source, messages } => {
+                write!(f, "messages {:?} in order on {}", messages, source)
+            }
+            WaitFor::LineCount { source, count, .. } => {
+                write!(f, "{} lines on {}", count, source)
+            }
+        }
+    }
+}
+
+impl fmt::Display for LogSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogSource::StdOut => f.write_str("stdout"),
+            LogSource::StdErr => f.write_str("stderr"),
+        }
+    }
+}
+
 impl From<(u16, u16)> for Port {
     fn from((local, internal): (u16, u16)) -> Self {
         Port { local, internal }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct NamedPortsImage;
+
+    impl Image for NamedPortsImage {
+        type Args = Vec<String>;
+        type EnvVars = HashMap<String, String>;
+        type Volumes = HashMap<String, String>;
+        type EntryPoint = std::convert::Infallible;
+
+        fn descriptor(&self) -> String {
+            "named-ports:latest".to_owned()
+        }
+
+        fn ready_conditions(&self) -> Vec<WaitFor> {
+            vec![]
+        }
+
+        fn args(&self) -> Self::Args {
+            vec![]
+        }
+
+        fn env_vars(&self) -> Self::EnvVars {
+            HashMap::new()
+        }
+
+        fn volumes(&self) -> Self::Volumes {
+            HashMap::new()
+        }
+
+        fn with_args(self, _: Self::Args) -> Self {
+            self
+        }
+
+        fn named_ports(&self) -> HashMap<String, u16> {
+            let mut ports = HashMap::new();
+            ports.insert("http".to_owned(), 8080);
+            ports.insert("admin".to_owned(), 9090);
+
+            ports
+        }
+    }
+
+    #[test]
+    fn should_resolve_named_port_to_host_port() {
+        let mut mapping = HashMap::new();
+        mapping.insert(
+            "8080/tcp".to_owned(),
+            Some(vec![vec![("HostPort".to_owned(), "33078".to_owned())]
+                .into_iter()
+                .collect()]),
+        );
+        let state = ContainerState::new(Ports::new(mapping));
+
+        let internal_port = internal_port_by_name(&NamedPortsImage, "http");
+
+        assert_eq!(internal_port, 8080);
+        assert_eq!(state.host_port(internal_port), 33078);
+    }
+
+    #[test]
+    #[should_panic(expected = "image named-ports:latest does not declare a port named grpc")]
+    fn should_panic_on_unknown_port_name() {
+        internal_port_by_name(&NamedPortsImage, "grpc");
+    }
+
+    #[test]
+    fn should_describe_wait_conditions() {
+        assert_eq!(
+            WaitFor::message_on_stderr("started").to_string(),
+            r#"message "started" on stderr"#
+        );
+        assert_eq!(
+            WaitFor::messages_in_order_on_stdout(vec!["one", "two"]).to_string(),
+            r#"messages ["one", "two"] in order on stdout"#
+        );
+        assert_eq!(WaitFor::lines_on_stderr(3).to_string(), "3 lines on stderr");
+        assert_eq!(
+            WaitFor::http(HttpWaitStrategy::new(8080, "/health")).to_string(),
+            "GET /health on port 8080 to respond"
+        );
+    }
+
+    #[test]
+    fn should_keep_unknown_container_status() {
+        assert_eq!(ContainerStatus::parse("exited"), ContainerStatus::Exited);
+        assert_eq!(
+            ContainerStatus::parse("stopping"),
+            ContainerStatus::Unknown("stopping".to_owned())
+        );
+    }
+}
@@ -1,7 +1,12 @@
 use futures::{stream::BoxStream, StreamExt};
 use std::{
-    fmt, io,
+    fmt,
+    fs::{self, File},
+    io,
     io::{BufRead, BufReader, Read},
+    path::Path,
+    sync::mpsc,
+    time::{Duration, Instant},
 };
 
 pub(crate) struct LogStreamAsync<'d> {
@@ -19,21 +24,185 @@ impl<'d> LogStreamAsync<'d> {
         Self { inner: stream }
     }
 
-    pub async fn wait_for_message(mut self, message: &str) -> Result<(), WaitError> {
-        let mut lines = vec![];
+    /// Waits until all messages appeared in the given order.
+    ///
+    /// Lines that match a message that was already found are ignored, they do not reset the
+    /// progress. The chunks of the stream do not necessarily end at line breaks, hence they are
+    /// joined before splitting them into lines.
+    pub async fn wait_for_messages(mut self, messages: &[String]) -> Result<(), WaitError> {
+        let mut progress = Progress::new(messages);
+        let mut pending = String::new();
 
-        while let Some(line) = self.inner.next().await.transpose()? {
-            if handle_line(line, message, &mut lines) {
-                return Ok(());
+        if progress.is_done() {
+            return Ok(());
+        }
+
+        while let Some(chunk) = self.inner.next().await.transpose()? {
+            pending.push_str(&chunk);
+
+            while let Some(end) = pending.find('\n') {
+                let line = pending[..end].trim_end_matches('\r').to_owned();
+                pending.replace_range(..=end, "");
+
+                if progress.handle_line(line) {
+                    return Ok(());
+                }
+            }
+        }
+
+        // the last line does not necessarily end with a line break
+        if !pending.is_empty() && progress.handle_line(pending) {
+            return Ok(());
+        }
+
+        Err(progress.end_of_stream())
+    }
+
+    /// Returns all lines up to and including the first one that contains the message.
+    ///
+    /// The chunks of the stream do not necessarily end at line breaks, hence they are joined
+    /// before splitting them into lines.
+    pub async fn lines_until(mut self, message: &str) -> Result<Vec<String>, WaitError> {
+        let mut lines = Vec::new();
+        let mut pending = String::new();
+
+        while let Some(chunk) = self.inner.next().await.transpose()? {
+            pending.push_str(&chunk);
+
+            while let Some(end) = pending.find('\n') {
+                let line = pending[..end].trim_end_matches('\r').to_owned();
+                pending.replace_range(..=end, "");
+
+                let found = line.contains(message);
+                lines.push(line);
+
+                if found {
+                    return Ok(lines);
+                }
             }
         }
 
-        Err(end_of_stream(lines))
+        if pending.contains(message) {
+            lines.push(pending);
+            return Ok(lines);
+        }
+
+        Err(WaitError::EndOfStream(lines))
+    }
+
+    /// Returns the lines that were logged after the given position, without their timestamps,
+    /// together with the position of the last one.
+    ///
+    /// Expects a stream that ends after the logs written so far and whose lines are prefixed with
+    /// their timestamp. Docker only filters the logs by whole seconds, hence lines of the second of
+    /// the position are skipped here.
+    #[cfg(feature = "chrono")]
+    pub async fn lines_after(
+        mut self,
+        position: LogPosition,
+    ) -> Result<(Vec<String>, LogPosition), WaitError> {
+        let mut logs = String::new();
+
+        while let Some(chunk) = self.inner.next().await.transpose()? {
+            logs.push_str(&chunk);
+        }
+
+        let mut lines = Vec::new();
+        let mut last = position;
+
+        for line in logs.lines() {
+            let (timestamp, line) = line.split_at(line.find(' ').unwrap_or(line.len()));
+            let timestamp = chrono::DateTime::parse_from_rfc3339(timestamp)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+                .with_timezone(&chrono::Utc);
+
+            if timestamp > position.timestamp {
+                lines.push(line.trim_start_matches(' ').to_owned());
+                last = LogPosition { timestamp };
+            }
+        }
+
+        Ok((lines, last))
+    }
+
+    /// Waits until the stream contains at least the given number of lines.
+    pub async fn wait_for_line_count(mut self, count: usize) -> Result<(), WaitError> {
+        let mut lines = Vec::new();
+        let mut pending = String::new();
+
+        if count == 0 {
+            return Ok(());
+        }
+
+        while let Some(chunk) = self.inner.next().await.transpose()? {
+            pending.push_str(&chunk);
+
+            while let Some(end) = pending.find('\n') {
+                lines.push(pending[..end].trim_end_matches('\r').to_owned());
+                pending.replace_range(..=end, "");
+
+                if lines.len() >= count {
+                    return Ok(());
+                }
+            }
+        }
+
+        // the last line does not necessarily end with a line break
+        if !pending.is_empty() {
+            lines.push(pending);
+        }
+        if lines.len() >= count {
+            return Ok(());
+        }
+
+        Err(WaitError::EndOfStream(lines))
+    }
+
+    /// Like [`wait_for_messages`](Self::wait_for_messages) but gives up once the deadline passed.
+    pub async fn wait_for_messages_until(
+        self,
+        messages: &[String],
+        deadline: Option<Instant>,
+    ) -> Result<(), WaitError> {
+        until(deadline, self.wait_for_messages(messages)).await
+    }
+
+    /// Like [`wait_for_line_count`](Self::wait_for_line_count) but gives up once the deadline
+    /// passed.
+    pub async fn wait_for_line_count_until(
+        self,
+        count: usize,
+        deadline: Option<Instant>,
+    ) -> Result<(), WaitError> {
+        until(deadline, self.wait_for_line_count(count)).await
+    }
+}
+
+/// The point in the logs of a container up to which they were read, to continue reading without
+/// returning the same lines again, e.g. after the container was restarted.
+#[cfg(feature = "chrono")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LogPosition {
+    timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+#[cfg(feature = "chrono")]
+impl LogPosition {
+    /// The position before the first line of the logs.
+    pub fn start() -> Self {
+        LogPosition {
+            timestamp: std::time::UNIX_EPOCH.into(),
+        }
+    }
+
+    /// Returns the time the last line that was read has been logged at.
+    pub fn timestamp(&self) -> chrono::DateTime<chrono::Utc> {
+        self.timestamp
     }
 }
 
 pub(crate) struct LogStream {
-    inner: Box<dyn Read>,
+    inner: Box<dyn Read + Send>,
 }
 
 impl fmt::Debug for LogStream {
@@ -43,56 +212,240 @@ impl fmt::Debug for LogStream {
 }
 
 impl LogStream {
-    pub fn new(stream: impl Read + 'static) -> Self {
+    pub fn new(stream: impl Read + Send + 'static) -> Self {
         Self {
             inner: Box::new(stream),
         }
     }
 
-    pub fn wait_for_message(self, message: &str) -> Result<(), WaitError> {
+    /// Waits until all messages appeared in the given order.
+    ///
+    /// Lines that match a message that was already found are ignored, they do not reset the
+    /// progress.
+    pub fn wait_for_messages(self, messages: &[String]) -> Result<(), WaitError> {
+        let mut progress = Progress::new(messages);
+
+        if progress.is_done() {
+            return Ok(());
+        }
+
         let logs = BufReader::new(self.inner);
-        let mut lines = vec![];
 
         for line in logs.lines() {
-            if handle_line(line?, message, &mut lines) {
+            if progress.handle_line(line?) {
+                return Ok(());
+            }
+        }
+
+        Err(progress.end_of_stream())
+    }
+
+    /// Returns all lines up to and including the first one that contains the message.
+    pub fn lines_until(self, message: &str) -> Result<Vec<String>, WaitError> {
+        let mut lines = Vec::new();
+
+        for line in BufReader::new(self.inner).lines() {
+            let line = line?;
+            let found = line.contains(message);
+            lines.push(line);
+
+            if found {
+                return Ok(lines);
+            }
+        }
+
+        Err(WaitError::EndOfStream(lines))
+    }
+
+    /// Waits until the stream contains at least the given number of lines.
+    pub fn wait_for_line_count(self, count: usize) -> Result<(), WaitError> {
+        let mut lines = Vec::new();
+
+        if count == 0 {
+            return Ok(());
+        }
+
+        for line in BufReader::new(self.inner).lines() {
+            lines.push(line?);
+
+            if lines.len() >= count {
                 return Ok(());
             }
         }
 
-        Err(end_of_stream(lines))
+        Err(WaitError::EndOfStream(lines))
+    }
+
+    /// Like [`wait_for_messages`](Self::wait_for_messages) but gives up once the deadline passed.
+    pub fn wait_for_messages_until(
+        self,
+        messages: &[String],
+        deadline: Option<Instant>,
+    ) -> Result<(), WaitError> {
+        let messages = messages.to_vec();
+
+        self.wait_until(deadline, move |logs| logs.wait_for_messages(&messages))
+    }
+
+    /// Like [`wait_for_line_count`](Self::wait_for_line_count) but gives up once the deadline
+    /// passed.
+    pub fn wait_for_line_count_until(
+        self,
+        count: usize,
+        deadline: Option<Instant>,
+    ) -> Result<(), WaitError> {
+        self.wait_until(deadline, move |logs| logs.wait_for_line_count(count))
+    }
+
+    /// Reading from the stream blocks, hence the stream is consumed on a separate thread. If the
+    /// deadline passes, the thread is left behind until the stream is closed.
+    fn wait_until<F>(self, deadline: Option<Instant>, wait: F) -> Result<(), WaitError>
+    where
+        F: FnOnce(Self) -> Result<(), WaitError> + Send + 'static,
+    {
+        let deadline = match deadline {
+            Some(deadline) => deadline,
+            None => return wait(self),
+        };
+
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            // the receiver is gone if the deadline passed already
+            let _ = sender.send(wait(self));
+        });
+
+        receiver
+            .recv_timeout(remaining(deadline))
+            .unwrap_or(Err(WaitError::Timeout))
     }
 }
 
-fn handle_line(line: String, message: &str, lines: &mut Vec<String>) -> bool {
-    if line.contains(message) {
-        log::info!("Found message after comparing {} lines", lines.len());
+/// Creates the file the logs of a container are written to, including its parent directories.
+pub(crate) fn create_log_file(path: &Path) -> io::Result<File> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    File::create(path)
+}
 
-        return true;
+/// Returns the time left until the given deadline.
+pub(crate) fn remaining(deadline: Instant) -> Duration {
+    deadline.saturating_duration_since(Instant::now())
+}
+
+/// Returns the deadline that passes first, given the startup deadline and the timeout of a single
+/// condition that starts now.
+pub(crate) fn earliest(deadline: Option<Instant>, timeout: Option<Duration>) -> Option<Instant> {
+    let condition_deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+    match (deadline, condition_deadline) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
     }
+}
 
-    lines.push(line);
+/// Gives up on the wait once the deadline passed.
+async fn until<F>(deadline: Option<Instant>, wait: F) -> Result<(), WaitError>
+where
+    F: std::future::Future<Output = Result<(), WaitError>>,
+{
+    match deadline {
+        Some(deadline) => tokio::time::timeout(remaining(deadline), wait)
+            .await
+            .map_err(|_| WaitError::Timeout)?,
+        None => wait.await,
+    }
+}
 
-    false
+/// Tracks which of the expected messages have been found so far.
+struct Progress<'m> {
+    messages: &'m [String],
+    found: usize,
+    lines: Vec<String>,
 }
 
-fn end_of_stream(lines: Vec<String>) -> WaitError {
-    log::error!(
-        "Failed to find message in stream after comparing {} lines.",
-        lines.len()
-    );
+impl<'m> Progress<'m> {
+    fn new(messages: &'m [String]) -> Self {
+        Progress {
+            messages,
+            found: 0,
+            lines: vec![],
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.found == self.messages.len()
+    }
+
+    /// Returns whether the line contained the last message.
+    fn handle_line(&mut self, line: String) -> bool {
+        if line.contains(self.messages[self.found].as_str()) {
+            self.found += 1;
+
+            if self.is_done() {
+                log::info!("Found message after comparing {} lines", self.lines.len());
+
+                return true;
+            }
+        }
+
+        self.lines.push(line);
+
+        false
+    }
 
-    WaitError::EndOfStream(lines)
+    fn end_of_stream(self) -> WaitError {
+        log::error!(
+            "Failed to find message in stream after comparing {} lines.",
+            self.lines.len()
+        );
+
+        WaitError::EndOfStream(self.lines)
+    }
 }
 
-/// Defines error cases when waiting for a message in a stream.
+/// Defines error cases when waiting for a container to become ready.
 #[derive(Debug)]
 pub enum WaitError {
     /// Indicates the stream ended before finding the log line you were looking for.
     /// Contains all the lines that were read for debugging purposes.
     EndOfStream(Vec<String>),
     Io(io::Error),
+    /// Indicates the startup timeout passed before the condition was met.
+    Timeout,
+    /// Like [`Timeout`](Self::Timeout), with everything the container logged to stdout and stderr
+    /// until then.
+    TimeoutWithLogs(String),
+    /// Indicates the container exited with the given code before the condition was met.
+    Exited(u64),
 }
 
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitError::EndOfStream(lines) => write!(
+                f,
+                "end of stream reached after reading {} lines without finding the message",
+                lines.len()
+            ),
+            WaitError::Io(e) => write!(f, "failed to read logs: {}", e),
+            WaitError::Timeout => write!(f, "startup timeout exceeded"),
+            WaitError::TimeoutWithLogs(logs) => {
+                write!(
+                    f,
+                    "startup timeout exceeded, logs of the container:\n{}",
+                    logs
+                )
+            }
+            WaitError::Exited(code) => write!(f, "container exited with code {}", code),
+        }
+    }
+}
+
+impl std::error::Error for WaitError {}
+
 impl From<io::Error> for WaitError {
     fn from(e: io::Error) -> Self {
         WaitError::Io(e)
@@ -102,6 +455,27 @@ impl From<io::Error> for WaitError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use futures::stream;
+
+    struct BrokenStream;
+
+    impl Read for BrokenStream {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "connection reset",
+            ))
+        }
+    }
+
+    struct SilentStream;
+
+    impl Read for SilentStream {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            std::thread::sleep(Duration::from_secs(60));
+            Ok(0)
+        }
+    }
 
     #[test]
     fn given_logs_when_line_contains_message_should_find_it() {
@@ -114,8 +488,195 @@ mod tests {
             .as_bytes(),
         );
 
-        let result = log_stream.wait_for_message("Message three");
+        let result = log_stream.wait_for_messages(&["Message three".to_owned()]);
+
+        assert!(result.is_ok())
+    }
+
+    #[test]
+    fn given_logs_when_messages_appear_in_order_should_find_them() {
+        let log_stream = LogStream::new(
+            r"
+            phase one
+            phase two
+            phase one
+            phase three
+        "
+            .as_bytes(),
+        );
+
+        let result = log_stream.wait_for_messages(&[
+            "phase one".to_owned(),
+            "phase two".to_owned(),
+            "phase three".to_owned(),
+        ]);
+
+        assert!(result.is_ok())
+    }
+
+    #[tokio::test]
+    async fn given_logs_when_messages_appear_out_of_order_should_not_find_them() {
+        let lines = vec!["phase two\n", "phase one\n"]
+            .into_iter()
+            .map(|line| Ok(line.to_owned()));
+
+        let result = LogStreamAsync::new(stream::iter(lines).boxed())
+            .wait_for_messages(&["phase one".to_owned(), "phase two".to_owned()])
+            .await;
+
+        match result {
+            Err(WaitError::EndOfStream(lines)) => assert_eq!(lines, vec!["phase two", "phase one"]),
+            other => panic!("expected end of stream, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn given_chunks_across_lines_should_find_messages_in_order() {
+        let chunks = vec!["phase one\nphase two\nphase th", "ree\n"]
+            .into_iter()
+            .map(|chunk| Ok(chunk.to_owned()));
+
+        let result = LogStreamAsync::new(stream::iter(chunks).boxed())
+            .wait_for_messages(&[
+                "phase one".to_owned(),
+                "phase two".to_owned(),
+                "phase three".to_owned(),
+            ])
+            .await;
 
         assert!(result.is_ok())
     }
+
+    #[test]
+    fn given_broken_stream_should_return_descriptive_error() {
+        let result = LogStream::new(BrokenStream).wait_for_messages(&["Message".to_owned()]);
+
+        match result {
+            Err(e @ WaitError::Io(_)) => {
+                assert_eq!(e.to_string(), "failed to read logs: connection reset")
+            }
+            other => panic!("expected an IO error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn given_silent_stream_should_time_out_at_deadline() {
+        let deadline = Instant::now() + Duration::from_millis(50);
+
+        let result = LogStream::new(SilentStream)
+            .wait_for_messages_until(&["Message".to_owned()], Some(deadline));
+
+        assert!(matches!(result, Err(WaitError::Timeout)))
+    }
+
+    #[tokio::test]
+    async fn given_silent_async_stream_should_time_out_at_deadline() {
+        let deadline = Instant::now() + Duration::from_millis(50);
+
+        let result = LogStreamAsync::new(stream::pending().boxed())
+            .wait_for_messages_until(&["Message".to_owned()], Some(deadline))
+            .await;
+
+        assert!(matches!(result, Err(WaitError::Timeout)))
+    }
+
+    #[tokio::test]
+    async fn given_chunks_across_lines_should_return_lines_until_message() {
+        let chunks = vec![
+            "Available Accounts\n(0) 0x90f8",
+            "bf6a\n(1) 0xffcf\n",
+            "Listening on 0.0.0.0:8545\nignored\n",
+        ]
+        .into_iter()
+        .map(|chunk| Ok(chunk.to_owned()));
+
+        let lines = LogStreamAsync::new(stream::iter(chunks).boxed())
+            .lines_until("Listening on")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            lines,
+            vec![
+                "Available Accounts",
+                "(0) 0x90f8bf6a",
+                "(1) 0xffcf",
+                "Listening on 0.0.0.0:8545"
+            ]
+        )
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "chrono")]
+    async fn given_position_should_only_return_later_lines() {
+        let chunks = vec![
+            "2021-05-04T10:00:00.1Z first\n2021-05-04T10:00:00.2",
+            "Z second\n2021-05-04T10:00:00.3Z third\n",
+        ]
+        .into_iter()
+        .map(|chunk| Ok(chunk.to_owned()));
+        let stream = || LogStreamAsync::new(stream::iter(chunks.clone()).boxed());
+
+        let (lines, position) = stream().lines_after(LogPosition::start()).await.unwrap();
+        assert_eq!(lines, vec!["first", "second", "third"]);
+
+        let (_, second) = LogStreamAsync::new(
+            stream::iter(vec![Ok("2021-05-04T10:00:00.2Z second\n".to_owned())]).boxed(),
+        )
+        .lines_after(LogPosition::start())
+        .await
+        .unwrap();
+        let (lines, resumed) = stream().lines_after(second).await.unwrap();
+
+        assert_eq!(lines, vec!["third"]);
+        assert_eq!(resumed, position);
+
+        let (lines, unchanged) = stream().lines_after(position).await.unwrap();
+
+        assert!(lines.is_empty());
+        assert_eq!(unchanged, position);
+    }
+
+    #[test]
+    fn given_logs_should_wait_for_line_count() {
+        let logs = LogStream::new("one\ntwo\nthree\n".as_bytes());
+        assert!(logs.wait_for_line_count(3).is_ok());
+
+        let logs = LogStream::new("one\ntwo\n".as_bytes());
+        let result = logs.wait_for_line_count(3);
+
+        assert!(matches!(result, Err(WaitError::EndOfStream(lines)) if lines.len() == 2))
+    }
+
+    #[tokio::test]
+    async fn given_chunks_across_lines_should_count_lines() {
+        let chunks = || {
+            stream::iter(
+                vec!["one\ntw", "o\n", "three"]
+                    .into_iter()
+                    .map(|chunk| Ok(chunk.to_owned())),
+            )
+            .boxed()
+        };
+
+        assert!(LogStreamAsync::new(chunks())
+            .wait_for_line_count(3)
+            .await
+            .is_ok());
+
+        let result = LogStreamAsync::new(chunks()).wait_for_line_count(4).await;
+
+        assert!(
+            matches!(result, Err(WaitError::EndOfStream(lines)) if lines == vec!["one", "two", "three"])
+        )
+    }
+
+    #[test]
+    fn given_silent_stream_should_time_out_waiting_for_line_count() {
+        let deadline = Instant::now() + Duration::from_millis(50);
+
+        let result = LogStream::new(SilentStream).wait_for_line_count_until(1, Some(deadline));
+
+        assert!(matches!(result, Err(WaitError::Timeout)))
+    }
 }
@@ -4,38 +4,111 @@ use std::collections::HashMap;
 #[derive(Debug, PartialEq, Default)]
 pub struct Ports {
     mapping: HashMap<u16, u16>,
+    udp_mapping: HashMap<u16, u16>,
 }
 
 impl Ports {
     pub fn new(ports: HashMap<String, Option<Vec<HashMap<String, String>>>>) -> Self {
-        let mapping = ports
-            .into_iter()
-            .filter_map(|(internal, external)| {
-                // internal is '8332/tcp', split off the protocol ...
-                let internal = internal.split('/').next()?;
+        let mut mapping = HashMap::new();
+        let mut udp_mapping = HashMap::new();
 
-                // external is a an optional list of maps: [ { "HostIp": "0.0.0.0", "HostPort": "33078" } ]
-                // get the first entry and get the value of the `HostPort` field
-                let external = external?.first()?.get("HostPort").cloned()?;
+        for (internal, external) in ports {
+            // internal is '8332/tcp', split off the protocol ...
+            let mut parts = internal.split('/');
+            let internal = match parts.next() {
+                Some(internal) => internal,
+                None => continue,
+            };
+            let protocol = parts.next().unwrap_or("tcp");
 
-                let internal = parse_port(internal);
-                let external = parse_port(&external);
+            // external is a an optional list of maps: [ { "HostIp": "0.0.0.0", "HostPort": "33078" } ]
+            // get the first entry and get the value of the `HostPort` field
+            let external = match external
+                .as_ref()
+                .and_then(|external| external.first())
+                .and_then(|binding| binding.get("HostPort"))
+            {
+                Some(external) => external,
+                None => continue,
+            };
 
-                log::debug!("Registering port mapping: {} -> {}", internal, external);
+            let internal = parse_port(internal);
+            let external = parse_port(external);
 
-                Some((internal, external))
-            })
-            .collect::<HashMap<_, _>>();
+            log::debug!(
+                "Registering port mapping: {}/{} -> {}",
+                internal,
+                protocol,
+                external
+            );
 
-        Self { mapping }
+            if protocol == "udp" {
+                udp_mapping.insert(internal, external);
+            } else {
+                mapping.insert(internal, external);
+            }
+        }
+
+        Self {
+            mapping,
+            udp_mapping,
+        }
     }
 
     /// Returns the host port for the given internal port.
+    ///
+    /// TCP ports take precedence, UDP ports are only considered if there is no TCP port with the
+    /// same number.
     pub fn map_to_host_port(&self, internal_port: u16) -> Option<u16> {
-        self.mapping.get(&internal_port).cloned()
+        self.mapping
+            .get(&internal_port)
+            .or_else(|| self.udp_mapping.get(&internal_port))
+            .cloned()
+    }
+
+    /// Returns the host port for the given internal UDP port.
+    pub fn map_to_host_port_udp(&self, internal_port: u16) -> Option<u16> {
+        self.udp_mapping.get(&internal_port).cloned()
     }
 }
 
+/// The transport protocol of a port exposed by an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+    Sctp,
+}
+
+/// Parses the keys of the `ExposedPorts` of an image, e.g. `8080/tcp`, sorted by port.
+///
+/// Keys without a protocol are TCP ports, just like docker treats them.
+pub(crate) fn parse_exposed_ports<'a, I: IntoIterator<Item = &'a String>>(
+    exposed_ports: I,
+) -> Vec<(u16, Protocol)> {
+    let mut ports = exposed_ports
+        .into_iter()
+        .map(|exposed_port| {
+            let mut parts = exposed_port.split('/');
+            let port = parse_port(parts.next().unwrap_or_default());
+            let protocol = match parts.next().unwrap_or("tcp") {
+                "tcp" => Protocol::Tcp,
+                "udp" => Protocol::Udp,
+                "sctp" => Protocol::Sctp,
+                other => panic!(
+                    "unknown protocol {} of exposed port {}",
+                    other, exposed_port
+                ),
+            };
+
+            (port, protocol)
+        })
+        .collect::<Vec<_>>();
+
+    ports.sort();
+    ports
+}
+
 fn parse_port(port: &str) -> u16 {
     port.parse()
         .unwrap_or_else(|e| panic!("Failed to parse {} as u16 because {}", port, e))
@@ -44,7 +117,7 @@ fn parse_port(port: &str) -> u16 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use shiplift::rep::ContainerDetails;
+    use shiplift::rep::{ContainerDetails, ImageDetails};
 
     #[test]
     fn can_deserialize_docker_inspect_response_into_api_ports() {
@@ -288,4 +361,91 @@ mod tests {
 
         assert_eq!(parsed_ports, expected_ports)
     }
+
+    #[test]
+    fn should_keep_udp_ports_apart_from_tcp_ports() {
+        let binding = |host_port: &str| {
+            Some(vec![vec![("HostPort".to_owned(), host_port.to_owned())]
+                .into_iter()
+                .collect()])
+        };
+        let mut ports = HashMap::new();
+        ports.insert("53/tcp".to_owned(), binding("33000"));
+        ports.insert("53/udp".to_owned(), binding("33001"));
+        ports.insert("6831/udp".to_owned(), binding("33002"));
+
+        let ports = Ports::new(ports);
+
+        assert_eq!(ports.map_to_host_port(53), Some(33000));
+        assert_eq!(ports.map_to_host_port_udp(53), Some(33001));
+        assert_eq!(ports.map_to_host_port(6831), Some(33002));
+        assert_eq!(ports.map_to_host_port_udp(6831), Some(33002));
+        assert_eq!(ports.map_to_host_port_udp(8080), None);
+    }
+
+    #[test]
+    fn can_deserialize_image_inspect_response_into_exposed_ports() {
+        let image_details = serde_json::from_str::<ImageDetails>(
+            r#"{
+  "Id": "sha256:7614ae9453d1d87e740a2056257a6de7135c84037c367e1fffa92ae922784631",
+  "RepoTags": ["redis:5.0"],
+  "RepoDigests": ["redis@sha256:2a9865e55c37293b71df051922022898d8e4ec0f579c9b53a0caee1b170bc81c"],
+  "Parent": "",
+  "Comment": "",
+  "Created": "2021-02-19T04:57:38.081442827Z",
+  "Container": "b9ba4b3ba5868c6f7ab8e6a0620ec78e80803551f99d5119c48cd2bb8da4fe05",
+  "DockerVersion": "19.03.12",
+  "Author": "",
+  "Config": {
+    "Hostname": "",
+    "Domainname": "",
+    "User": "",
+    "AttachStdin": false,
+    "AttachStdout": false,
+    "AttachStderr": false,
+    "ExposedPorts": {
+      "6379/tcp": {},
+      "5353/udp": {},
+      "132": {},
+      "3868/sctp": {}
+    },
+    "Tty": false,
+    "OpenStdin": false,
+    "StdinOnce": false,
+    "Env": [
+      "PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin",
+      "REDIS_VERSION=5.0.10"
+    ],
+    "Cmd": ["redis-server"],
+    "Image": "sha256:02ddbc4ff9b6a3e1a5d1b5c4e8d41c7c2e2d0b3b8b9f2e3a4c7d6e5f4a3b2c1d",
+    "Volumes": {"/data": {}},
+    "WorkingDir": "/data",
+    "Entrypoint": ["docker-entrypoint.sh"],
+    "OnBuild": null,
+    "Labels": null
+  },
+  "Architecture": "amd64",
+  "Os": "linux",
+  "Size": 98361408,
+  "VirtualSize": 98361408
+}"#,
+        )
+        .unwrap();
+
+        let exposed_ports = image_details
+            .config
+            .exposed_ports
+            .map(|exposed_ports| parse_exposed_ports(exposed_ports.keys()))
+            .unwrap_or_default();
+
+        assert_eq!(
+            exposed_ports,
+            vec![
+                (132, Protocol::Tcp),
+                (3868, Protocol::Sctp),
+                (5353, Protocol::Udp),
+                (6379, Protocol::Tcp),
+            ]
+        )
+    }
 }
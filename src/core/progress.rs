@@ -0,0 +1,125 @@
+use crate::core::WaitFor;
+use std::{
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Periodically reports which ready condition a container is still waiting for.
+///
+/// The reports are sent from a dedicated thread so that they keep coming while the waiting
+/// thread is blocked, regardless of whether the wait is sync or async. Reporting stops once this
+/// is dropped.
+pub(crate) struct ReadyProgress {
+    pending: Arc<Mutex<String>>,
+    stop: mpsc::Sender<()>,
+    reporter: Option<thread::JoinHandle<()>>,
+}
+
+impl ReadyProgress {
+    /// Logs the progress of the given container at the given interval.
+    pub(crate) fn start(container_id: &str, interval: Duration) -> Self {
+        let container_id = container_id.to_owned();
+
+        Self::start_with(interval, move |elapsed, pending| {
+            log::info!(
+                "still waiting for container {} to be ready ({}s elapsed), pending: {}",
+                container_id,
+                elapsed.as_secs(),
+                pending
+            )
+        })
+    }
+
+    fn start_with<F>(interval: Duration, mut report: F) -> Self
+    where
+        F: FnMut(Duration, &str) + Send + 'static,
+    {
+        let pending = Arc::new(Mutex::new(WaitFor::Nothing.to_string()));
+        let (stop, stopped) = mpsc::channel();
+        let started = Instant::now();
+
+        let reporter = {
+            let pending = pending.clone();
+
+            thread::spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    let pending = pending.lock().expect("lock is not poisoned").clone();
+
+                    report(started.elapsed(), &pending);
+                }
+            })
+        };
+
+        ReadyProgress {
+            pending,
+            stop,
+            reporter: Some(reporter),
+        }
+    }
+
+    /// Marks the given condition as the one that is currently waited for.
+    pub(crate) fn pending(&self, condition: &WaitFor) {
+        *self.pending.lock().expect("lock is not poisoned") = condition.to_string();
+    }
+}
+
+impl Drop for ReadyProgress {
+    fn drop(&mut self) {
+        // the reporter also stops if the receiver is disconnected, hence the result is irrelevant
+        let _ = self.stop.send(());
+
+        if let Some(reporter) = self.reporter.take() {
+            let _ = reporter.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_report_pending_condition_at_interval() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+
+        let progress = {
+            let reports = reports.clone();
+
+            ReadyProgress::start_with(Duration::from_millis(10), move |_, pending| {
+                reports.lock().unwrap().push(pending.to_owned())
+            })
+        };
+        progress.pending(&WaitFor::message_on_stdout("ready"));
+        thread::sleep(Duration::from_millis(100));
+        drop(progress);
+
+        let reports = reports.lock().unwrap().clone();
+        assert!(!reports.is_empty());
+        assert_eq!(
+            reports.last().map(String::as_str),
+            Some(r#"message "ready" on stdout"#)
+        );
+    }
+
+    #[test]
+    fn should_stop_reporting_when_dropped() {
+        let reports = Arc::new(Mutex::new(0));
+
+        let progress = {
+            let reports = reports.clone();
+
+            ReadyProgress::start_with(Duration::from_millis(10), move |_, _| {
+                *reports.lock().unwrap() += 1
+            })
+        };
+        drop(progress);
+        let count = *reports.lock().unwrap();
+        thread::sleep(Duration::from_millis(50));
+
+        assert_eq!(*reports.lock().unwrap(), count);
+    }
+}
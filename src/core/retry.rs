@@ -0,0 +1,154 @@
+use std::fmt;
+
+/// Parts of the error messages of the docker daemon that are bound to be reported again.
+const PERMANENT_ERRORS: [&str; 6] = [
+    "No such image",
+    "pull access denied",
+    "manifest unknown",
+    "repository does not exist",
+    "invalid reference format",
+    "executable file not found",
+];
+
+/// The reason why a container could not be started, as reported by the docker daemon.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StartError {
+    message: String,
+}
+
+impl StartError {
+    pub fn new<M: Into<String>>(message: M) -> Self {
+        StartError {
+            message: message.into(),
+        }
+    }
+
+    /// Returns whether starting the container again might succeed, e.g. after a port conflict.
+    ///
+    /// A name conflict is bound to happen again, as the container that holds the name is not
+    /// removed by retrying.
+    pub fn is_retryable(&self) -> bool {
+        !self.is_name_conflict()
+            && !PERMANENT_ERRORS
+                .iter()
+                .any(|error| self.message.contains(error))
+    }
+
+    /// Returns whether another container already has the requested name.
+    pub fn is_name_conflict(&self) -> bool {
+        self.message.contains("is already in use")
+    }
+}
+
+impl fmt::Display for StartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message.trim())
+    }
+}
+
+/// Calls `start` until it succeeds, retrying retryable errors as long as there are retries left.
+///
+/// The retries are shared with the readiness checks of the container, which is why they are
+/// counted down in place.
+pub(crate) fn retry_start<T, F>(retries_left: &mut u32, mut start: F) -> Result<T, StartError>
+where
+    F: FnMut() -> Result<T, StartError>,
+{
+    loop {
+        match start() {
+            Err(e) if e.is_retryable() && *retries_left > 0 => {
+                *retries_left -= 1;
+                log::warn!("Failed to start container, retrying: {}", e);
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_retry_transient_error_once() {
+        let mut retries_left = 3;
+        let mut attempts = 0;
+
+        let result = retry_start(&mut retries_left, || {
+            attempts += 1;
+
+            if attempts == 1 {
+                Err(StartError::new(
+                    "Bind for 0.0.0.0:6379 failed: port is already allocated",
+                ))
+            } else {
+                Ok("container-id")
+            }
+        });
+
+        assert_eq!(result, Ok("container-id"));
+        assert_eq!(attempts, 2);
+        assert_eq!(retries_left, 2);
+    }
+
+    #[test]
+    fn should_not_retry_permanent_error() {
+        let mut retries_left = 3;
+        let mut attempts = 0;
+
+        let result = retry_start(&mut retries_left, || -> Result<(), _> {
+            attempts += 1;
+
+            Err(StartError::new(
+                "Unable to find image 'foo:bar' locally\nError response from daemon: manifest unknown",
+            ))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+        assert_eq!(retries_left, 3);
+    }
+
+    #[test]
+    fn should_give_up_once_retries_are_used_up() {
+        let mut retries_left = 2;
+        let mut attempts = 0;
+
+        let result = retry_start(&mut retries_left, || -> Result<(), _> {
+            attempts += 1;
+
+            Err(StartError::new("address already in use"))
+        });
+
+        assert_eq!(result, Err(StartError::new("address already in use")));
+        assert_eq!(attempts, 3);
+        assert_eq!(retries_left, 0);
+    }
+
+    #[test]
+    fn should_not_retry_name_conflict() {
+        let mut retries_left = 3;
+        let mut attempts = 0;
+
+        let result = retry_start(&mut retries_left, || -> Result<(), _> {
+            attempts += 1;
+
+            Err(StartError::new(
+                "docker: Error response from daemon: Conflict. The container name \"/db\" is already in use by container \"5f2c\".",
+            ))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+        assert_eq!(retries_left, 3);
+    }
+
+    #[test]
+    fn should_detect_name_conflict_but_not_port_conflict() {
+        assert!(StartError::new(
+            "docker: Error response from daemon: Conflict. The container name \"/testcontainers-lease-db\" is already in use by container \"5f2c\"."
+        )
+        .is_name_conflict());
+        assert!(!StartError::new("address already in use").is_name_conflict());
+    }
+}
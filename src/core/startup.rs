@@ -0,0 +1,87 @@
+use std::time::{Duration, Instant};
+
+/// A step of starting a container, whose duration a [`StartupReport`] records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupPhase {
+    /// Pulling the image, which both clients only do if it is not available locally, unless the
+    /// pull policy of the Http client says otherwise.
+    Pull,
+    /// Creating the container and copying files into it.
+    Create,
+    /// Starting the created container.
+    Start,
+    /// Creating and starting the container in a single step, which the Cli client does through
+    /// `docker run` unless it needs a created container, e.g. to copy files into it.
+    CreateAndStart,
+    /// Waiting for the ready conditions of the image and running the commands it wants to
+    /// execute after start.
+    Ready,
+}
+
+/// The durations of the phases that starting a container went through, in the order they
+/// happened.
+///
+/// Attempts that are retried, e.g. through [`RunArgs::with_start_retries`], show up as repeated
+/// phases, so the [`total`] is the whole time it took to start the container.
+///
+/// [`RunArgs::with_start_retries`]: struct.RunArgs.html#method.with_start_retries
+/// [`total`]: struct.StartupReport.html#method.total
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StartupReport {
+    phases: Vec<(StartupPhase, Duration)>,
+}
+
+impl StartupReport {
+    /// Returns the phases in the order they happened.
+    pub fn phases(&self) -> &[(StartupPhase, Duration)] {
+        &self.phases
+    }
+
+    /// Returns the time spent in the given phase, summed up across retries, or `None` if the
+    /// container did not go through it.
+    pub fn duration(&self, phase: StartupPhase) -> Option<Duration> {
+        self.phases
+            .iter()
+            .filter(|(p, _)| *p == phase)
+            .map(|(_, duration)| *duration)
+            .fold(None, |sum, duration| {
+                Some(sum.unwrap_or_default() + duration)
+            })
+    }
+
+    /// Returns the time spent in all phases.
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|(_, duration)| *duration).sum()
+    }
+
+    /// Records that the given phase took from `started` until now.
+    pub(crate) fn record_since(&mut self, phase: StartupPhase, started: Instant) {
+        self.record(phase, started.elapsed())
+    }
+
+    pub(crate) fn record(&mut self, phase: StartupPhase, duration: Duration) {
+        self.phases.push((phase, duration));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_sum_up_repeated_phases() {
+        let mut report = StartupReport::default();
+        report.record(StartupPhase::Create, Duration::from_millis(10));
+        report.record(StartupPhase::Start, Duration::from_millis(20));
+        report.record(StartupPhase::Create, Duration::from_millis(30));
+        report.record(StartupPhase::Start, Duration::from_millis(40));
+
+        assert_eq!(
+            report.duration(StartupPhase::Create),
+            Some(Duration::from_millis(40))
+        );
+        assert_eq!(report.duration(StartupPhase::Pull), None);
+        assert_eq!(report.total(), Duration::from_millis(100));
+        assert_eq!(report.phases()[2].0, StartupPhase::Create);
+    }
+}
@@ -0,0 +1,469 @@
+use crate::core::{logs::WaitError, ContainerState};
+use std::{
+    fmt,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Describes an HTTP request that is repeatedly sent to a container until it responds as expected.
+///
+/// The request is sent to `localhost` on the host port that is mapped to the configured container
+/// port. By default, any response with status code `200` is accepted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpWaitStrategy {
+    port: u16,
+    path: String,
+    method: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    basic_auth: Option<(String, String)>,
+    status_codes: Vec<u16>,
+    response_contains: Option<String>,
+    poll_interval: Duration,
+}
+
+impl HttpWaitStrategy {
+    /// Creates a strategy that sends `GET` requests to the given path of the given container port.
+    pub fn new<P: Into<String>>(port: u16, path: P) -> Self {
+        HttpWaitStrategy {
+            port,
+            path: path.into(),
+            method: "GET".to_owned(),
+            headers: Vec::new(),
+            body: None,
+            basic_auth: None,
+            status_codes: Vec::new(),
+            response_contains: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Sets the HTTP method of the request, e.g. `POST`.
+    pub fn with_method<M: Into<String>>(self, method: M) -> Self {
+        let method = method.into();
+
+        if let Err(e) = reqwest::Method::from_bytes(method.as_bytes()) {
+            panic!("{} is not a valid HTTP method: {}", method, e)
+        }
+
+        HttpWaitStrategy { method, ..self }
+    }
+
+    /// Adds a header to the request.
+    pub fn with_header<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the body of the request.
+    pub fn with_body<B: Into<String>>(self, body: B) -> Self {
+        HttpWaitStrategy {
+            body: Some(body.into()),
+            ..self
+        }
+    }
+
+    /// Authenticates the request using HTTP basic authentication.
+    pub fn with_basic_auth<U: Into<String>, P: Into<String>>(
+        self,
+        username: U,
+        password: P,
+    ) -> Self {
+        HttpWaitStrategy {
+            basic_auth: Some((username.into(), password.into())),
+            ..self
+        }
+    }
+
+    /// Accepts responses with the given status code.
+    ///
+    /// Can be called multiple times to accept several status codes. Once a status code has been
+    /// configured, `200` is no longer accepted unless it is added explicitly.
+    pub fn with_expected_status_code(mut self, status_code: u16) -> Self {
+        self.status_codes.push(status_code);
+        self
+    }
+
+    /// Only accepts responses whose body contains the given text.
+    pub fn with_response_containing<S: Into<String>>(self, text: S) -> Self {
+        HttpWaitStrategy {
+            response_contains: Some(text.into()),
+            ..self
+        }
+    }
+
+    /// Sets the time to wait between two requests.
+    pub fn with_poll_interval(self, poll_interval: Duration) -> Self {
+        HttpWaitStrategy {
+            poll_interval,
+            ..self
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Blocks until the endpoint, reachable through the given host port, responds as expected or
+    /// the deadline passed.
+    ///
+    /// The requests are sent from a dedicated thread because the blocking `reqwest` client must
+    /// not be used from within an async runtime.
+    pub(crate) fn wait_until_ready(
+        &self,
+        host_port: u16,
+        deadline: Option<Instant>,
+    ) -> Result<(), WaitError> {
+        let strategy = self.clone();
+
+        std::thread::spawn(move || {
+            let client = reqwest::blocking::Client::new();
+            let url = strategy.url(host_port);
+
+            loop {
+                match strategy.send_blocking(&client, &url) {
+                    Ok(true) => return Ok(()),
+                    Ok(false) => log::trace!("{} did not respond as expected yet", url),
+                    Err(e) => log::trace!("{} is not reachable yet: {}", url, e),
+                }
+
+                if is_passed(deadline) {
+                    return Err(WaitError::Timeout);
+                }
+
+                std::thread::sleep(strategy.poll_interval);
+            }
+        })
+        .join()
+        .expect("thread waiting for the HTTP endpoint panicked")
+    }
+
+    /// Waits until the endpoint, reachable through the given host port, responds as expected or
+    /// the deadline passed.
+    pub(crate) async fn wait_until_ready_async(
+        &self,
+        host_port: u16,
+        deadline: Option<Instant>,
+    ) -> Result<(), WaitError> {
+        let client = reqwest::Client::new();
+        let url = self.url(host_port);
+
+        loop {
+            match self.send(&client, &url).await {
+                Ok(true) => return Ok(()),
+                Ok(false) => log::trace!("{} did not respond as expected yet", url),
+                Err(e) => log::trace!("{} is not reachable yet: {}", url, e),
+            }
+
+            if is_passed(deadline) {
+                return Err(WaitError::Timeout);
+            }
+
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    fn send_blocking(
+        &self,
+        client: &reqwest::blocking::Client,
+        url: &str,
+    ) -> Result<bool, reqwest::Error> {
+        let mut request = client.request(self.method(), url);
+
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        if let Some(body) = &self.body {
+            request = request.body(body.clone());
+        }
+        if let Some((username, password)) = &self.basic_auth {
+            request = request.basic_auth(username, Some(password));
+        }
+
+        let response = request.send()?;
+        let status = response.status().as_u16();
+        let body = response.text()?;
+
+        Ok(self.is_expected(status, &body))
+    }
+
+    async fn send(&self, client: &reqwest::Client, url: &str) -> Result<bool, reqwest::Error> {
+        let mut request = client.request(self.method(), url);
+
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        if let Some(body) = &self.body {
+            request = request.body(body.clone());
+        }
+        if let Some((username, password)) = &self.basic_auth {
+            request = request.basic_auth(username, Some(password));
+        }
+
+        let response = request.send().await?;
+        let status = response.status().as_u16();
+        let body = response.text().await?;
+
+        Ok(self.is_expected(status, &body))
+    }
+
+    fn url(&self, host_port: u16) -> String {
+        format!(
+            "http://localhost:{}/{}",
+            host_port,
+            self.path.trim_start_matches('/')
+        )
+    }
+
+    fn method(&self) -> reqwest::Method {
+        reqwest::Method::from_bytes(self.method.as_bytes()).expect("method is validated")
+    }
+
+    fn is_expected(&self, status: u16, body: &str) -> bool {
+        let status_matches = if self.status_codes.is_empty() {
+            status == 200
+        } else {
+            self.status_codes.contains(&status)
+        };
+        let body_matches = self
+            .response_contains
+            .as_ref()
+            .map_or(true, |text| body.contains(text.as_str()));
+
+        status_matches && body_matches
+    }
+}
+
+impl fmt::Display for HttpWaitStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} on port {} to respond",
+            self.method, self.path, self.port
+        )
+    }
+}
+
+/// Describes a condition on the inspected state of a container, which is checked repeatedly until
+/// it holds.
+///
+/// This is an escape hatch for readiness conditions none of the other [`WaitFor`] variants cover,
+/// e.g. a container that signals its readiness by exiting.
+///
+/// [`WaitFor`]: enum.WaitFor.html
+#[derive(Clone)]
+pub struct StateWaitStrategy {
+    description: String,
+    predicate: Arc<dyn Fn(&ContainerState) -> bool + Send + Sync>,
+    poll_interval: Duration,
+}
+
+impl StateWaitStrategy {
+    /// Creates a strategy that waits for the given predicate to hold. The description names the
+    /// condition in progress reports and panic messages.
+    pub fn new<D, F>(description: D, predicate: F) -> Self
+    where
+        D: Into<String>,
+        F: Fn(&ContainerState) -> bool + Send + Sync + 'static,
+    {
+        StateWaitStrategy {
+            description: description.into(),
+            predicate: Arc::new(predicate),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Sets the time to wait between two inspections of the container.
+    pub fn with_poll_interval(self, poll_interval: Duration) -> Self {
+        StateWaitStrategy {
+            poll_interval,
+            ..self
+        }
+    }
+
+    /// Blocks until the predicate holds for the state returned by `inspect` or the deadline
+    /// passed.
+    pub(crate) fn wait_until_ready<F>(
+        &self,
+        mut inspect: F,
+        deadline: Option<Instant>,
+    ) -> Result<(), WaitError>
+    where
+        F: FnMut() -> ContainerState,
+    {
+        loop {
+            if (self.predicate)(&inspect()) {
+                return Ok(());
+            }
+            log::trace!("container did not meet {} yet", self.description);
+
+            if is_passed(deadline) {
+                return Err(WaitError::Timeout);
+            }
+
+            std::thread::sleep(self.poll_interval);
+        }
+    }
+
+    /// Waits until the predicate holds for the state returned by `inspect` or the deadline
+    /// passed.
+    pub(crate) async fn wait_until_ready_async<F, Fut>(
+        &self,
+        mut inspect: F,
+        deadline: Option<Instant>,
+    ) -> Result<(), WaitError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ContainerState>,
+    {
+        loop {
+            if (self.predicate)(&inspect().await) {
+                return Ok(());
+            }
+            log::trace!("container did not meet {} yet", self.description);
+
+            if is_passed(deadline) {
+                return Err(WaitError::Timeout);
+            }
+
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
+
+impl fmt::Debug for StateWaitStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StateWaitStrategy")
+            .field("description", &self.description)
+            .field("poll_interval", &self.poll_interval)
+            .finish()
+    }
+}
+
+/// Two strategies are only equal if they share the same predicate, i.e. one is a clone of the
+/// other.
+impl PartialEq for StateWaitStrategy {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::as_ptr(&self.predicate) as *const () == Arc::as_ptr(&other.predicate) as *const ()
+            && self.description == other.description
+            && self.poll_interval == other.poll_interval
+    }
+}
+
+impl fmt::Display for StateWaitStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.description)
+    }
+}
+
+/// Sends the request from a dedicated thread, because the blocking `reqwest` client must not be
+/// used from within an async runtime.
+pub(crate) fn blocking_request<F>(request: F) -> Result<String, reqwest::Error>
+where
+    F: FnOnce(&reqwest::blocking::Client) -> reqwest::blocking::RequestBuilder + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let client = reqwest::blocking::Client::new();
+
+        request(&client).send()?.error_for_status()?.text()
+    })
+    .join()
+    .expect("thread sending the request panicked")
+}
+
+/// Builds a `POST` request with the given JSON body, for use with [`blocking_request`].
+pub(crate) fn json_post(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    body: &str,
+) -> reqwest::blocking::RequestBuilder {
+    client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body.to_owned())
+}
+
+fn is_passed(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+    };
+
+    #[test]
+    fn should_only_accept_200_by_default() {
+        let strategy = HttpWaitStrategy::new(80, "/");
+
+        assert!(strategy.is_expected(200, ""));
+        assert!(!strategy.is_expected(204, ""));
+        assert!(!strategy.is_expected(503, ""));
+    }
+
+    #[test]
+    fn should_accept_configured_status_codes_and_body() {
+        let strategy = HttpWaitStrategy::new(80, "/")
+            .with_expected_status_code(200)
+            .with_expected_status_code(401)
+            .with_response_containing("ok");
+
+        assert!(strategy.is_expected(401, "ok"));
+        assert!(!strategy.is_expected(200, "starting"));
+        assert!(!strategy.is_expected(500, "ok"));
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a valid HTTP method")]
+    fn should_reject_invalid_method() {
+        HttpWaitStrategy::new(80, "/").with_method("NOT A METHOD");
+    }
+
+    #[test]
+    fn should_retry_until_endpoint_responds_as_expected() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        std::thread::spawn(move || {
+            let responses = vec![
+                "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                "HTTP/1.1 200 OK\r\nContent-Length: 8\r\nConnection: close\r\n\r\nstarting",
+                "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+            ];
+
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0; 1024];
+                let _ = stream.read(&mut buf).unwrap();
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        HttpWaitStrategy::new(port, "/health")
+            .with_response_containing("ok")
+            .with_poll_interval(Duration::from_millis(10))
+            .wait_until_ready(port, None)
+            .unwrap();
+    }
+
+    #[test]
+    fn should_time_out_if_state_never_satisfies_predicate() {
+        let strategy = StateWaitStrategy::new("main process to run", |state| state.running())
+            .with_poll_interval(Duration::from_millis(10));
+
+        let result = strategy.wait_until_ready(
+            || ContainerState::new(Default::default()),
+            Some(Instant::now() + Duration::from_millis(50)),
+        );
+
+        assert!(matches!(result, Err(WaitError::Timeout)));
+        assert_eq!(strategy.to_string(), "main process to run");
+        assert_eq!(strategy.clone(), strategy);
+    }
+}
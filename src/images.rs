@@ -1,13 +1,63 @@
+pub mod arangodb;
+pub mod artemis;
+pub mod azurite;
+pub mod caddy;
+pub mod cassandra;
+pub mod centrifugo;
+pub mod clamav;
 pub mod coblox_bitcoincore;
+pub mod couchbase;
+pub mod couchdb;
+pub mod dex;
+pub mod dragonfly;
 pub mod dynamodb_local;
 pub mod elasticmq;
+pub mod electrs;
+pub mod eventstore;
+pub mod ftp;
+pub mod gcloud;
+pub mod gcs;
 pub mod generic;
+pub mod geth;
+pub mod grafana;
+pub mod haproxy;
 pub mod hello_world;
+pub mod jaeger;
+pub mod k3s;
 pub mod kafka;
+pub mod mailpit;
+pub mod meilisearch;
+pub mod minio;
 pub mod mongo;
+pub mod mosquitto;
+pub mod nginx;
+pub mod node;
+pub mod opa;
+pub mod openldap;
+#[cfg(feature = "oracle")]
+pub mod oracle;
 pub mod orientdb;
+pub mod otel_collector;
 pub mod parity_parity;
 pub mod postgres;
+pub mod prometheus;
+pub mod questdb;
 pub mod redis;
+pub mod redpanda;
+pub mod registry;
+pub mod schema_registry;
+pub mod sftp;
+pub mod solana;
+pub mod solr;
+pub mod surrealdb;
+pub mod temporal;
+pub mod tika;
+pub mod toxiproxy;
+pub mod trino;
 pub mod trufflesuite_ganachecli;
+pub mod unleash;
+pub mod verdaccio;
+pub mod victoriametrics;
+pub mod wiremock;
 pub mod zookeeper;
+pub mod zot;
@@ -0,0 +1,180 @@
+use crate::{
+    core::{ContainerState, ExecCommand, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "arangodb";
+const DEFAULT_TAG: &str = "3.11.5";
+
+const ROOT_USER: &str = "root";
+const DEFAULT_ROOT_PASSWORD: &str = "password";
+
+/// Port of the HTTP API and web interface.
+pub const PORT: u16 = 8529;
+
+#[derive(Debug, Default, Clone)]
+pub struct ArangoDbArgs;
+
+impl IntoIterator for ArangoDbArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A single ArangoDB server.
+///
+/// By default, the `root` user is protected by a password. The configured databases are created
+/// through `arangosh` once the server is ready.
+#[derive(Debug)]
+pub struct ArangoDb {
+    tag: String,
+    arguments: ArangoDbArgs,
+    root_password: Option<String>,
+    databases: Vec<String>,
+}
+
+impl Default for ArangoDb {
+    fn default() -> Self {
+        ArangoDb {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: ArangoDbArgs {},
+            root_password: Some(DEFAULT_ROOT_PASSWORD.to_string()),
+            databases: Vec::new(),
+        }
+    }
+}
+
+impl Image for ArangoDb {
+    type Args = ArangoDbArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        let strategy = HttpWaitStrategy::new(PORT, "/_api/version");
+
+        let strategy = match &self.root_password {
+            Some(password) => strategy.with_basic_auth(ROOT_USER, password.clone()),
+            None => strategy,
+        };
+
+        vec![WaitFor::http(strategy)]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+
+        match &self.root_password {
+            Some(password) => {
+                env_vars.insert("ARANGO_ROOT_PASSWORD".to_owned(), password.clone());
+            }
+            None => {
+                env_vars.insert("ARANGO_NO_AUTH".to_owned(), "1".to_owned());
+            }
+        }
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        ArangoDb { arguments, ..self }
+    }
+
+    fn exec_after_start(&self, _: ContainerState) -> Vec<ExecCommand> {
+        self.databases
+            .iter()
+            .map(|database| {
+                let mut cmd = vec![
+                    "arangosh".to_owned(),
+                    "--server.endpoint".to_owned(),
+                    format!("tcp://127.0.0.1:{}", PORT),
+                    "--javascript.execute-string".to_owned(),
+                    format!("db._createDatabase({:?})", database),
+                ];
+
+                match &self.root_password {
+                    Some(password) => cmd.extend(vec![
+                        "--server.username".to_owned(),
+                        ROOT_USER.to_owned(),
+                        "--server.password".to_owned(),
+                        password.clone(),
+                    ]),
+                    None => cmd.extend(vec![
+                        "--server.authentication".to_owned(),
+                        "false".to_owned(),
+                    ]),
+                }
+
+                ExecCommand::new(cmd)
+            })
+            .collect()
+    }
+
+    fn named_ports(&self) -> HashMap<String, u16> {
+        let mut ports = HashMap::new();
+        ports.insert("http".to_owned(), PORT);
+
+        ports
+    }
+}
+
+impl ArangoDb {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        ArangoDb {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the password of the `root` user.
+    pub fn with_root_password<P: Into<String>>(self, password: P) -> Self {
+        ArangoDb {
+            root_password: Some(password.into()),
+            ..self
+        }
+    }
+
+    /// Disables authentication, the server can be accessed without any credentials.
+    pub fn without_auth(self) -> Self {
+        ArangoDb {
+            root_password: None,
+            ..self
+        }
+    }
+
+    /// Adds a database that is created once the server is ready.
+    pub fn with_database<N: Into<String>>(mut self, name: N) -> Self {
+        self.databases.push(name.into());
+        self
+    }
+
+    pub fn username(&self) -> &str {
+        ROOT_USER
+    }
+
+    /// Returns the password of the `root` user, or `None` if authentication is disabled.
+    pub fn password(&self) -> Option<&str> {
+        self.root_password.as_deref()
+    }
+
+    /// Returns the url of the server, given the host port mapped to [`PORT`].
+    pub fn url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+}
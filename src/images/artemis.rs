@@ -0,0 +1,129 @@
+use crate::{core::WaitFor, Image};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "apache/activemq-artemis";
+const DEFAULT_TAG: &str = "2.31.2";
+
+const DEFAULT_USER: &str = "artemis";
+const DEFAULT_PASSWORD: &str = "artemis";
+
+/// Port of the core protocol (also serves OpenWire and JMS clients).
+pub const CORE_PORT: u16 = 61616;
+/// Port of the AMQP 1.0 acceptor.
+pub const AMQP_PORT: u16 = 5672;
+/// Port of the web console.
+pub const CONSOLE_PORT: u16 = 8161;
+
+#[derive(Debug, Default, Clone)]
+pub struct ActiveMqArtemisArgs;
+
+impl IntoIterator for ActiveMqArtemisArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+#[derive(Debug)]
+pub struct ActiveMqArtemis {
+    tag: String,
+    arguments: ActiveMqArtemisArgs,
+    user: String,
+    password: String,
+    anonymous_login: bool,
+}
+
+impl Default for ActiveMqArtemis {
+    fn default() -> Self {
+        ActiveMqArtemis {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: ActiveMqArtemisArgs {},
+            user: DEFAULT_USER.to_string(),
+            password: DEFAULT_PASSWORD.to_string(),
+            anonymous_login: false,
+        }
+    }
+}
+
+impl Image for ActiveMqArtemis {
+    type Args = ActiveMqArtemisArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::message_on_stdout("Server is now live")]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("ARTEMIS_USER".to_owned(), self.user.clone());
+        env_vars.insert("ARTEMIS_PASSWORD".to_owned(), self.password.clone());
+        env_vars.insert(
+            "ANONYMOUS_LOGIN".to_owned(),
+            self.anonymous_login.to_string(),
+        );
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        ActiveMqArtemis { arguments, ..self }
+    }
+}
+
+impl ActiveMqArtemis {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        ActiveMqArtemis {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the credentials of the broker's admin user.
+    pub fn with_user<U: Into<String>, P: Into<String>>(self, user: U, password: P) -> Self {
+        ActiveMqArtemis {
+            user: user.into(),
+            password: password.into(),
+            ..self
+        }
+    }
+
+    /// Allows clients to connect without providing any credentials.
+    pub fn with_anonymous_login(self, anonymous_login: bool) -> Self {
+        ActiveMqArtemis {
+            anonymous_login,
+            ..self
+        }
+    }
+
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    /// Returns the AMQP url of the broker, given the host port mapped to [`AMQP_PORT`].
+    pub fn amqp_url(&self, host_port: u16) -> String {
+        format!(
+            "amqp://{}:{}@localhost:{}",
+            self.user, self.password, host_port
+        )
+    }
+}
@@ -0,0 +1,325 @@
+use crate::{core::WaitFor, Image};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "mcr.microsoft.com/azure-storage/azurite";
+const DEFAULT_TAG: &str = "3.28.0";
+
+/// Port of the Blob service.
+pub const BLOB_PORT: u16 = 10000;
+/// Port of the Queue service.
+pub const QUEUE_PORT: u16 = 10001;
+/// Port of the Table service.
+pub const TABLE_PORT: u16 = 10002;
+
+/// The name of the well-known development storage account.
+pub const ACCOUNT_NAME: &str = "devstoreaccount1";
+/// The key of the well-known development storage account.
+pub const ACCOUNT_KEY: &str =
+    "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+
+/// A storage service emulated by Azurite.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Service {
+    Blob,
+    Queue,
+    Table,
+}
+
+impl Service {
+    fn name(self) -> &'static str {
+        match self {
+            Service::Blob => "blob",
+            Service::Queue => "queue",
+            Service::Table => "table",
+        }
+    }
+
+    fn ready_message(self) -> &'static str {
+        match self {
+            Service::Blob => "Azurite Blob service is successfully listening",
+            Service::Queue => "Azurite Queue service is successfully listening",
+            Service::Table => "Azurite Table service is successfully listening",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AzuriteArgs {
+    services: Vec<Service>,
+    loose: bool,
+    skip_api_version_check: bool,
+}
+
+impl Default for AzuriteArgs {
+    fn default() -> Self {
+        AzuriteArgs {
+            services: vec![Service::Blob, Service::Queue, Service::Table],
+            loose: false,
+            skip_api_version_check: false,
+        }
+    }
+}
+
+impl AzuriteArgs {
+    fn flags(&self) -> Vec<String> {
+        let mut flags = vec!["--location".to_owned(), "/data".to_owned()];
+
+        if self.loose {
+            flags.push("--loose".to_owned());
+        }
+        if self.skip_api_version_check {
+            flags.push("--skipApiVersionCheck".to_owned());
+        }
+
+        flags
+    }
+}
+
+impl IntoIterator for AzuriteArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        let host =
+            |service: Service| vec![format!("--{}Host", service.name()), "0.0.0.0".to_owned()];
+
+        let args = match self.services.as_slice() {
+            [Service::Blob, Service::Queue, Service::Table] => {
+                let mut args = vec!["azurite".to_owned()];
+                args.extend(self.services.iter().flat_map(|service| host(*service)));
+                args.extend(self.flags());
+                args
+            }
+            [service] => {
+                let mut args = vec![format!("azurite-{}", service.name())];
+                args.extend(host(*service));
+                args.extend(self.flags());
+                args
+            }
+            // every service ships its own binary, run them side by side
+            services => {
+                let flags = self.flags().join(" ");
+                let script = services
+                    .iter()
+                    .map(|service| {
+                        format!(
+                            "azurite-{} {} {} &",
+                            service.name(),
+                            host(*service).join(" "),
+                            flags
+                        )
+                    })
+                    .chain(Some("wait".to_owned()))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                vec!["sh".to_owned(), "-c".to_owned(), script]
+            }
+        };
+
+        args.into_iter()
+    }
+}
+
+/// An Azurite instance, emulating Azure Blob, Queue and Table storage.
+///
+/// All services accept the well-known development storage account [`ACCOUNT_NAME`], see
+/// [`blob_connection_string`] for connecting clients to it.
+///
+/// [`blob_connection_string`]: struct.Azurite.html#method.blob_connection_string
+#[derive(Debug)]
+pub struct Azurite {
+    tag: String,
+    arguments: AzuriteArgs,
+}
+
+impl Default for Azurite {
+    fn default() -> Self {
+        Azurite {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: AzuriteArgs::default(),
+        }
+    }
+}
+
+impl Image for Azurite {
+    type Args = AzuriteArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        self.arguments
+            .services
+            .iter()
+            .map(|service| WaitFor::message_on_stdout(service.ready_message()))
+            .collect()
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Azurite { arguments, ..self }
+    }
+}
+
+impl Azurite {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Azurite {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Only starts the given services, all of them are started by default.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if no service is given.
+    pub fn with_services<S: IntoIterator<Item = Service>>(self, services: S) -> Self {
+        let services = services.into_iter().collect::<Vec<_>>();
+        assert!(!services.is_empty(), "at least one service must be enabled");
+
+        // bring the services into a canonical order, so all of them end up in one process
+        let services = vec![Service::Blob, Service::Queue, Service::Table]
+            .into_iter()
+            .filter(|service| services.contains(service))
+            .collect();
+
+        Azurite {
+            arguments: AzuriteArgs {
+                services,
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Disables the strict mode, which rejects unsupported headers and query parameters.
+    pub fn with_loose(self, loose: bool) -> Self {
+        Azurite {
+            arguments: AzuriteArgs {
+                loose,
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Accepts requests with API versions newer than the emulator knows about.
+    ///
+    /// Newer SDKs are often released before the emulator supports their API version.
+    pub fn with_skip_api_version_check(self, skip_api_version_check: bool) -> Self {
+        Azurite {
+            arguments: AzuriteArgs {
+                skip_api_version_check,
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Returns the connection string of the Blob service, given the host port mapped to
+    /// [`BLOB_PORT`].
+    pub fn blob_connection_string(&self, host_port: u16) -> String {
+        connection_string("BlobEndpoint", host_port)
+    }
+
+    /// Returns the connection string of the Queue service, given the host port mapped to
+    /// [`QUEUE_PORT`].
+    pub fn queue_connection_string(&self, host_port: u16) -> String {
+        connection_string("QueueEndpoint", host_port)
+    }
+
+    /// Returns the connection string of the Table service, given the host port mapped to
+    /// [`TABLE_PORT`].
+    pub fn table_connection_string(&self, host_port: u16) -> String {
+        connection_string("TableEndpoint", host_port)
+    }
+}
+
+fn connection_string(endpoint: &str, host_port: u16) -> String {
+    format!(
+        "DefaultEndpointsProtocol=http;AccountName={account};AccountKey={key};{endpoint}=http://127.0.0.1:{port}/{account};",
+        account = ACCOUNT_NAME,
+        key = ACCOUNT_KEY,
+        endpoint = endpoint,
+        port = host_port
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_start_all_services_in_one_process_by_default() {
+        let args = AzuriteArgs::default().into_iter().collect::<Vec<_>>();
+
+        assert_eq!(
+            args,
+            vec![
+                "azurite",
+                "--blobHost",
+                "0.0.0.0",
+                "--queueHost",
+                "0.0.0.0",
+                "--tableHost",
+                "0.0.0.0",
+                "--location",
+                "/data",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_start_single_service_with_its_own_binary() {
+        let image = Azurite::default()
+            .with_services(vec![Service::Blob])
+            .with_loose(true);
+
+        assert_eq!(
+            image.args().into_iter().collect::<Vec<_>>(),
+            vec![
+                "azurite-blob",
+                "--blobHost",
+                "0.0.0.0",
+                "--location",
+                "/data",
+                "--loose"
+            ]
+        );
+        assert_eq!(image.ready_conditions().len(), 1);
+    }
+
+    #[test]
+    fn should_start_several_services_side_by_side() {
+        let image = Azurite::default()
+            .with_services(vec![Service::Table, Service::Blob])
+            .with_skip_api_version_check(true);
+
+        assert_eq!(
+            image.args().into_iter().collect::<Vec<_>>(),
+            vec![
+                "sh",
+                "-c",
+                "azurite-blob --blobHost 0.0.0.0 --location /data --skipApiVersionCheck & \
+                 azurite-table --tableHost 0.0.0.0 --location /data --skipApiVersionCheck & wait"
+            ]
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{
+    core::{
+        wait::{blocking_request, json_post},
+        Container, CopyToContainer, HttpWaitStrategy, WaitFor,
+    },
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "caddy";
+const DEFAULT_TAG: &str = "2.7.6-alpine";
+
+/// Port of the HTTP server.
+pub const PORT: u16 = 80;
+/// Port of the HTTPS server, only listening if the config serves a site over TLS.
+pub const TLS_PORT: u16 = 443;
+/// Port of the admin API.
+pub const ADMIN_PORT: u16 = 2019;
+
+const CADDYFILE: &str = "/etc/caddy/Caddyfile";
+const ROOT_CERTIFICATE_FILE: &str = "/data/caddy/pki/authorities/local/root.crt";
+
+/// The admin API only listens on the loopback interface of the container unless configured
+/// otherwise.
+const ADMIN_LISTEN: &str = "0.0.0.0:2019";
+
+const DEFAULT_CADDYFILE: &str = "{
+	admin 0.0.0.0:2019
+}
+
+:80 {
+	respond \"Hello from Caddy\"
+}
+";
+
+#[derive(Debug, Default, Clone)]
+pub struct CaddyArgs;
+
+impl IntoIterator for CaddyArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A Caddy web server, answering `Hello from Caddy` on [`PORT`] by default.
+///
+/// The config can be replaced while the container runs through [`load_config`], e.g. to proxy to
+/// a different upstream in the middle of a test. A Caddyfile passed through [`with_caddyfile`]
+/// needs to keep `admin 0.0.0.0:2019` in its global options, as the server is ready once the
+/// admin API answers on [`ADMIN_PORT`].
+///
+/// [`load_config`]: struct.Caddy.html#method.load_config
+/// [`with_caddyfile`]: struct.Caddy.html#method.with_caddyfile
+#[derive(Debug)]
+pub struct Caddy {
+    tag: String,
+    arguments: CaddyArgs,
+    caddyfile: Vec<u8>,
+}
+
+impl Default for Caddy {
+    fn default() -> Self {
+        Caddy {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: CaddyArgs,
+            caddyfile: DEFAULT_CADDYFILE.into(),
+        }
+    }
+}
+
+impl Image for Caddy {
+    type Args = CaddyArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(ADMIN_PORT, "/config/"))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Caddy { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        vec![CopyToContainer::new(self.caddyfile.clone(), CADDYFILE)]
+    }
+
+    fn expose_ports(&self) -> Vec<u16> {
+        vec![PORT, TLS_PORT, ADMIN_PORT]
+    }
+}
+
+impl Caddy {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Caddy {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Replaces `/etc/caddy/Caddyfile`, which the server is started with.
+    pub fn with_caddyfile<C: Into<Vec<u8>>>(self, caddyfile: C) -> Self {
+        Caddy {
+            caddyfile: caddyfile.into(),
+            ..self
+        }
+    }
+
+    /// Returns the url of the HTTP server, given the host port mapped to [`PORT`], e.g.
+    /// `http://localhost:32768`.
+    pub fn base_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Replaces the running config with the given JSON config through the admin API, given the
+    /// host port mapped to [`ADMIN_PORT`]. A config without an `admin` section keeps the admin
+    /// API listening on [`ADMIN_PORT`], so it can be reconfigured again.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the admin API cannot be reached or rejects the config.
+    pub fn load_config(&self, host_port: u16, config: &serde_json::Value) {
+        let url = format!("http://localhost:{}/load", host_port);
+        let body = with_admin_listen(config).to_string();
+
+        blocking_request(move |client| json_post(client, &url, &body))
+            .unwrap_or_else(|e| panic!("failed to load config into caddy: {}", e));
+    }
+}
+
+impl<'d> Container<'d, Caddy> {
+    /// Returns the PEM encoded root certificate of the internal CA of Caddy, which signs the
+    /// certificates of sites served through `tls internal` or of `localhost`, so clients can
+    /// trust them, e.g. through `reqwest::Certificate::from_pem`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the certificate cannot be read from the container, e.g. because no
+    /// site uses the internal CA yet.
+    pub fn root_certificate(&self) -> Vec<u8> {
+        self.copy_file_from(ROOT_CERTIFICATE_FILE)
+    }
+}
+
+fn with_admin_listen(config: &serde_json::Value) -> serde_json::Value {
+    let mut config = config.clone();
+    if let Some(config) = config.as_object_mut() {
+        config
+            .entry("admin")
+            .or_insert_with(|| serde_json::json!({ "listen": ADMIN_LISTEN }));
+    }
+
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_keep_admin_api_reachable_unless_configured() {
+        let apps = serde_json::json!({ "apps": {} });
+        assert_eq!(
+            with_admin_listen(&apps),
+            serde_json::json!({ "admin": { "listen": "0.0.0.0:2019" }, "apps": {} })
+        );
+
+        let disabled = serde_json::json!({ "admin": { "disabled": true } });
+        assert_eq!(with_admin_listen(&disabled), disabled);
+    }
+}
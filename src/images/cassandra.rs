@@ -0,0 +1,112 @@
+use crate::{core::WaitFor, Image};
+use std::{collections::HashMap, time::Duration};
+
+const CONTAINER_IDENTIFIER: &str = "cassandra";
+const DEFAULT_TAG: &str = "4.1.3";
+
+/// Port of the CQL native protocol.
+pub const PORT: u16 = 9042;
+
+/// Even a single node with a small heap takes a minute or more to boot.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Default, Clone)]
+pub struct CassandraArgs;
+
+impl IntoIterator for CassandraArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A single Cassandra node.
+///
+/// The node runs with a small heap and skips waiting for gossip to settle, as there are no other
+/// nodes to gossip with. It is ready once it accepts CQL clients on [`PORT`].
+#[derive(Debug)]
+pub struct Cassandra {
+    tag: String,
+    arguments: CassandraArgs,
+    max_heap_size: String,
+}
+
+impl Default for Cassandra {
+    fn default() -> Self {
+        Cassandra {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: CassandraArgs,
+            max_heap_size: "512M".to_owned(),
+        }
+    }
+}
+
+impl Image for Cassandra {
+    type Args = CassandraArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::message_on_stdout(
+            "Starting listening for CQL clients",
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("MAX_HEAP_SIZE".to_owned(), self.max_heap_size.clone());
+        // the start script requires the size of the young generation whenever the heap is set
+        env_vars.insert("HEAP_NEWSIZE".to_owned(), "100M".to_owned());
+        env_vars.insert(
+            "JVM_OPTS".to_owned(),
+            "-Dcassandra.skip_wait_for_gossip_to_settle=0 -Dcassandra.initial_token=0".to_owned(),
+        );
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Cassandra { arguments, ..self }
+    }
+
+    fn startup_timeout(&self) -> Option<Duration> {
+        Some(STARTUP_TIMEOUT)
+    }
+}
+
+impl Cassandra {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Cassandra {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the maximum heap size of the JVM, `512M` by default.
+    pub fn with_max_heap_size<S: Into<String>>(self, max_heap_size: S) -> Self {
+        Cassandra {
+            max_heap_size: max_heap_size.into(),
+            ..self
+        }
+    }
+
+    /// Returns the contact point of CQL drivers, given the host port mapped to [`PORT`].
+    pub fn contact_point(&self, host_port: u16) -> String {
+        format!("localhost:{}", host_port)
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{
+    core::{wait::blocking_request, CopyToContainer, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "centrifugo/centrifugo";
+const DEFAULT_TAG: &str = "v5.1.2";
+
+/// Port of the websocket endpoint, the server API and the health check.
+pub const PORT: u16 = 8000;
+
+const CONFIG_FILE: &str = "/centrifugo/config.json";
+const DEFAULT_API_KEY: &str = "testcontainers";
+
+#[derive(Debug, Default, Clone)]
+pub struct CentrifugoArgs;
+
+impl IntoIterator for CentrifugoArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![
+            "centrifugo".to_owned(),
+            "--config".to_owned(),
+            CONFIG_FILE.to_owned(),
+        ]
+        .into_iter()
+    }
+}
+
+/// A Centrifugo realtime messaging server, which lets anonymous clients connect and subscribe to
+/// any channel by default.
+///
+/// Messages are published through the server API, e.g. through [`publish`], with the key set
+/// through [`with_api_key`]. A config passed through [`with_config`] replaces the generated one
+/// including the API key and allowed origins, and needs to keep `"health": true`, as the server
+/// is ready once `/health` answers.
+///
+/// [`publish`]: struct.Centrifugo.html#method.publish
+/// [`with_api_key`]: struct.Centrifugo.html#method.with_api_key
+/// [`with_config`]: struct.Centrifugo.html#method.with_config
+#[derive(Debug)]
+pub struct Centrifugo {
+    tag: String,
+    arguments: CentrifugoArgs,
+    config: Option<Vec<u8>>,
+    api_key: String,
+    allowed_origins: Vec<String>,
+}
+
+impl Default for Centrifugo {
+    fn default() -> Self {
+        Centrifugo {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: CentrifugoArgs,
+            config: None,
+            api_key: DEFAULT_API_KEY.to_owned(),
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+impl Image for Centrifugo {
+    type Args = CentrifugoArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(PORT, "/health"))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Centrifugo { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        let config = match &self.config {
+            Some(config) => config.clone(),
+            None => self.generated_config().into_bytes(),
+        };
+
+        vec![CopyToContainer::new(config, CONFIG_FILE)]
+    }
+}
+
+impl Centrifugo {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Centrifugo {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Replaces the generated config with the given JSON, e.g. to require tokens signed with
+    /// `token_hmac_secret_key`.
+    pub fn with_config<C: Into<Vec<u8>>>(self, config: C) -> Self {
+        Centrifugo {
+            config: Some(config.into()),
+            ..self
+        }
+    }
+
+    /// Sets the key of the server API, `testcontainers` by default.
+    pub fn with_api_key<K: Into<String>>(self, api_key: K) -> Self {
+        Centrifugo {
+            api_key: api_key.into(),
+            ..self
+        }
+    }
+
+    /// Allows browsers to connect from the given origin, e.g. `http://localhost:3000`. Clients
+    /// that do not send an `Origin` header, like most non-browser clients, are always allowed.
+    pub fn with_allowed_origin<O: Into<String>>(mut self, origin: O) -> Self {
+        self.allowed_origins.push(origin.into());
+        self
+    }
+
+    /// Returns the key of the server API.
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    /// Returns the url of the websocket endpoint, given the host port mapped to [`PORT`].
+    pub fn ws_url(&self, host_port: u16) -> String {
+        format!("ws://localhost:{}/connection/websocket", host_port)
+    }
+
+    /// Returns the url of the server API, given the host port mapped to [`PORT`].
+    pub fn api_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}/api", host_port)
+    }
+
+    /// Publishes the data to all subscribers of the channel through the server API, given the
+    /// host port mapped to [`PORT`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the server cannot be reached or rejects the publication, e.g.
+    /// because the API key does not match a custom config.
+    pub fn publish(&self, host_port: u16, channel: &str, data: &serde_json::Value) {
+        let url = format!("{}/publish", self.api_url(host_port));
+        let body = serde_json::json!({ "channel": channel, "data": data }).to_string();
+        let api_key = self.api_key.clone();
+
+        let response = blocking_request(move |client| {
+            client
+                .post(&url)
+                .header("X-API-Key", api_key)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body)
+        })
+        .unwrap_or_else(|e| panic!("failed to publish to {}: {}", channel, e));
+
+        // errors of the command are reported with status 200
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap_or_else(|e| {
+            panic!("failed to parse response publishing to {}: {}", channel, e)
+        });
+        if let Some(error) = response.get("error") {
+            panic!("failed to publish to {}: {}", channel, error)
+        }
+    }
+
+    fn generated_config(&self) -> String {
+        serde_json::json!({
+            "api_key": self.api_key,
+            "allowed_origins": self.allowed_origins,
+            "health": true,
+            "allow_anonymous_connect_without_token": true,
+            "allow_subscribe_for_anonymous": true,
+            "allow_subscribe_for_client": true,
+        })
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_generate_config_from_builders() {
+        let image = Centrifugo::default()
+            .with_api_key("secret")
+            .with_allowed_origin("http://localhost:3000");
+        let config: serde_json::Value = serde_json::from_str(&image.generated_config()).unwrap();
+
+        assert_eq!(config["api_key"], "secret");
+        assert_eq!(
+            config["allowed_origins"],
+            serde_json::json!(["http://localhost:3000"])
+        );
+        assert_eq!(config["health"], true);
+    }
+}
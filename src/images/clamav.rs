@@ -0,0 +1,172 @@
+use crate::{core::WaitFor, Image};
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+    net::TcpStream,
+    time::Duration,
+};
+
+const CONTAINER_IDENTIFIER: &str = "clamav/clamav";
+const DEFAULT_TAG: &str = "1.2.1";
+
+/// Port of the clamd protocol.
+pub const PORT: u16 = 3310;
+
+/// clamd loads the signature databases bundled with the image before it accepts clients, which
+/// takes a minute or more.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Size of the chunks a stream is sent to clamd in, well below its default `StreamMaxLength`.
+const CHUNK_SIZE: usize = 8192;
+
+#[derive(Debug, Default, Clone)]
+pub struct ClamAvArgs;
+
+impl IntoIterator for ClamAvArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A clamd virus scanner, scanning streams sent to [`PORT`], e.g. through [`scan_bytes`].
+///
+/// The scanner runs with the signature databases bundled with the image. It does not update
+/// them through `freshclam`, so tests neither wait for the download nor reach the internet.
+///
+/// [`scan_bytes`]: struct.ClamAv.html#method.scan_bytes
+#[derive(Debug)]
+pub struct ClamAv {
+    tag: String,
+    arguments: ClamAvArgs,
+}
+
+/// The verdict of clamd on scanned data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanResult {
+    Clean,
+    /// The data matches the signature with the given name, e.g. `Win.Test.EICAR_HDB-1`.
+    Infected(String),
+}
+
+impl Default for ClamAv {
+    fn default() -> Self {
+        ClamAv {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: ClamAvArgs,
+        }
+    }
+}
+
+impl Image for ClamAv {
+    type Args = ClamAvArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        // logged by the entrypoint once the socket of clamd exists
+        vec![WaitFor::message_on_stdout("socket found, clamd started.")]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("CLAMAV_NO_FRESHCLAMD".to_owned(), "true".to_owned());
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        ClamAv { arguments, ..self }
+    }
+
+    fn startup_timeout(&self) -> Option<Duration> {
+        Some(STARTUP_TIMEOUT)
+    }
+}
+
+impl ClamAv {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        ClamAv {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Returns the address clamd clients connect to, given the host port mapped to [`PORT`], e.g.
+    /// `localhost:32768`.
+    pub fn clamd_addr(&self, host_port: u16) -> String {
+        format!("localhost:{}", host_port)
+    }
+
+    /// Scans the data through the `INSTREAM` command of clamd, given the host port mapped to
+    /// [`PORT`]. The EICAR test file is detected as `Win.Test.EICAR_HDB-1`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if clamd cannot be reached or fails to scan the data, e.g. because it
+    /// exceeds `StreamMaxLength`.
+    pub fn scan_bytes(&self, host_port: u16, data: &[u8]) -> ScanResult {
+        let scan = || -> io::Result<String> {
+            let mut stream = TcpStream::connect(("localhost", host_port))?;
+            stream.write_all(b"zINSTREAM\0")?;
+
+            for chunk in data.chunks(CHUNK_SIZE) {
+                stream.write_all(&(chunk.len() as u32).to_be_bytes())?;
+                stream.write_all(chunk)?;
+            }
+            stream.write_all(&[0; 4])?;
+
+            // clamd closes the connection after answering a single command
+            let mut response = String::new();
+            stream.read_to_string(&mut response)?;
+
+            Ok(response)
+        };
+
+        let response = scan().unwrap_or_else(|e| panic!("failed to send data to clamd: {}", e));
+
+        parse_scan_response(&response)
+    }
+}
+
+fn parse_scan_response(response: &str) -> ScanResult {
+    let verdict = response.trim_end_matches('\0').trim_end();
+    let verdict = verdict.strip_prefix("stream: ").unwrap_or(verdict);
+
+    if verdict == "OK" {
+        return ScanResult::Clean;
+    }
+    match verdict.strip_suffix(" FOUND") {
+        Some(signature) => ScanResult::Infected(signature.to_owned()),
+        None => panic!("clamd failed to scan the data: {}", verdict),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_verdict_of_instream() {
+        assert_eq!(parse_scan_response("stream: OK\0"), ScanResult::Clean);
+        assert_eq!(
+            parse_scan_response("stream: Win.Test.EICAR_HDB-1 FOUND\0"),
+            ScanResult::Infected("Win.Test.EICAR_HDB-1".to_owned())
+        );
+    }
+}
@@ -1,11 +1,12 @@
-use crate::core::{Image, WaitFor};
+use crate::core::{Container, ContainerAsync, ExecCommand, ExecOutput, Image, WaitFor};
 use hex::encode;
 use hmac::{Hmac, Mac, NewMac};
 use rand::{thread_rng, Rng};
 use sha2::Sha256;
 use std::{collections::HashMap, fmt};
 
-const BITCOIND_STARTUP_MESSAGE: &str = "bitcoind startup sequence completed.";
+/// Logged once bitcoind finished loading the chain and its RPC interface exits the warm-up.
+const BITCOIND_READY_MESSAGE: &str = "init message: Done loading";
 
 #[derive(Debug)]
 pub struct BitcoinCore {
@@ -17,6 +18,63 @@ impl BitcoinCore {
     pub fn auth(&self) -> &RpcAuth {
         &self.arguments.rpc_auth
     }
+
+    /// Returns the username and password to authenticate against the RPC interface.
+    pub fn rpc_credentials(&self) -> (&str, &str) {
+        (
+            self.arguments.rpc_auth.username(),
+            self.arguments.rpc_auth.password(),
+        )
+    }
+
+    /// Returns the port of the RPC interface in the container, which depends on the network.
+    pub fn rpc_port(&self) -> u16 {
+        match self.arguments.network {
+            Network::Mainnet => 8332,
+            Network::Testnet => 18332,
+            Network::Regtest => 18443,
+        }
+    }
+
+    /// Returns the port of the P2P interface in the container, which depends on the network.
+    pub fn p2p_port(&self) -> u16 {
+        match self.arguments.network {
+            Network::Mainnet => 8333,
+            Network::Testnet => 18333,
+            Network::Regtest => 18444,
+        }
+    }
+
+    pub fn network(&self) -> &Network {
+        &self.arguments.network
+    }
+
+    /// Returns the url of the RPC interface, given the host port mapped to [`rpc_port`].
+    ///
+    /// [`rpc_port`]: struct.BitcoinCore.html#method.rpc_port
+    pub fn rpc_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    fn generate_blocks_command(&self, blocks: u32, address: &str) -> ExecCommand {
+        let mut cmd = vec![
+            "bitcoin-cli".to_owned(),
+            format!("-rpcuser={}", self.arguments.rpc_auth.username()),
+            format!("-rpcpassword={}", self.arguments.rpc_auth.password()),
+        ];
+
+        match self.arguments.network {
+            Network::Testnet => cmd.push("-testnet".to_owned()),
+            Network::Regtest => cmd.push("-regtest".to_owned()),
+            Network::Mainnet => {}
+        }
+
+        cmd.push("generatetoaddress".to_owned());
+        cmd.push(blocks.to_string());
+        cmd.push(address.to_owned());
+
+        ExecCommand::new(cmd)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -51,6 +109,15 @@ pub struct RpcAuth {
 }
 
 impl RpcAuth {
+    /// Uses the given password instead of generating one.
+    pub fn with_password(username: String, password: String) -> Self {
+        RpcAuth {
+            username,
+            password,
+            salt: Self::generate_salt(),
+        }
+    }
+
     pub fn username(&self) -> &str {
         &self.username
     }
@@ -137,8 +204,6 @@ impl IntoIterator for BitcoinCoreImageArgs {
     fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
         let mut args = vec![
             format!("-rpcauth={}", self.rpc_auth.encode()),
-            // Will print a message when bitcoind is fully started
-            format!("-startupnotify='echo \'{}\''", BITCOIND_STARTUP_MESSAGE),
             format!("-addresstype={}", self.address_type),
         ];
 
@@ -199,10 +264,8 @@ impl Image for BitcoinCore {
     }
 
     fn ready_conditions(&self) -> Vec<WaitFor> {
-        vec![
-            WaitFor::message_on_stdout(BITCOIND_STARTUP_MESSAGE),
-            WaitFor::millis_in_env_var("BITCOIND_ADDITIONAL_SLEEP_PERIOD"),
-        ]
+        // only logged if `print_to_console` is enabled
+        vec![WaitFor::message_on_stdout(BITCOIND_READY_MESSAGE)]
     }
 
     fn args(&self) -> <Self as Image>::Args {
@@ -238,12 +301,118 @@ impl BitcoinCore {
             ..self
         }
     }
+
+    /// Sets the credentials of the RPC interface instead of generating a random password.
+    ///
+    /// The password is salted and hashed into the `-rpcauth` argument, hence it never shows up in
+    /// the process list of the container.
+    pub fn with_rpc_auth<U: Into<String>, P: Into<String>>(self, username: U, password: P) -> Self {
+        BitcoinCore {
+            arguments: BitcoinCoreImageArgs {
+                rpc_auth: RpcAuth::with_password(username.into(), password.into()),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Sets the fee rate in BTC/kvB used by the wallet when fee estimation has no data, which is
+    /// always the case on a fresh regtest chain.
+    pub fn with_fallback_fee(self, fallback_fee: f64) -> Self {
+        BitcoinCore {
+            arguments: BitcoinCoreImageArgs {
+                fallback_fee: Some(fallback_fee),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Enables the index of all transactions, which `getrawtransaction` needs for transactions
+    /// that are not in the wallet.
+    pub fn with_txindex(self, tx_index: bool) -> Self {
+        BitcoinCore {
+            arguments: BitcoinCoreImageArgs {
+                tx_index,
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+}
+
+impl<'d> Container<'d, BitcoinCore> {
+    /// Mines the given number of blocks to the address through `bitcoin-cli` and returns their
+    /// hashes.
+    ///
+    /// Coinbase outputs can only be spent after 100 confirmations, hence funding a wallet on
+    /// regtest requires generating at least 101 blocks.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `bitcoin-cli` fails, e.g. because the address is invalid.
+    pub fn generate_blocks(&self, blocks: u32, address: &str) -> Vec<String> {
+        let output = self.exec(self.image().generate_blocks_command(blocks, address));
+
+        parse_block_hashes(output)
+    }
+}
+
+impl<'d> ContainerAsync<'d, BitcoinCore> {
+    /// Mines the given number of blocks to the address through `bitcoin-cli` and returns their
+    /// hashes.
+    ///
+    /// Coinbase outputs can only be spent after 100 confirmations, hence funding a wallet on
+    /// regtest requires generating at least 101 blocks.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `bitcoin-cli` fails, e.g. because the address is invalid.
+    pub async fn generate_blocks(&self, blocks: u32, address: &str) -> Vec<String> {
+        let output = self
+            .exec(self.image().generate_blocks_command(blocks, address))
+            .await;
+
+        parse_block_hashes(output)
+    }
+}
+
+fn parse_block_hashes(output: ExecOutput) -> Vec<String> {
+    assert!(
+        output.success(),
+        "failed to generate blocks with exit code {}: {}",
+        output.exit_code,
+        output.stderr
+    );
+
+    serde_json::from_str(&output.stdout)
+        .unwrap_or_else(|e| panic!("failed to parse block hashes: {}", e))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn generates_blocks_with_rpc_credentials() {
+        let bitcoin_core = BitcoinCore::default().with_rpc_auth("alice", "secret");
+
+        assert_eq!(
+            bitcoin_core
+                .generate_blocks_command(101, "bcrt1qaddress")
+                .cmd,
+            vec![
+                "bitcoin-cli",
+                "-rpcuser=alice",
+                "-rpcpassword=secret",
+                "-regtest",
+                "generatetoaddress",
+                "101",
+                "bcrt1qaddress"
+            ]
+        );
+    }
+
     #[test]
     fn encodes_rpc_auth_correctly() {
         let auth = RpcAuth {
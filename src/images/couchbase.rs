@@ -0,0 +1,224 @@
+use crate::{
+    core::{shell_quote, ContainerState, ExecCommand, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::{collections::HashMap, time::Duration};
+
+const CONTAINER_IDENTIFIER: &str = "couchbase/server";
+const DEFAULT_TAG: &str = "community-7.1.1";
+
+const DEFAULT_USERNAME: &str = "Administrator";
+const DEFAULT_PASSWORD: &str = "password";
+
+/// Port of the cluster management REST API and web console.
+pub const MANAGEMENT_PORT: u16 = 8091;
+/// Port of the views REST API.
+pub const VIEWS_PORT: u16 = 8092;
+/// Port of the query service.
+pub const QUERY_PORT: u16 = 8093;
+/// Port of the search service.
+pub const SEARCH_PORT: u16 = 8094;
+/// Port of the analytics service.
+pub const ANALYTICS_PORT: u16 = 8095;
+/// Port of the eventing service.
+pub const EVENTING_PORT: u16 = 8096;
+/// Port of the key-value (data) service.
+pub const KV_PORT: u16 = 11210;
+
+/// All REST calls are made from inside the container, therefore the management port is never remapped.
+const MANAGEMENT_URL: &str = "http://127.0.0.1:8091";
+
+/// How long a bucket may take to become healthy after it was created.
+const BUCKET_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Default, Clone)]
+pub struct CouchbaseArgs;
+
+impl IntoIterator for CouchbaseArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A bucket that is created once the cluster has been initialized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucket {
+    pub name: String,
+    pub quota_mb: u32,
+}
+
+/// A single-node Couchbase cluster.
+///
+/// Couchbase is not usable before the cluster has been initialized through its REST API.
+/// This image takes care of the initialization right after the container has started: It sets up
+/// the services, the admin credentials and the configured buckets and waits until all buckets are
+/// healthy. The alternate addresses of the node are pointed to the mapped host ports, so SDKs
+/// running on the host can bootstrap using the [`connection_string`].
+///
+/// [`connection_string`]: struct.Couchbase.html#method.connection_string
+#[derive(Debug)]
+pub struct Couchbase {
+    tag: String,
+    arguments: CouchbaseArgs,
+    username: String,
+    password: String,
+    buckets: Vec<Bucket>,
+}
+
+impl Default for Couchbase {
+    fn default() -> Self {
+        Couchbase {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: CouchbaseArgs {},
+            username: DEFAULT_USERNAME.to_string(),
+            password: DEFAULT_PASSWORD.to_string(),
+            buckets: Vec::new(),
+        }
+    }
+}
+
+impl Image for Couchbase {
+    type Args = CouchbaseArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        // the server logs its startup message before the REST API is actually available
+        vec![WaitFor::http(HttpWaitStrategy::new(
+            MANAGEMENT_PORT,
+            "/pools",
+        ))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Couchbase { arguments, ..self }
+    }
+
+    fn exec_after_start(&self, cs: ContainerState) -> Vec<ExecCommand> {
+        let auth = shell_quote(&format!("{}:{}", self.username, self.password));
+
+        let mut commands = vec![
+            ExecCommand::shell(format!(
+                "curl -sf -X POST {}/node/controller/setupServices -d services=kv,n1ql,index",
+                MANAGEMENT_URL
+            )),
+            ExecCommand::shell(format!(
+                "curl -sf -X POST {}/pools/default -d memoryQuota=256 -d indexMemoryQuota=256",
+                MANAGEMENT_URL
+            )),
+            ExecCommand::shell(format!(
+                "curl -sf -X POST {}/settings/web -d port=SAME --data-urlencode {} --data-urlencode {}",
+                MANAGEMENT_URL,
+                shell_quote(&format!("username={}", self.username)),
+                shell_quote(&format!("password={}", self.password))
+            )),
+            ExecCommand::shell(format!(
+                "curl -sf -u {} -X POST {}/settings/indexes -d storageMode={}",
+                auth,
+                MANAGEMENT_URL,
+                self.index_storage_mode()
+            )),
+            ExecCommand::shell(format!(
+                "curl -sf -u {} -X PUT {}/node/controller/setupAlternateAddresses/external -d hostname=localhost -d mgmt={} -d capi={} -d n1ql={} -d kv={}",
+                auth,
+                MANAGEMENT_URL,
+                cs.host_port(MANAGEMENT_PORT),
+                cs.host_port(VIEWS_PORT),
+                cs.host_port(QUERY_PORT),
+                cs.host_port(KV_PORT)
+            )),
+        ];
+
+        for bucket in &self.buckets {
+            let name = shell_quote(&bucket.name);
+
+            commands.push(ExecCommand::shell(format!(
+                "curl -sf -u {} -X POST {}/pools/default/buckets -d name={} -d ramQuota={} -d bucketType=couchbase",
+                auth, MANAGEMENT_URL, name, bucket.quota_mb
+            )));
+            commands.push(ExecCommand::shell_until(
+                format!(
+                    "curl -sf -u {} {}/pools/default/buckets/{} | grep -q '\"status\":\"healthy\"'",
+                    auth, MANAGEMENT_URL, name
+                ),
+                Duration::from_secs(1),
+                BUCKET_TIMEOUT,
+            ));
+        }
+
+        commands
+    }
+}
+
+impl Couchbase {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Couchbase {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the credentials of the cluster administrator.
+    pub fn with_admin<U: Into<String>, P: Into<String>>(self, username: U, password: P) -> Self {
+        Couchbase {
+            username: username.into(),
+            password: password.into(),
+            ..self
+        }
+    }
+
+    /// Adds a bucket with the given RAM quota that is created once the cluster is initialized.
+    pub fn with_bucket<N: Into<String>>(mut self, name: N, quota_mb: u32) -> Self {
+        self.buckets.push(Bucket {
+            name: name.into(),
+            quota_mb,
+        });
+        self
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    pub fn buckets(&self) -> &[Bucket] {
+        &self.buckets
+    }
+
+    /// Returns the connection string for SDKs, given the host port mapped to [`KV_PORT`].
+    pub fn connection_string(&self, host_kv_port: u16) -> String {
+        format!("couchbase://localhost:{}", host_kv_port)
+    }
+
+    /// The community edition only supports the ForestDB storage engine for indexes.
+    fn index_storage_mode(&self) -> &'static str {
+        if self.tag.starts_with("community") {
+            "forestdb"
+        } else {
+            "memory_optimized"
+        }
+    }
+}
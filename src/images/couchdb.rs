@@ -0,0 +1,140 @@
+use crate::{
+    core::{ContainerState, ExecCommand, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "couchdb";
+const DEFAULT_TAG: &str = "3.3.3";
+
+const DEFAULT_USER: &str = "admin";
+const DEFAULT_PASSWORD: &str = "password";
+
+/// Port of the HTTP API.
+pub const PORT: u16 = 5984;
+
+/// The databases a single node does not create on its own, see
+/// <https://docs.couchdb.org/en/stable/setup/single-node.html>.
+const SYSTEM_DATABASES: &[&str] = &["_users", "_replicator"];
+
+#[derive(Debug, Default, Clone)]
+pub struct CouchDbArgs;
+
+impl IntoIterator for CouchDbArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A single-node CouchDB server.
+///
+/// Recent images refuse to start without an admin, hence an admin is always configured. The
+/// `_users` and `_replicator` system databases are created once the server is up.
+#[derive(Debug)]
+pub struct CouchDb {
+    tag: String,
+    arguments: CouchDbArgs,
+    user: String,
+    password: String,
+}
+
+impl Default for CouchDb {
+    fn default() -> Self {
+        CouchDb {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: CouchDbArgs {},
+            user: DEFAULT_USER.to_string(),
+            password: DEFAULT_PASSWORD.to_string(),
+        }
+    }
+}
+
+impl Image for CouchDb {
+    type Args = CouchDbArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(
+            HttpWaitStrategy::new(PORT, "/_up")
+                .with_basic_auth(self.user.clone(), self.password.clone())
+                .with_response_containing(r#""status":"ok""#),
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("COUCHDB_USER".to_owned(), self.user.clone());
+        env_vars.insert("COUCHDB_PASSWORD".to_owned(), self.password.clone());
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        CouchDb { arguments, ..self }
+    }
+
+    fn exec_after_start(&self, _: ContainerState) -> Vec<ExecCommand> {
+        SYSTEM_DATABASES
+            .iter()
+            .map(|database| {
+                // 412 means the database already exists, e.g. because a volume is reused
+                ExecCommand::shell(format!(
+                    "status=$(curl -s -o /dev/null -w '%{{http_code}}' -u {}:{} -X PUT http://127.0.0.1:{}/{}); [ \"$status\" = 201 ] || [ \"$status\" = 202 ] || [ \"$status\" = 412 ]",
+                    self.user, self.password, PORT, database
+                ))
+            })
+            .collect()
+    }
+}
+
+impl CouchDb {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        CouchDb {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the credentials of the server admin.
+    pub fn with_admin<U: Into<String>, P: Into<String>>(self, user: U, password: P) -> Self {
+        CouchDb {
+            user: user.into(),
+            password: password.into(),
+            ..self
+        }
+    }
+
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    /// Returns the url of the server including the admin credentials, given the host port mapped
+    /// to [`PORT`].
+    pub fn url(&self, host_port: u16) -> String {
+        format!(
+            "http://{}:{}@localhost:{}",
+            self.user, self.password, host_port
+        )
+    }
+}
@@ -0,0 +1,280 @@
+use crate::{
+    core::{CopyToContainer, HttpWaitStrategy, Port, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "ghcr.io/dexidp/dex";
+const DEFAULT_TAG: &str = "v2.37.0";
+
+/// Port of the web server, serving the OIDC endpoints below `/dex`.
+pub const PORT: u16 = 5556;
+
+const CONFIG_FILE: &str = "/etc/dex/testcontainers.yaml";
+
+#[derive(Debug, Default, Clone)]
+pub struct DexArgs;
+
+impl IntoIterator for DexArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec!["dex".to_owned(), "serve".to_owned(), CONFIG_FILE.to_owned()].into_iter()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StaticUser {
+    email: String,
+    password_hash: String,
+}
+
+#[derive(Debug, Clone)]
+struct StaticClient {
+    id: String,
+    secret: String,
+    redirect_uris: Vec<String>,
+}
+
+/// A Dex OpenID Connect provider with static users and clients, e.g. to test login flows.
+///
+/// Unless a config is passed through [`with_config`], the provider is configured with the users
+/// and clients added through [`with_static_user`] and [`with_static_client`], stores its state in
+/// memory and allows the password grant for the static users.
+///
+/// Dex embeds its issuer url into the discovery document and all tokens, which clients compare to
+/// the url they reach the provider through. Hence, the generated config uses the issuer
+/// `http://localhost:5556/dex`, which only matches the actual url if the provider is published on
+/// the same host port through [`with_host_port`]. Tests only fetching tokens can use the randomly
+/// mapped port returned by [`issuer_url`] regardless.
+///
+/// [`with_config`]: struct.Dex.html#method.with_config
+/// [`with_static_user`]: struct.Dex.html#method.with_static_user
+/// [`with_static_client`]: struct.Dex.html#method.with_static_client
+/// [`with_host_port`]: struct.Dex.html#method.with_host_port
+/// [`issuer_url`]: struct.Dex.html#method.issuer_url
+#[derive(Debug)]
+pub struct Dex {
+    tag: String,
+    arguments: DexArgs,
+    config: Option<Vec<u8>>,
+    host_port: Option<u16>,
+    users: Vec<StaticUser>,
+    clients: Vec<StaticClient>,
+}
+
+impl Default for Dex {
+    fn default() -> Self {
+        Dex {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: DexArgs,
+            config: None,
+            host_port: None,
+            users: Vec::new(),
+            clients: Vec::new(),
+        }
+    }
+}
+
+impl Image for Dex {
+    type Args = DexArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(PORT, "/dex/healthz"))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Dex { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        let config = self
+            .config
+            .clone()
+            .unwrap_or_else(|| self.generate_config().into_bytes());
+
+        vec![CopyToContainer::new(config, CONFIG_FILE)]
+    }
+
+    fn mapped_ports(&self) -> Vec<Port> {
+        self.host_port
+            .iter()
+            .map(|&local| Port {
+                local,
+                internal: PORT,
+            })
+            .collect()
+    }
+}
+
+impl Dex {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Dex {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Replaces the generated config with the given one, ignoring all static users and clients.
+    ///
+    /// The web server has to listen on [`PORT`] with an issuer path of `/dex` for the readiness
+    /// check to pass.
+    pub fn with_config<C: Into<Vec<u8>>>(self, config: C) -> Self {
+        Dex {
+            config: Some(config.into()),
+            ..self
+        }
+    }
+
+    /// Publishes the provider on the given host port and embeds it into the issuer url, so the
+    /// issuer of discovery document and tokens matches the url the provider is reached through.
+    ///
+    /// Containers using the same host port cannot run at the same time.
+    pub fn with_host_port(self, host_port: u16) -> Self {
+        Dex {
+            host_port: Some(host_port),
+            ..self
+        }
+    }
+
+    /// Adds a user that logs in with the given email and password, given as its bcrypt hash,
+    /// e.g. `$2a$10$2b2cU8CPhOTaGrs1HRQuAueS7JTT5ZHsHSzYiFPm1leZck7Mc8T4W` for `password`.
+    pub fn with_static_user<E: Into<String>, H: Into<String>>(
+        mut self,
+        email: E,
+        password_hash: H,
+    ) -> Self {
+        self.users.push(StaticUser {
+            email: email.into(),
+            password_hash: password_hash.into(),
+        });
+        self
+    }
+
+    /// Adds a client with the given credentials, which may redirect to the given urls after a
+    /// login.
+    pub fn with_static_client<I, S, R>(mut self, id: I, secret: S, redirect_uris: R) -> Self
+    where
+        I: Into<String>,
+        S: Into<String>,
+        R: IntoIterator,
+        R::Item: Into<String>,
+    {
+        self.clients.push(StaticClient {
+            id: id.into(),
+            secret: secret.into(),
+            redirect_uris: redirect_uris.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+
+    /// Returns the id of the first client added through [`with_static_client`], if any.
+    ///
+    /// [`with_static_client`]: struct.Dex.html#method.with_static_client
+    pub fn client_id(&self) -> Option<&str> {
+        self.clients.first().map(|client| client.id.as_str())
+    }
+
+    /// Returns the secret of the first client added through [`with_static_client`], if any.
+    ///
+    /// [`with_static_client`]: struct.Dex.html#method.with_static_client
+    pub fn client_secret(&self) -> Option<&str> {
+        self.clients.first().map(|client| client.secret.as_str())
+    }
+
+    /// Returns the url of the provider, given the host port mapped to [`PORT`]. The discovery
+    /// document is served below `/.well-known/openid-configuration` of this url.
+    pub fn issuer_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}/dex", host_port)
+    }
+
+    /// Dex parses its config as YAML, which JSON is a subset of. This saves escaping the values.
+    fn generate_config(&self) -> String {
+        let users = self
+            .users
+            .iter()
+            .map(|user| {
+                serde_json::json!({
+                    "email": user.email,
+                    "hash": user.password_hash,
+                    "username": user.email,
+                    "userID": user.email,
+                })
+            })
+            .collect::<Vec<_>>();
+        let clients = self
+            .clients
+            .iter()
+            .map(|client| {
+                serde_json::json!({
+                    "id": client.id,
+                    "secret": client.secret,
+                    "name": client.id,
+                    "redirectURIs": client.redirect_uris,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::json!({
+            "issuer": self.issuer_url(self.host_port.unwrap_or(PORT)),
+            "storage": { "type": "memory" },
+            "web": { "http": format!("0.0.0.0:{}", PORT) },
+            "oauth2": {
+                "passwordConnector": "local",
+                "skipApprovalScreen": true,
+            },
+            "enablePasswordDB": true,
+            "staticPasswords": users,
+            "staticClients": clients,
+        })
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_embed_host_port_into_generated_issuer() {
+        let image = Dex::default().with_host_port(15556).with_static_client(
+            "app",
+            "secret",
+            vec!["http://localhost/callback"],
+        );
+        let config =
+            serde_json::from_slice::<serde_json::Value>(&image.copy_to_sources()[0].data).unwrap();
+
+        assert_eq!(config["issuer"], "http://localhost:15556/dex");
+        assert_eq!(config["staticClients"][0]["id"], "app");
+        assert_eq!(
+            image.mapped_ports(),
+            vec![Port {
+                local: 15556,
+                internal: 5556
+            }]
+        );
+        assert_eq!(image.client_id(), Some("app"));
+        assert_eq!(image.client_secret(), Some("secret"));
+    }
+}
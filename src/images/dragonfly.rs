@@ -0,0 +1,159 @@
+use crate::{
+    core::{ConnectionString, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "docker.dragonflydb.io/dragonflydb/dragonfly";
+const DEFAULT_TAG: &str = "v1.13.0";
+
+/// Port of the Redis protocol.
+pub const PORT: u16 = 6379;
+
+#[derive(Debug, Default, Clone)]
+pub struct DragonflyArgs {
+    max_memory: Option<String>,
+    proactor_threads: Option<u32>,
+}
+
+impl IntoIterator for DragonflyArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        // without `--logtostderr` Dragonfly logs to files only
+        let mut args = vec!["dragonfly".to_owned(), "--logtostderr".to_owned()];
+
+        if let Some(max_memory) = self.max_memory {
+            args.push(format!("--maxmemory={}", max_memory));
+        }
+        if let Some(proactor_threads) = self.proactor_threads {
+            args.push(format!("--proactor_threads={}", proactor_threads));
+        }
+
+        args.into_iter()
+    }
+}
+
+/// A Dragonfly server, which speaks the Redis protocol and therefore replaces the [`Redis`] image
+/// in tests that are generic over [`ConnectionString`].
+///
+/// Dragonfly starts a thread per CPU and reserves much of the memory of the host by default, which
+/// [`with_proactor_threads`] and [`with_max_memory`] keep in check on shared CI machines.
+///
+/// [`Redis`]: ../redis/struct.Redis.html
+/// [`ConnectionString`]: ../../core/trait.ConnectionString.html
+/// [`with_proactor_threads`]: struct.Dragonfly.html#method.with_proactor_threads
+/// [`with_max_memory`]: struct.Dragonfly.html#method.with_max_memory
+#[derive(Debug)]
+pub struct Dragonfly {
+    tag: String,
+    arguments: DragonflyArgs,
+}
+
+impl Default for Dragonfly {
+    fn default() -> Self {
+        Dragonfly {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: DragonflyArgs::default(),
+        }
+    }
+}
+
+impl Image for Dragonfly {
+    type Args = DragonflyArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        // e.g. `accept_server.cc:23] AcceptServer - listening on port 6379`
+        vec![WaitFor::message_on_stderr(
+            "AcceptServer - listening on port",
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Dragonfly { arguments, ..self }
+    }
+}
+
+impl Dragonfly {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Dragonfly {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Limits the memory of the store, e.g. `512mb`. Dragonfly refuses to start with less than
+    /// 256 MiB per proactor thread.
+    pub fn with_max_memory<M: Into<String>>(self, max_memory: M) -> Self {
+        Dragonfly {
+            arguments: DragonflyArgs {
+                max_memory: Some(max_memory.into()),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Sets the number of threads serving connections, one per CPU by default.
+    pub fn with_proactor_threads(self, proactor_threads: u32) -> Self {
+        Dragonfly {
+            arguments: DragonflyArgs {
+                proactor_threads: Some(proactor_threads),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+}
+
+impl ConnectionString for Dragonfly {
+    fn connection_port(&self) -> u16 {
+        PORT
+    }
+
+    fn connection_string(&self, host_port: u16) -> String {
+        format!("redis://localhost:{}", host_port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_pass_limits_after_logging_flag() {
+        let image = Dragonfly::default()
+            .with_proactor_threads(2)
+            .with_max_memory("512mb");
+
+        assert_eq!(
+            image.args().into_iter().collect::<Vec<_>>(),
+            vec![
+                "dragonfly",
+                "--logtostderr",
+                "--maxmemory=512mb",
+                "--proactor_threads=2"
+            ]
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{
+    core::WaitFor,
+    images::coblox_bitcoincore::{BitcoinCore, Network},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "getumbrel/electrs";
+const DEFAULT_TAG: &str = "v0.10.2";
+
+/// Port of the Electrum protocol.
+pub const PORT: u16 = 50001;
+
+const DEFAULT_BITCOIND_HOST: &str = "bitcoind";
+
+#[derive(Debug, Clone)]
+pub struct ElectrsArgs {
+    network: Network,
+    daemon_rpc_addr: String,
+    daemon_p2p_addr: String,
+    auth: Option<(String, String)>,
+}
+
+impl Default for ElectrsArgs {
+    fn default() -> Self {
+        ElectrsArgs {
+            network: Network::Regtest,
+            daemon_rpc_addr: format!("{}:18443", DEFAULT_BITCOIND_HOST),
+            daemon_p2p_addr: format!("{}:18444", DEFAULT_BITCOIND_HOST),
+            auth: None,
+        }
+    }
+}
+
+impl IntoIterator for ElectrsArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        let network = match self.network {
+            Network::Mainnet => "bitcoin",
+            Network::Testnet => "testnet",
+            Network::Regtest => "regtest",
+        };
+
+        let mut args = vec![
+            "--network".to_owned(),
+            network.to_owned(),
+            "--daemon-rpc-addr".to_owned(),
+            self.daemon_rpc_addr,
+            "--daemon-p2p-addr".to_owned(),
+            self.daemon_p2p_addr,
+            "--electrum-rpc-addr".to_owned(),
+            format!("0.0.0.0:{}", PORT),
+            "--db-dir".to_owned(),
+            "/tmp/electrs".to_owned(),
+            // nothing is logged by default
+            "--log-filters".to_owned(),
+            "INFO".to_owned(),
+        ];
+
+        if let Some((username, password)) = self.auth {
+            args.push("--auth".to_owned());
+            args.push(format!("{}:{}", username, password));
+        }
+
+        args.into_iter()
+    }
+}
+
+/// An electrs instance serving the Electrum protocol for a bitcoind node.
+///
+/// electrs connects to bitcoind through RPC and P2P, hence both are expected to run on the same
+/// docker network, with bitcoind being started under the name `bitcoind` through
+/// [`RunArgs::with_name`] unless configured otherwise. electrs only starts serving once bitcoind
+/// left the initial block download, which requires mining a block on a fresh regtest chain.
+///
+/// [`RunArgs::with_name`]: ../../core/struct.RunArgs.html#method.with_name
+#[derive(Debug)]
+pub struct Electrs {
+    tag: String,
+    arguments: ElectrsArgs,
+}
+
+impl Default for Electrs {
+    fn default() -> Self {
+        Electrs {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: ElectrsArgs::default(),
+        }
+    }
+}
+
+impl Image for Electrs {
+    type Args = ElectrsArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::message_on_stderr("serving Electrum RPC on")]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Electrs { arguments, ..self }
+    }
+}
+
+impl Electrs {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Electrs {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Connects to the given bitcoind, which runs under the given host name on the docker network
+    /// of this container, using its network and RPC credentials.
+    pub fn with_bitcoind(self, host: &str, bitcoind: &BitcoinCore) -> Self {
+        let (username, password) = bitcoind.rpc_credentials();
+
+        self.with_network(bitcoind.network().clone())
+            .with_daemon_rpc_addr(format!("{}:{}", host, bitcoind.rpc_port()))
+            .with_daemon_p2p_addr(format!("{}:{}", host, bitcoind.p2p_port()))
+            .with_auth(username, password)
+    }
+
+    pub fn with_network(self, network: Network) -> Self {
+        Electrs {
+            arguments: ElectrsArgs {
+                network,
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Sets the address of the RPC interface of bitcoind, e.g. `bitcoind:18443`.
+    pub fn with_daemon_rpc_addr<A: Into<String>>(self, addr: A) -> Self {
+        Electrs {
+            arguments: ElectrsArgs {
+                daemon_rpc_addr: addr.into(),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Sets the address of the P2P interface of bitcoind, e.g. `bitcoind:18444`.
+    pub fn with_daemon_p2p_addr<A: Into<String>>(self, addr: A) -> Self {
+        Electrs {
+            arguments: ElectrsArgs {
+                daemon_p2p_addr: addr.into(),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Sets the credentials of the RPC interface of bitcoind.
+    pub fn with_auth<U: Into<String>, P: Into<String>>(self, username: U, password: P) -> Self {
+        Electrs {
+            arguments: ElectrsArgs {
+                auth: Some((username.into(), password.into())),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Returns the url of the Electrum server, given the host port mapped to [`PORT`].
+    pub fn electrum_url(&self, host_port: u16) -> String {
+        format!("tcp://localhost:{}", host_port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_connect_to_bitcoind_on_shared_network() {
+        let bitcoind = BitcoinCore::default().with_rpc_auth("alice", "secret");
+        let image = Electrs::default().with_bitcoind("node", &bitcoind);
+
+        assert_eq!(
+            image.args().into_iter().collect::<Vec<_>>(),
+            vec![
+                "--network",
+                "regtest",
+                "--daemon-rpc-addr",
+                "node:18443",
+                "--daemon-p2p-addr",
+                "node:18444",
+                "--electrum-rpc-addr",
+                "0.0.0.0:50001",
+                "--db-dir",
+                "/tmp/electrs",
+                "--log-filters",
+                "INFO",
+                "--auth",
+                "alice:secret",
+            ]
+        );
+    }
+}
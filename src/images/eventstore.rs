@@ -0,0 +1,173 @@
+use crate::{
+    core::{ConnectionString, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "eventstore/eventstore";
+const DEFAULT_TAG: &str = "23.10.0-bookworm-slim";
+
+/// Port of the gRPC and HTTP APIs.
+pub const PORT: u16 = 2113;
+
+#[derive(Debug, Default, Clone)]
+pub struct EventStoreDbArgs;
+
+impl IntoIterator for EventStoreDbArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A single EventStoreDB node.
+///
+/// By default, the node runs without TLS, runs all projections and serves the AtomPub HTTP API
+/// next to gRPC, so events can be appended and read without a gRPC client. Requests to the HTTP
+/// API authenticate as the default user `admin` with the password `changeit`.
+#[derive(Debug)]
+pub struct EventStoreDb {
+    tag: String,
+    arguments: EventStoreDbArgs,
+    insecure: bool,
+    run_projections: String,
+    cluster_size: u32,
+    atom_pub_over_http: bool,
+}
+
+impl Default for EventStoreDb {
+    fn default() -> Self {
+        EventStoreDb {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: EventStoreDbArgs,
+            insecure: true,
+            run_projections: "All".to_owned(),
+            cluster_size: 1,
+            atom_pub_over_http: true,
+        }
+    }
+}
+
+impl Image for EventStoreDb {
+    type Args = EventStoreDbArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(
+            HttpWaitStrategy::new(PORT, "/health/live").with_expected_status_code(204),
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("EVENTSTORE_INSECURE".to_owned(), self.insecure.to_string());
+        env_vars.insert(
+            "EVENTSTORE_RUN_PROJECTIONS".to_owned(),
+            self.run_projections.clone(),
+        );
+        env_vars.insert(
+            "EVENTSTORE_CLUSTER_SIZE".to_owned(),
+            self.cluster_size.to_string(),
+        );
+        env_vars.insert(
+            "EVENTSTORE_ENABLE_ATOM_PUB_OVER_HTTP".to_owned(),
+            self.atom_pub_over_http.to_string(),
+        );
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        EventStoreDb { arguments, ..self }
+    }
+}
+
+impl EventStoreDb {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        EventStoreDb {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets whether the node runs without TLS and authentication of its nodes, which is the
+    /// default. A secure node needs certificates mounted into the container.
+    pub fn with_insecure(self, insecure: bool) -> Self {
+        EventStoreDb { insecure, ..self }
+    }
+
+    /// Sets which projections run, `None`, `System` or `All`, which is the default.
+    pub fn with_run_projections<P: Into<String>>(self, run_projections: P) -> Self {
+        EventStoreDb {
+            run_projections: run_projections.into(),
+            ..self
+        }
+    }
+
+    /// Sets the number of nodes the node expects in its cluster, which is 1 by default.
+    pub fn with_cluster_size(self, cluster_size: u32) -> Self {
+        EventStoreDb {
+            cluster_size,
+            ..self
+        }
+    }
+
+    /// Sets whether the node serves the AtomPub HTTP API, which is the default.
+    pub fn with_atom_pub_over_http(self, atom_pub_over_http: bool) -> Self {
+        EventStoreDb {
+            atom_pub_over_http,
+            ..self
+        }
+    }
+
+    /// Returns the url of the HTTP API, given the host port mapped to [`PORT`].
+    pub fn http_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+}
+
+impl ConnectionString for EventStoreDb {
+    fn connection_port(&self) -> u16 {
+        PORT
+    }
+
+    /// Returns the connection string of the official gRPC clients.
+    fn connection_string(&self, host_port: u16) -> String {
+        format!("esdb://localhost:{}?tls={}", host_port, !self.insecure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_disable_tls_in_connection_string_of_insecure_node() {
+        assert_eq!(
+            EventStoreDb::default().connection_string(32768),
+            "esdb://localhost:32768?tls=false"
+        );
+        assert_eq!(
+            EventStoreDb::default()
+                .with_insecure(false)
+                .connection_string(32768),
+            "esdb://localhost:32768?tls=true"
+        );
+    }
+}
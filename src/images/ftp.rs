@@ -0,0 +1,256 @@
+use crate::{
+    core::{
+        env::{GetEnvValue, Os},
+        Port, WaitFor,
+    },
+    Image,
+};
+use std::{collections::HashMap, ops::RangeInclusive};
+
+const CONTAINER_IDENTIFIER: &str = "stilliard/pure-ftpd";
+const DEFAULT_TAG: &str = "hardened";
+
+const DEFAULT_USERNAME: &str = "testcontainers";
+const DEFAULT_PASSWORD: &str = "testcontainers";
+
+/// Port of the FTP control connection.
+pub const PORT: u16 = 21;
+
+const DEFAULT_PASSIVE_PORTS: RangeInclusive<u16> = 30000..=30009;
+
+#[derive(Debug, Default, Clone)]
+pub struct PureFtpdArgs;
+
+impl IntoIterator for PureFtpdArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A Pure-FTPd server with a single virtual user.
+///
+/// In passive mode, the server tells clients which address and port to open the data connection
+/// to. Hence, the passive ports are published on the same port numbers of the host (30000 to
+/// 30009 by default, see [`with_passive_ports`]) and the server advertises the address configured
+/// through [`with_public_host`]. As a consequence, containers using the same passive ports cannot
+/// run at the same time.
+///
+/// [`with_passive_ports`]: struct.PureFtpd.html#method.with_passive_ports
+/// [`with_public_host`]: struct.PureFtpd.html#method.with_public_host
+#[derive(Debug)]
+pub struct PureFtpd {
+    tag: String,
+    arguments: PureFtpdArgs,
+    username: String,
+    password: String,
+    public_host: String,
+    passive_ports: RangeInclusive<u16>,
+}
+
+impl Default for PureFtpd {
+    fn default() -> Self {
+        PureFtpd {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: PureFtpdArgs {},
+            username: DEFAULT_USERNAME.to_string(),
+            password: DEFAULT_PASSWORD.to_string(),
+            public_host: public_host::<Os>(),
+            passive_ports: DEFAULT_PASSIVE_PORTS,
+        }
+    }
+}
+
+impl Image for PureFtpd {
+    type Args = PureFtpdArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        // the entrypoint logs this right before it replaces itself with the server
+        vec![WaitFor::message_on_stdout("Starting Pure-FTPd")]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("PUBLICHOST".to_owned(), self.public_host.clone());
+        env_vars.insert("FTP_USER_NAME".to_owned(), self.username.clone());
+        env_vars.insert("FTP_USER_PASS".to_owned(), self.password.clone());
+        env_vars.insert(
+            "FTP_USER_HOME".to_owned(),
+            format!("/home/ftpusers/{}", self.username),
+        );
+        env_vars.insert(
+            "FTP_PASSIVE_PORTS".to_owned(),
+            format!(
+                "{}:{}",
+                self.passive_ports.start(),
+                self.passive_ports.end()
+            ),
+        );
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        PureFtpd { arguments, ..self }
+    }
+
+    fn mapped_ports(&self) -> Vec<Port> {
+        self.passive_ports
+            .clone()
+            .map(|port| Port {
+                local: port,
+                internal: port,
+            })
+            .collect()
+    }
+}
+
+impl PureFtpd {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        PureFtpd {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the credentials of the user, which is created when the server starts.
+    pub fn with_credentials<U: Into<String>, P: Into<String>>(
+        self,
+        username: U,
+        password: P,
+    ) -> Self {
+        PureFtpd {
+            username: username.into(),
+            password: password.into(),
+            ..self
+        }
+    }
+
+    /// Sets the address of the Docker host as seen by the tests, which the server advertises for
+    /// passive data connections. Defaults to the host of `DOCKER_HOST` if it points to a TCP
+    /// endpoint, and to `localhost` otherwise.
+    pub fn with_public_host<H: Into<String>>(self, public_host: H) -> Self {
+        PureFtpd {
+            public_host: public_host.into(),
+            ..self
+        }
+    }
+
+    /// Sets the ports used for passive data connections, which are published on the same ports of
+    /// the host.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the range is empty or contains the control [`PORT`].
+    pub fn with_passive_ports(self, passive_ports: RangeInclusive<u16>) -> Self {
+        assert!(
+            passive_ports.start() <= passive_ports.end(),
+            "passive ports must not be empty"
+        );
+        assert!(
+            !passive_ports.contains(&PORT),
+            "passive ports must not contain the control port {}",
+            PORT
+        );
+
+        PureFtpd {
+            passive_ports,
+            ..self
+        }
+    }
+
+    /// Returns the address of the control connection, given the host port mapped to [`PORT`].
+    pub fn ftp_addr(&self, host_port: u16) -> String {
+        format!("{}:{}", self.public_host, host_port)
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    pub fn passive_ports(&self) -> &RangeInclusive<u16> {
+        &self.passive_ports
+    }
+}
+
+/// Returns the host the daemon publishes ports on, as configured through `DOCKER_HOST`.
+fn public_host<E: GetEnvValue>() -> String {
+    E::get_env_value("DOCKER_HOST")
+        .and_then(|docker_host| {
+            let url = reqwest::Url::parse(&docker_host).ok()?;
+
+            match url.scheme() {
+                "tcp" | "http" | "https" => url.host_str().map(ToOwned::to_owned),
+                _ => None,
+            }
+        })
+        .unwrap_or_else(|| "localhost".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeEnvRemoteDaemon;
+
+    impl GetEnvValue for FakeEnvRemoteDaemon {
+        fn get_env_value(key: &str) -> Option<String> {
+            match key {
+                "DOCKER_HOST" => Some("tcp://192.168.99.100:2376".to_owned()),
+                _ => None,
+            }
+        }
+    }
+
+    struct FakeEnvLocalDaemon;
+
+    impl GetEnvValue for FakeEnvLocalDaemon {
+        fn get_env_value(key: &str) -> Option<String> {
+            match key {
+                "DOCKER_HOST" => Some("unix:///var/run/docker.sock".to_owned()),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn should_advertise_host_of_remote_daemon() {
+        assert_eq!(public_host::<FakeEnvRemoteDaemon>(), "192.168.99.100");
+        assert_eq!(public_host::<FakeEnvLocalDaemon>(), "localhost");
+    }
+
+    #[test]
+    fn should_publish_passive_ports_on_same_host_ports() {
+        let ftp = PureFtpd::default().with_passive_ports(31000..=31001);
+
+        assert_eq!(
+            ftp.mapped_ports(),
+            vec![Port::from((31000, 31000)), Port::from((31001, 31001))]
+        );
+        assert_eq!(
+            ftp.env_vars().get("FTP_PASSIVE_PORTS").map(String::as_str),
+            Some("31000:31001")
+        );
+    }
+}
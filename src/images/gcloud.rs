@@ -0,0 +1,260 @@
+use crate::{core::WaitFor, Image};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "google/cloud-sdk";
+const DEFAULT_TAG: &str = "458.0.0-emulators";
+
+const DEFAULT_PROJECT_ID: &str = "test-project";
+
+/// Port of the Pub/Sub emulator.
+pub const PUBSUB_PORT: u16 = 8085;
+/// Port of the Firestore emulator.
+pub const FIRESTORE_PORT: u16 = 8080;
+
+#[derive(Debug, Clone)]
+pub struct PubSubEmulatorArgs {
+    project_id: String,
+}
+
+impl Default for PubSubEmulatorArgs {
+    fn default() -> Self {
+        PubSubEmulatorArgs {
+            project_id: DEFAULT_PROJECT_ID.to_owned(),
+        }
+    }
+}
+
+impl IntoIterator for PubSubEmulatorArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        emulator_command("pubsub", PUBSUB_PORT, &self.project_id).into_iter()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FirestoreEmulatorArgs {
+    project_id: String,
+}
+
+impl Default for FirestoreEmulatorArgs {
+    fn default() -> Self {
+        FirestoreEmulatorArgs {
+            project_id: DEFAULT_PROJECT_ID.to_owned(),
+        }
+    }
+}
+
+impl IntoIterator for FirestoreEmulatorArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        emulator_command("firestore", FIRESTORE_PORT, &self.project_id).into_iter()
+    }
+}
+
+/// Starts one of the emulators bundled with the Cloud SDK, listening on all interfaces.
+fn emulator_command(emulator: &str, port: u16, project_id: &str) -> Vec<String> {
+    vec![
+        "gcloud".to_owned(),
+        "beta".to_owned(),
+        "emulators".to_owned(),
+        emulator.to_owned(),
+        "start".to_owned(),
+        format!("--project={}", project_id),
+        format!("--host-port=0.0.0.0:{}", port),
+    ]
+}
+
+/// The Pub/Sub emulator of the Google Cloud SDK.
+///
+/// Client libraries connect to the emulator instead of Google Cloud if `PUBSUB_EMULATOR_HOST`
+/// is set to the value returned by [`emulator_host`].
+///
+/// [`emulator_host`]: struct.PubSubEmulator.html#method.emulator_host
+#[derive(Debug)]
+pub struct PubSubEmulator {
+    tag: String,
+    arguments: PubSubEmulatorArgs,
+}
+
+impl Default for PubSubEmulator {
+    fn default() -> Self {
+        PubSubEmulator {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: PubSubEmulatorArgs::default(),
+        }
+    }
+}
+
+impl Image for PubSubEmulator {
+    type Args = PubSubEmulatorArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        // gcloud forwards the output of the emulator to stderr
+        vec![WaitFor::message_on_stderr("Server started, listening on")]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        PubSubEmulator { arguments, ..self }
+    }
+}
+
+impl PubSubEmulator {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        PubSubEmulator {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the project the emulator serves, `test-project` by default.
+    pub fn with_project_id<P: Into<String>>(self, project_id: P) -> Self {
+        PubSubEmulator {
+            arguments: PubSubEmulatorArgs {
+                project_id: project_id.into(),
+            },
+            ..self
+        }
+    }
+
+    pub fn project_id(&self) -> &str {
+        &self.arguments.project_id
+    }
+
+    /// Returns the value for `PUBSUB_EMULATOR_HOST`, given the host port mapped to
+    /// [`PUBSUB_PORT`].
+    pub fn emulator_host(&self, host_port: u16) -> String {
+        format!("localhost:{}", host_port)
+    }
+}
+
+/// The Firestore emulator of the Google Cloud SDK.
+///
+/// Client libraries connect to the emulator instead of Google Cloud if `FIRESTORE_EMULATOR_HOST`
+/// is set to the value returned by [`emulator_host`].
+///
+/// [`emulator_host`]: struct.FirestoreEmulator.html#method.emulator_host
+#[derive(Debug)]
+pub struct FirestoreEmulator {
+    tag: String,
+    arguments: FirestoreEmulatorArgs,
+}
+
+impl Default for FirestoreEmulator {
+    fn default() -> Self {
+        FirestoreEmulator {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: FirestoreEmulatorArgs::default(),
+        }
+    }
+}
+
+impl Image for FirestoreEmulator {
+    type Args = FirestoreEmulatorArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        // gcloud forwards the output of the emulator to stderr
+        vec![WaitFor::message_on_stderr("Dev App Server is now running")]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        FirestoreEmulator { arguments, ..self }
+    }
+}
+
+impl FirestoreEmulator {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        FirestoreEmulator {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the project the emulator serves, `test-project` by default.
+    pub fn with_project_id<P: Into<String>>(self, project_id: P) -> Self {
+        FirestoreEmulator {
+            arguments: FirestoreEmulatorArgs {
+                project_id: project_id.into(),
+            },
+            ..self
+        }
+    }
+
+    pub fn project_id(&self) -> &str {
+        &self.arguments.project_id
+    }
+
+    /// Returns the value for `FIRESTORE_EMULATOR_HOST`, given the host port mapped to
+    /// [`FIRESTORE_PORT`].
+    pub fn emulator_host(&self, host_port: u16) -> String {
+        format!("localhost:{}", host_port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_start_emulator_for_project_on_all_interfaces() {
+        let args = PubSubEmulator::default()
+            .with_project_id("my-project")
+            .args()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            args,
+            vec![
+                "gcloud",
+                "beta",
+                "emulators",
+                "pubsub",
+                "start",
+                "--project=my-project",
+                "--host-port=0.0.0.0:8085",
+            ]
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{
+    core::{shell_quote, ContainerState, ExecCommand, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "fsouza/fake-gcs-server";
+const DEFAULT_TAG: &str = "1.47.7";
+
+/// Port of the JSON and XML APIs.
+pub const PORT: u16 = 4443;
+
+#[derive(Debug, Default, Clone)]
+pub struct FakeGcsServerArgs;
+
+impl IntoIterator for FakeGcsServerArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![
+            "-scheme".to_owned(),
+            "http".to_owned(),
+            "-port".to_owned(),
+            PORT.to_string(),
+        ]
+        .into_iter()
+    }
+}
+
+/// A fake Google Cloud Storage server.
+///
+/// The server returns absolute urls for resumable uploads and downloads, which need to point to
+/// the mapped port. Since that port is only known once the container is running, the external url
+/// is configured after start.
+#[derive(Debug)]
+pub struct FakeGcsServer {
+    tag: String,
+    arguments: FakeGcsServerArgs,
+    initial_buckets: Vec<String>,
+}
+
+impl Default for FakeGcsServer {
+    fn default() -> Self {
+        FakeGcsServer {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: FakeGcsServerArgs {},
+            initial_buckets: Vec::new(),
+        }
+    }
+}
+
+impl Image for FakeGcsServer {
+    type Args = FakeGcsServerArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(PORT, "/storage/v1/b"))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        FakeGcsServer { arguments, ..self }
+    }
+
+    fn exec_after_start(&self, cs: ContainerState) -> Vec<ExecCommand> {
+        let external_url = format!("http://localhost:{}", cs.host_port(PORT));
+
+        // the image only ships busybox, whose wget cannot send PUT requests
+        let mut commands = vec![ExecCommand::shell(format!(
+            "body={}; \
+             printf 'PUT /_internal/config HTTP/1.1\\r\\nHost: 127.0.0.1\\r\\nContent-Type: application/json\\r\\nContent-Length: %s\\r\\nConnection: close\\r\\n\\r\\n%s' \"${{#body}}\" \"$body\" \
+             | nc 127.0.0.1 {} | head -n 1 | grep -q ' 200 '",
+            shell_quote(&serde_json::json!({ "externalUrl": external_url }).to_string()),
+            PORT
+        ))];
+
+        commands.extend(self.initial_buckets.iter().map(|bucket| {
+            ExecCommand::shell(format!(
+                "wget -q -O /dev/null --header 'Content-Type: application/json' --post-data {} http://127.0.0.1:{}/storage/v1/b",
+                shell_quote(&serde_json::json!({ "name": bucket }).to_string()),
+                PORT
+            ))
+        }));
+
+        commands
+    }
+}
+
+impl FakeGcsServer {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        FakeGcsServer {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Creates the given bucket once the server is ready.
+    pub fn with_initial_bucket<B: Into<String>>(mut self, bucket: B) -> Self {
+        self.initial_buckets.push(bucket.into());
+        self
+    }
+
+    /// Returns the url to override the base url of GCS clients with, given the host port mapped
+    /// to [`PORT`].
+    ///
+    /// The JSON API is served below `/storage/v1`, uploads below `/upload/storage/v1`.
+    pub fn endpoint_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ports::Ports;
+
+    fn container_state() -> ContainerState {
+        let binding = vec![("HostPort".to_owned(), "32768".to_owned())]
+            .into_iter()
+            .collect();
+        let ports = vec![(format!("{}/tcp", PORT), Some(vec![binding]))]
+            .into_iter()
+            .collect();
+
+        ContainerState::new(Ports::new(ports))
+    }
+
+    #[test]
+    fn should_configure_external_url_with_host_port() {
+        let commands = FakeGcsServer::default()
+            .with_initial_bucket("fruits")
+            .exec_after_start(container_state());
+
+        assert_eq!(commands.len(), 2);
+        assert!(commands[0].cmd[2].contains(r#"{"externalUrl":"http://localhost:32768"}"#));
+        assert!(commands[1].cmd[2].contains(r#"{"name":"fruits"}"#));
+    }
+
+    #[test]
+    fn should_quote_initial_bucket_for_shell_and_json() {
+        let commands = FakeGcsServer::default()
+            .with_initial_bucket(r#"it's "fruits""#)
+            .exec_after_start(container_state());
+
+        assert!(commands[1].cmd[2].contains(r#"--post-data '{"name":"it'\''s \"fruits\""}' "#));
+    }
+}
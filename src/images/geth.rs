@@ -0,0 +1,245 @@
+use crate::{
+    core::{CopyToContainer, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "ethereum/client-go";
+const DEFAULT_TAG: &str = "v1.10.26";
+
+/// Port of the JSON-RPC API over HTTP.
+pub const HTTP_PORT: u16 = 8545;
+/// Port of the JSON-RPC API over WebSocket.
+pub const WS_PORT: u16 = 8546;
+
+const DEFAULT_APIS: [&str; 4] = ["eth", "net", "web3", "personal"];
+
+const DATA_DIR: &str = "/data";
+const GENESIS_FILE: &str = "/genesis.json";
+
+#[derive(Debug, Clone)]
+pub struct GethArgs {
+    apis: Vec<String>,
+    dev_period: Option<u64>,
+    genesis: bool,
+}
+
+impl Default for GethArgs {
+    fn default() -> Self {
+        GethArgs {
+            apis: DEFAULT_APIS.iter().map(|api| api.to_string()).collect(),
+            dev_period: None,
+            genesis: false,
+        }
+    }
+}
+
+impl GethArgs {
+    fn flags(&self) -> Vec<String> {
+        let mut flags = vec![
+            "--dev".to_owned(),
+            "--http".to_owned(),
+            "--http.addr".to_owned(),
+            "0.0.0.0".to_owned(),
+            "--http.api".to_owned(),
+            self.apis.join(","),
+            "--ws".to_owned(),
+            "--ws.addr".to_owned(),
+            "0.0.0.0".to_owned(),
+        ];
+
+        if let Some(period) = self.dev_period {
+            flags.push("--dev.period".to_owned());
+            flags.push(period.to_string());
+        }
+
+        flags
+    }
+}
+
+impl IntoIterator for GethArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        if !self.genesis {
+            return self.flags().into_iter();
+        }
+
+        // dev mode keeps the chain of an initialized data directory instead of generating one
+        let script = format!(
+            "geth --datadir {dir} init {genesis} && exec geth --datadir {dir} {flags}",
+            dir = DATA_DIR,
+            genesis = GENESIS_FILE,
+            flags = self.flags().join(" ")
+        );
+
+        vec!["-c".to_owned(), script].into_iter()
+    }
+}
+
+/// A go-ethereum node in developer mode.
+///
+/// The node mines blocks with a pre-funded developer account, which is unlocked and available
+/// through `eth_accounts`. Because the log output of geth changes between versions, the node is
+/// considered ready once it answers `eth_blockNumber`.
+#[derive(Debug)]
+pub struct Geth {
+    tag: String,
+    arguments: GethArgs,
+    genesis: Option<Vec<u8>>,
+}
+
+impl Default for Geth {
+    fn default() -> Self {
+        Geth {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: GethArgs::default(),
+            genesis: None,
+        }
+    }
+}
+
+impl Image for Geth {
+    type Args = GethArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(
+            HttpWaitStrategy::new(HTTP_PORT, "/")
+                .with_method("POST")
+                .with_header("Content-Type", "application/json")
+                .with_body(r#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":1}"#)
+                .with_response_containing(r#""result""#),
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        GethArgs {
+            genesis: self.genesis.is_some(),
+            ..self.arguments.clone()
+        }
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Geth { arguments, ..self }
+    }
+
+    fn entrypoint(&self) -> Option<String> {
+        // the genesis has to be written to the data directory before the node starts
+        self.genesis.as_ref().map(|_| "sh".to_owned())
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        self.genesis
+            .iter()
+            .map(|genesis| CopyToContainer::new(genesis.clone(), GENESIS_FILE))
+            .collect()
+    }
+}
+
+impl Geth {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Geth {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Enables an additional API namespace on the HTTP endpoint, e.g. `debug` or `txpool`.
+    pub fn with_api<A: Into<String>>(mut self, api: A) -> Self {
+        self.arguments.apis.push(api.into());
+        self
+    }
+
+    /// Mines a block every given number of seconds instead of only when transactions are pending.
+    pub fn with_dev_period(self, seconds: u64) -> Self {
+        Geth {
+            arguments: GethArgs {
+                dev_period: Some(seconds),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Initializes the chain from the given `genesis.json` instead of the one generated for
+    /// developer mode.
+    ///
+    /// Blocks are only mined if the genesis configures clique with the developer account as
+    /// signer.
+    pub fn with_genesis<G: Into<Vec<u8>>>(self, genesis: G) -> Self {
+        Geth {
+            genesis: Some(genesis.into()),
+            ..self
+        }
+    }
+
+    /// Returns the url of the JSON-RPC API over HTTP, given the host port mapped to [`HTTP_PORT`].
+    pub fn http_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Returns the url of the JSON-RPC API over WebSocket, given the host port mapped to
+    /// [`WS_PORT`].
+    pub fn ws_url(&self, host_port: u16) -> String {
+        format!("ws://localhost:{}", host_port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_start_dev_node_with_additional_apis() {
+        let image = Geth::default().with_api("debug").with_dev_period(1);
+
+        assert_eq!(image.entrypoint(), None);
+        assert_eq!(
+            image.args().into_iter().collect::<Vec<_>>(),
+            vec![
+                "--dev",
+                "--http",
+                "--http.addr",
+                "0.0.0.0",
+                "--http.api",
+                "eth,net,web3,personal,debug",
+                "--ws",
+                "--ws.addr",
+                "0.0.0.0",
+                "--dev.period",
+                "1"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_init_custom_genesis_before_starting_node() {
+        let image = Geth::default().with_genesis(r#"{"config":{"chainId":4242}}"#);
+
+        assert_eq!(image.entrypoint(), Some("sh".to_owned()));
+        assert_eq!(
+            image.args().into_iter().collect::<Vec<_>>(),
+            vec![
+                "-c",
+                "geth --datadir /data init /genesis.json && exec geth --datadir /data --dev --http --http.addr 0.0.0.0 --http.api eth,net,web3,personal --ws --ws.addr 0.0.0.0"
+            ]
+        );
+        assert_eq!(image.copy_to_sources()[0].target, "/genesis.json");
+    }
+}
@@ -0,0 +1,235 @@
+use crate::{
+    core::{wait::blocking_request, CopyToContainer, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "grafana/grafana";
+const DEFAULT_TAG: &str = "10.2.2";
+
+/// Port of the web server and HTTP API.
+pub const PORT: u16 = 3000;
+
+const PROVISIONING_DIR: &str = "/etc/grafana/provisioning";
+const DASHBOARD_DIR: &str = "/var/lib/grafana/dashboards";
+
+#[derive(Debug, Default, Clone)]
+pub struct GrafanaArgs;
+
+impl IntoIterator for GrafanaArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A Grafana server with provisioned datasources and dashboards.
+///
+/// Datasources and dashboards added through [`with_provisioned_datasource`] and
+/// [`with_dashboard`] are copied into the container before it starts, so Grafana provisions them
+/// on startup. The admin user is `admin` with the password `admin` by default.
+///
+/// [`with_provisioned_datasource`]: struct.Grafana.html#method.with_provisioned_datasource
+/// [`with_dashboard`]: struct.Grafana.html#method.with_dashboard
+#[derive(Debug)]
+pub struct Grafana {
+    tag: String,
+    arguments: GrafanaArgs,
+    admin_user: String,
+    admin_password: String,
+    anonymous_access: bool,
+    datasources: Vec<Vec<u8>>,
+    dashboards: Vec<Vec<u8>>,
+}
+
+impl Default for Grafana {
+    fn default() -> Self {
+        Grafana {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: GrafanaArgs,
+            admin_user: "admin".to_owned(),
+            admin_password: "admin".to_owned(),
+            anonymous_access: false,
+            datasources: Vec::new(),
+            dashboards: Vec::new(),
+        }
+    }
+}
+
+impl Image for Grafana {
+    type Args = GrafanaArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        // the database is the only component the health check reports on
+        vec![WaitFor::http(
+            HttpWaitStrategy::new(PORT, "/api/health").with_response_containing(r#""ok""#),
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("GF_SECURITY_ADMIN_USER".to_owned(), self.admin_user.clone());
+        env_vars.insert(
+            "GF_SECURITY_ADMIN_PASSWORD".to_owned(),
+            self.admin_password.clone(),
+        );
+        if self.anonymous_access {
+            env_vars.insert("GF_AUTH_ANONYMOUS_ENABLED".to_owned(), "true".to_owned());
+            env_vars.insert("GF_AUTH_ANONYMOUS_ORG_ROLE".to_owned(), "Admin".to_owned());
+        }
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Grafana { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        let mut files = self
+            .datasources
+            .iter()
+            .enumerate()
+            .map(|(i, datasource)| {
+                CopyToContainer::new(
+                    datasource.clone(),
+                    format!("{}/datasources/testcontainers-{}.yaml", PROVISIONING_DIR, i),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if !self.dashboards.is_empty() {
+            files.push(CopyToContainer::new(
+                dashboard_provider(),
+                format!("{}/dashboards/testcontainers.yaml", PROVISIONING_DIR),
+            ));
+            files.extend(self.dashboards.iter().enumerate().map(|(i, dashboard)| {
+                CopyToContainer::new(
+                    dashboard.clone(),
+                    format!("{}/testcontainers-{}.json", DASHBOARD_DIR, i),
+                )
+            }));
+        }
+
+        files
+    }
+}
+
+impl Grafana {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Grafana {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the credentials of the admin user, `admin` and `admin` by default.
+    pub fn with_admin<U: Into<String>, P: Into<String>>(self, user: U, password: P) -> Self {
+        Grafana {
+            admin_user: user.into(),
+            admin_password: password.into(),
+            ..self
+        }
+    }
+
+    /// Allows anonymous requests, which act as admin of the default organization.
+    pub fn with_anonymous_access(self) -> Self {
+        Grafana {
+            anonymous_access: true,
+            ..self
+        }
+    }
+
+    /// Provisions the datasources of the given provisioning file, e.g.
+    /// `apiVersion: 1\ndatasources:\n  - name: Prometheus\n    type: prometheus\n    url: http://prometheus:9090\n`.
+    pub fn with_provisioned_datasource<Y: Into<Vec<u8>>>(mut self, yaml: Y) -> Self {
+        self.datasources.push(yaml.into());
+        self
+    }
+
+    /// Provisions the dashboard of the given JSON model in the General folder.
+    pub fn with_dashboard<J: Into<Vec<u8>>>(mut self, json: J) -> Self {
+        self.dashboards.push(json.into());
+        self
+    }
+
+    /// Returns the url of the web server, given the host port mapped to [`PORT`].
+    pub fn url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Sends a `GET` request to the given path of the HTTP API as admin user and returns the
+    /// response, given the host port mapped to [`PORT`], e.g. for `/api/datasources`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the server cannot be reached, rejects the request or does not
+    /// respond with JSON.
+    pub fn api_get(&self, host_port: u16, path: &str) -> serde_json::Value {
+        let url = format!("{}/{}", self.url(host_port), path.trim_start_matches('/'));
+        let (user, password) = (self.admin_user.clone(), self.admin_password.clone());
+
+        let response =
+            blocking_request(move |client| client.get(&url).basic_auth(user, Some(password)))
+                .unwrap_or_else(|e| panic!("failed to get {}: {}", path, e));
+
+        serde_json::from_str(&response)
+            .unwrap_or_else(|e| panic!("failed to parse response of {}: {}", path, e))
+    }
+}
+
+fn dashboard_provider() -> String {
+    format!(
+        "apiVersion: 1\nproviders:\n  - name: testcontainers\n    type: file\n    options:\n      path: {}\n",
+        DASHBOARD_DIR
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_only_provision_dashboard_provider_with_dashboards() {
+        let image = Grafana::default().with_provisioned_datasource("apiVersion: 1\n");
+        assert_eq!(
+            image
+                .copy_to_sources()
+                .into_iter()
+                .map(|file| file.target)
+                .collect::<Vec<_>>(),
+            vec!["/etc/grafana/provisioning/datasources/testcontainers-0.yaml"]
+        );
+
+        let image = image.with_dashboard(r#"{"title":"Overview"}"#);
+        assert_eq!(
+            image
+                .copy_to_sources()
+                .into_iter()
+                .map(|file| file.target)
+                .collect::<Vec<_>>(),
+            vec![
+                "/etc/grafana/provisioning/datasources/testcontainers-0.yaml",
+                "/etc/grafana/provisioning/dashboards/testcontainers.yaml",
+                "/var/lib/grafana/dashboards/testcontainers-0.json"
+            ]
+        );
+    }
+}
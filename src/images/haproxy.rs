@@ -0,0 +1,244 @@
+use crate::{
+    core::{CopyToContainer, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+    net::TcpStream,
+};
+
+const CONTAINER_IDENTIFIER: &str = "haproxy";
+const DEFAULT_TAG: &str = "2.8.4-alpine";
+
+/// Port of the frontend in the generated config, unless changed through
+/// [`HaProxy::with_frontend_port`].
+///
+/// [`HaProxy::with_frontend_port`]: struct.HaProxy.html#method.with_frontend_port
+pub const FRONTEND_PORT: u16 = 8080;
+/// Port of the statistics page at `/stats` in the generated config.
+pub const STATS_PORT: u16 = 8404;
+/// Port of the runtime API in the generated config, see [`HaProxy::runtime_command`].
+///
+/// [`HaProxy::runtime_command`]: struct.HaProxy.html#method.runtime_command
+pub const RUNTIME_PORT: u16 = 9999;
+
+const CONFIG_FILE: &str = "/usr/local/etc/haproxy/haproxy.cfg";
+
+#[derive(Debug, Default, Clone)]
+pub struct HaProxyArgs;
+
+impl IntoIterator for HaProxyArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// An HAProxy load balancer.
+///
+/// Unless a config is passed through [`with_config`], HAProxy balances HTTP requests to
+/// [`FRONTEND_PORT`] round-robin across the backends added through [`with_backend`], which are
+/// other containers on the same network. It serves its statistics on [`STATS_PORT`] and its
+/// runtime API on [`RUNTIME_PORT`]. HAProxy resolves the backends on startup, so they need to run
+/// before it is started.
+///
+/// [`with_config`]: struct.HaProxy.html#method.with_config
+/// [`with_backend`]: struct.HaProxy.html#method.with_backend
+#[derive(Debug)]
+pub struct HaProxy {
+    tag: String,
+    arguments: HaProxyArgs,
+    config: Option<Vec<u8>>,
+    frontend_port: u16,
+    backends: Vec<(String, u16)>,
+}
+
+impl Default for HaProxy {
+    fn default() -> Self {
+        HaProxy {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: HaProxyArgs,
+            config: None,
+            frontend_port: FRONTEND_PORT,
+            backends: Vec::new(),
+        }
+    }
+}
+
+impl Image for HaProxy {
+    type Args = HaProxyArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        match self.config {
+            // the master process reports once the workers run the config
+            Some(_) => vec![WaitFor::message_on_stderr("Loading success.")],
+            None => vec![WaitFor::http(HttpWaitStrategy::new(STATS_PORT, "/stats"))],
+        }
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        HaProxy { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        let config = match &self.config {
+            Some(config) => config.clone(),
+            None => self.generated_config().into_bytes(),
+        };
+
+        vec![CopyToContainer::new(config, CONFIG_FILE)]
+    }
+
+    fn expose_ports(&self) -> Vec<u16> {
+        match self.config {
+            Some(_) => vec![],
+            None => vec![self.frontend_port, STATS_PORT, RUNTIME_PORT],
+        }
+    }
+}
+
+impl HaProxy {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        HaProxy {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Replaces the generated config, e.g. to configure sticky sessions. Ports the config binds to
+    /// need to be published through [`RunArgs::with_mapped_port`], as the image does not declare
+    /// any.
+    ///
+    /// [`RunArgs::with_mapped_port`]: ../../struct.RunArgs.html#method.with_mapped_port
+    pub fn with_config<C: Into<Vec<u8>>>(self, config: C) -> Self {
+        HaProxy {
+            config: Some(config.into()),
+            ..self
+        }
+    }
+
+    /// Sets the port the frontend of the generated config binds to, [`FRONTEND_PORT`] by default.
+    pub fn with_frontend_port(self, frontend_port: u16) -> Self {
+        HaProxy {
+            frontend_port,
+            ..self
+        }
+    }
+
+    /// Adds a server to the backend of the generated config, i.e. the container with the given
+    /// name or network alias listening on the port. The server is named after the alias in the
+    /// runtime API, e.g. `disable server servers/<alias>`.
+    pub fn with_backend<A: Into<String>>(mut self, alias: A, port: u16) -> Self {
+        self.backends.push((alias.into(), port));
+        self
+    }
+
+    /// Returns the port the frontend of the generated config binds to.
+    pub fn frontend_port(&self) -> u16 {
+        self.frontend_port
+    }
+
+    /// Returns the url of the frontend, given the host port mapped to its port.
+    pub fn frontend_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Sends the command to the runtime API and returns its response, given the host port mapped
+    /// to [`RUNTIME_PORT`], e.g. `disable server servers/backend-a` to take a backend out of the
+    /// rotation in the middle of a test.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the runtime API cannot be reached.
+    pub fn runtime_command(&self, host_port: u16, cmd: &str) -> String {
+        let send = || -> io::Result<String> {
+            let mut stream = TcpStream::connect(("localhost", host_port))?;
+            stream.write_all(format!("{}\n", cmd).as_bytes())?;
+
+            // the runtime API closes the connection after answering a single command
+            let mut response = String::new();
+            stream.read_to_string(&mut response)?;
+
+            Ok(response)
+        };
+
+        send().unwrap_or_else(|e| panic!("failed to send {} to the runtime API: {}", cmd, e))
+    }
+
+    fn generated_config(&self) -> String {
+        let mut config = format!(
+            "global
+    stats socket ipv4@0.0.0.0:{runtime_port} level admin
+
+defaults
+    mode http
+    timeout connect 5s
+    timeout client 30s
+    timeout server 30s
+
+frontend stats
+    bind :{stats_port}
+    stats enable
+    stats uri /stats
+
+frontend testcontainers
+    bind :{frontend_port}
+    default_backend servers
+
+backend servers
+    balance roundrobin
+",
+            runtime_port = RUNTIME_PORT,
+            stats_port = STATS_PORT,
+            frontend_port = self.frontend_port,
+        );
+
+        for (alias, port) in &self.backends {
+            config.push_str(&format!("    server {0} {0}:{1} check\n", alias, port));
+        }
+
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_generate_config_balancing_across_backends() {
+        let image = HaProxy::default()
+            .with_frontend_port(8000)
+            .with_backend("web-a", 80)
+            .with_backend("web-b", 80);
+        let config = image.generated_config();
+
+        assert!(config.contains("    bind :8000\n"));
+        assert!(
+            config.contains("    server web-a web-a:80 check\n    server web-b web-b:80 check\n")
+        );
+        assert_eq!(image.expose_ports(), vec![8000, STATS_PORT, RUNTIME_PORT]);
+    }
+}
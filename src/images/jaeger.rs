@@ -0,0 +1,227 @@
+use crate::{
+    core::{wait::blocking_request, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "jaegertracing/all-in-one";
+const DEFAULT_TAG: &str = "1.52";
+
+/// Port of the web UI and the query API.
+pub const QUERY_PORT: u16 = 16686;
+/// Port of the OTLP receiver speaking gRPC.
+pub const OTLP_GRPC_PORT: u16 = 4317;
+/// Port of the OTLP receiver speaking HTTP.
+pub const OTLP_HTTP_PORT: u16 = 4318;
+/// UDP port of the agent accepting spans in the compact thrift protocol.
+///
+/// Use [`Container::get_host_port_udp`] to look up the host port of this port.
+///
+/// [`Container::get_host_port_udp`]: ../../struct.Container.html#method.get_host_port_udp
+pub const AGENT_COMPACT_PORT: u16 = 6831;
+/// Port of the admin server, serving health checks and metrics.
+pub const ADMIN_PORT: u16 = 14269;
+
+#[derive(Debug, Default, Clone)]
+pub struct JaegerArgs;
+
+impl IntoIterator for JaegerArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A Jaeger all-in-one instance, running the collector, the query service and an in-memory
+/// storage in a single container.
+///
+/// The OTLP receivers are enabled, so applications can export spans through OpenTelemetry to
+/// [`otlp_grpc_endpoint`] or [`otlp_http_endpoint`]. The collected traces can be inspected through
+/// [`find_traces`].
+///
+/// [`otlp_grpc_endpoint`]: struct.Jaeger.html#method.otlp_grpc_endpoint
+/// [`otlp_http_endpoint`]: struct.Jaeger.html#method.otlp_http_endpoint
+/// [`find_traces`]: struct.Jaeger.html#method.find_traces
+#[derive(Debug)]
+pub struct Jaeger {
+    tag: String,
+    arguments: JaegerArgs,
+}
+
+impl Default for Jaeger {
+    fn default() -> Self {
+        Jaeger {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: JaegerArgs {},
+        }
+    }
+}
+
+/// A trace as reported by the query API.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Trace {
+    #[serde(rename = "traceID")]
+    pub trace_id: String,
+    pub spans: Vec<Span>,
+}
+
+/// A span of a [`Trace`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Span {
+    #[serde(rename = "traceID")]
+    pub trace_id: String,
+    #[serde(rename = "spanID")]
+    pub span_id: String,
+    #[serde(rename = "operationName")]
+    pub operation_name: String,
+}
+
+#[derive(Deserialize)]
+struct Traces {
+    // the query API reports `null` instead of an empty list if nothing was found
+    data: Option<Vec<Trace>>,
+}
+
+impl Image for Jaeger {
+    type Args = JaegerArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(ADMIN_PORT, "/"))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("COLLECTOR_OTLP_ENABLED".to_owned(), "true".to_owned());
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Jaeger { arguments, ..self }
+    }
+}
+
+impl Jaeger {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Jaeger {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Returns the endpoint of the OTLP gRPC receiver, given the host port mapped to
+    /// [`OTLP_GRPC_PORT`].
+    pub fn otlp_grpc_endpoint(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Returns the endpoint of the OTLP HTTP receiver, given the host port mapped to
+    /// [`OTLP_HTTP_PORT`].
+    pub fn otlp_http_endpoint(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Returns the url of the web UI, given the host port mapped to [`QUERY_PORT`].
+    pub fn ui_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Returns the traces recorded for the given service, given the host port mapped to
+    /// [`QUERY_PORT`].
+    ///
+    /// Spans are ingested asynchronously, so recently exported spans may not be reported yet.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the query API cannot be reached.
+    pub fn find_traces(&self, host_port: u16, service_name: &str) -> Vec<Trace> {
+        let url = format!("{}/api/traces", self.ui_url(host_port));
+        let service_name = service_name.to_owned();
+
+        let body =
+            blocking_request(move |client| client.get(&url).query(&[("service", service_name)]))
+                .unwrap_or_else(|e| panic!("failed to find traces: {}", e));
+
+        serde_json::from_str::<Traces>(&body)
+            .unwrap_or_else(|e| panic!("failed to parse traces: {}", e))
+            .data
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_deserialize_traces() {
+        let traces = serde_json::from_str::<Traces>(
+            r#"{
+                "data": [
+                    {
+                        "traceID": "5b8aa5a2d2c872e8321cf37308d69df2",
+                        "spans": [
+                            {
+                                "traceID": "5b8aa5a2d2c872e8321cf37308d69df2",
+                                "spanID": "051581bf3cb55c13",
+                                "operationName": "checkout",
+                                "references": [],
+                                "startTime": 1700000000000000,
+                                "duration": 1000,
+                                "tags": [],
+                                "logs": [],
+                                "processID": "p1",
+                                "warnings": null
+                            }
+                        ],
+                        "processes": { "p1": { "serviceName": "shop", "tags": [] } },
+                        "warnings": null
+                    }
+                ],
+                "total": 0,
+                "limit": 0,
+                "offset": 0,
+                "errors": null
+            }"#,
+        )
+        .unwrap()
+        .data;
+
+        assert_eq!(
+            traces,
+            Some(vec![Trace {
+                trace_id: "5b8aa5a2d2c872e8321cf37308d69df2".to_owned(),
+                spans: vec![Span {
+                    trace_id: "5b8aa5a2d2c872e8321cf37308d69df2".to_owned(),
+                    span_id: "051581bf3cb55c13".to_owned(),
+                    operation_name: "checkout".to_owned(),
+                }],
+            }])
+        );
+    }
+
+    #[test]
+    fn should_treat_missing_traces_as_empty() {
+        let traces = serde_json::from_str::<Traces>(r#"{ "data": null }"#).unwrap();
+
+        assert_eq!(traces.data.unwrap_or_default(), vec![]);
+    }
+}
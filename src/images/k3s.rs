@@ -0,0 +1,183 @@
+use crate::{
+    core::{Container, ContainerState, CopyToContainer, ExecCommand, WaitFor},
+    Image,
+};
+use std::{collections::HashMap, time::Duration};
+
+const CONTAINER_IDENTIFIER: &str = "rancher/k3s";
+const DEFAULT_TAG: &str = "v1.25.16-k3s1";
+
+/// Port of the Kubernetes API server.
+pub const API_PORT: u16 = 6443;
+
+/// The kubeconfig of the cluster admin inside the container, pointing to the API server at
+/// `127.0.0.1:6443`.
+pub const KUBECONFIG_FILE: &str = "/etc/rancher/k3s/k3s.yaml";
+const MANIFEST_DIR: &str = "/var/lib/rancher/k3s/server/manifests";
+
+/// Pulling the images of the system pods takes a while on a fresh daemon.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(180);
+
+#[derive(Debug, Default, Clone)]
+pub struct K3sArgs;
+
+impl IntoIterator for K3sArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        // the ingress controller is not needed to test against the API and slows down the startup
+        vec!["server".to_owned(), "--disable=traefik".to_owned()].into_iter()
+    }
+}
+
+/// A single-node Kubernetes cluster run by K3s, e.g. to test controllers and operators.
+///
+/// K3s runs its pods inside the container, which therefore runs privileged and with tmpfs mounts
+/// for `/run` and `/var/run`, both of which the `Cli` and the `Http` client support. Clients
+/// connect through the [`kubeconfig`] of the container. On the `Http` client, read
+/// [`KUBECONFIG_FILE`] through `ContainerAsync::copy_file_from` and point its server to the host
+/// port mapped to [`API_PORT`] instead.
+///
+/// [`kubeconfig`]: ../../core/struct.Container.html#method.kubeconfig
+#[derive(Debug)]
+pub struct K3s {
+    tag: String,
+    arguments: K3sArgs,
+    manifests: Vec<String>,
+}
+
+impl Default for K3s {
+    fn default() -> Self {
+        K3s {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: K3sArgs,
+            manifests: Vec::new(),
+        }
+    }
+}
+
+impl Image for K3s {
+    type Args = K3sArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::message_on_stderr("k3s is up and running")]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        K3s { arguments, ..self }
+    }
+
+    fn startup_timeout(&self) -> Option<Duration> {
+        Some(STARTUP_TIMEOUT)
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        // K3s applies the manifests of the directory in alphabetical order
+        self.manifests
+            .iter()
+            .enumerate()
+            .map(|(index, manifest)| {
+                CopyToContainer::new(
+                    manifest.clone(),
+                    format!("{}/{:02}-testcontainers.yaml", MANIFEST_DIR, index),
+                )
+            })
+            .collect()
+    }
+
+    fn exec_after_start(&self, _: ContainerState) -> Vec<ExecCommand> {
+        // K3s reports to be up before the API server passed its own readiness checks
+        vec![ExecCommand::shell_until(
+            "kubectl get --raw=/readyz > /dev/null 2>&1",
+            Duration::from_millis(100),
+            STARTUP_TIMEOUT,
+        )]
+    }
+
+    fn privileged(&self) -> bool {
+        true
+    }
+
+    fn tmpfs(&self) -> Vec<String> {
+        vec!["/run".to_owned(), "/var/run".to_owned()]
+    }
+}
+
+impl K3s {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        K3s {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Deploys the resources of the given YAML manifest once the cluster started, in the order
+    /// the manifests were added.
+    pub fn with_manifest<M: Into<String>>(mut self, manifest: M) -> Self {
+        self.manifests.push(manifest.into());
+        self
+    }
+}
+
+impl<'d> Container<'d, K3s> {
+    /// Returns the kubeconfig of the cluster admin, pointing to the host port mapped to
+    /// [`API_PORT`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the kubeconfig cannot be read from the container.
+    ///
+    /// [`API_PORT`]: ../images/k3s/constant.API_PORT.html
+    pub fn kubeconfig(&self) -> String {
+        let kubeconfig =
+            String::from_utf8(self.copy_file_from(KUBECONFIG_FILE)).unwrap_or_else(|e| {
+                panic!("kubeconfig of container {} is not UTF-8: {}", self.id(), e)
+            });
+
+        rewrite_server(&kubeconfig, self.get_host_port(API_PORT))
+    }
+}
+
+/// K3s writes the kubeconfig for clients inside the container, the certificate of the API server
+/// is valid for `127.0.0.1` on the host as well.
+fn rewrite_server(kubeconfig: &str, host_port: u16) -> String {
+    kubeconfig.replace(
+        &format!("https://127.0.0.1:{}", API_PORT),
+        &format!("https://127.0.0.1:{}", host_port),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_point_kubeconfig_to_host_port() {
+        let kubeconfig = "apiVersion: v1\nclusters:\n- cluster:\n    server: https://127.0.0.1:6443\n  name: default\n";
+
+        assert_eq!(
+            rewrite_server(kubeconfig, 32768),
+            "apiVersion: v1\nclusters:\n- cluster:\n    server: https://127.0.0.1:32768\n  name: default\n"
+        );
+    }
+}
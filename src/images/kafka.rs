@@ -84,7 +84,7 @@ impl Default for Kafka {
         );
 
         Self {
-            arguments: KafkaArgs::default(),
+            arguments: KafkaArgs,
             env_vars,
             tag: DEFAULT_TAG.to_owned(),
         }
@@ -0,0 +1,210 @@
+use crate::{
+    core::{wait::blocking_request, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "axllent/mailpit";
+const DEFAULT_TAG: &str = "v1.13";
+
+/// Port of the SMTP server.
+pub const SMTP_PORT: u16 = 1025;
+/// Port of the HTTP API and web UI.
+pub const HTTP_PORT: u16 = 8025;
+
+#[derive(Debug, Default, Clone)]
+pub struct MailpitArgs;
+
+impl IntoIterator for MailpitArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A Mailpit server that accepts all mails sent to it through SMTP.
+///
+/// The received mails can be inspected through [`messages`] and removed through
+/// [`clear_messages`].
+///
+/// [`messages`]: struct.Mailpit.html#method.messages
+/// [`clear_messages`]: struct.Mailpit.html#method.clear_messages
+#[derive(Debug)]
+pub struct Mailpit {
+    tag: String,
+    arguments: MailpitArgs,
+}
+
+impl Default for Mailpit {
+    fn default() -> Self {
+        Mailpit {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: MailpitArgs {},
+        }
+    }
+}
+
+/// A mail address as reported by the Mailpit API.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Address {
+    pub name: String,
+    pub address: String,
+}
+
+/// The summary of a received mail as reported by the Mailpit API.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MessageSummary {
+    #[serde(rename = "ID")]
+    pub id: String,
+    pub from: Address,
+    pub to: Vec<Address>,
+    pub subject: String,
+    pub snippet: String,
+}
+
+#[derive(Deserialize)]
+struct Messages {
+    messages: Vec<MessageSummary>,
+}
+
+impl Image for Mailpit {
+    type Args = MailpitArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(
+            HTTP_PORT,
+            "/api/v1/messages",
+        ))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Mailpit { arguments, ..self }
+    }
+}
+
+impl Mailpit {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Mailpit {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Returns the address of the SMTP server, given the host port mapped to [`SMTP_PORT`].
+    pub fn smtp_addr(&self, host_port: u16) -> String {
+        format!("localhost:{}", host_port)
+    }
+
+    /// Returns the url of the HTTP API, given the host port mapped to [`HTTP_PORT`].
+    pub fn api_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}/api/v1", host_port)
+    }
+
+    /// Returns the summaries of all received mails, newest first, given the host port mapped to
+    /// [`HTTP_PORT`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the API cannot be reached.
+    pub fn messages(&self, host_port: u16) -> Vec<MessageSummary> {
+        let url = format!("{}/messages", self.api_url(host_port));
+
+        let body = blocking_request(move |client| client.get(&url))
+            .unwrap_or_else(|e| panic!("failed to list messages: {}", e));
+
+        serde_json::from_str::<Messages>(&body)
+            .unwrap_or_else(|e| panic!("failed to parse messages: {}", e))
+            .messages
+    }
+
+    /// Deletes all received mails, given the host port mapped to [`HTTP_PORT`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the API cannot be reached.
+    pub fn clear_messages(&self, host_port: u16) {
+        let url = format!("{}/messages", self.api_url(host_port));
+
+        blocking_request(move |client| client.delete(&url))
+            .unwrap_or_else(|e| panic!("failed to delete messages: {}", e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_deserialize_message_summaries() {
+        let messages = serde_json::from_str::<Messages>(
+            r#"{
+                "total": 1,
+                "unread": 1,
+                "count": 1,
+                "start": 0,
+                "tags": [],
+                "messages": [
+                    {
+                        "ID": "k4UmWtnAb3ZvJtLFx2DJfw",
+                        "MessageID": "1@example.com",
+                        "Read": false,
+                        "From": { "Name": "Alice", "Address": "alice@example.com" },
+                        "To": [ { "Name": "", "Address": "bob@example.com" } ],
+                        "Cc": [],
+                        "Bcc": [],
+                        "ReplyTo": [],
+                        "Subject": "Hello",
+                        "Created": "2023-01-01T00:00:00Z",
+                        "Tags": [],
+                        "Size": 311,
+                        "Attachments": 0,
+                        "Snippet": "Hi Bob!"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap()
+        .messages;
+
+        assert_eq!(
+            messages,
+            vec![MessageSummary {
+                id: "k4UmWtnAb3ZvJtLFx2DJfw".to_owned(),
+                from: Address {
+                    name: "Alice".to_owned(),
+                    address: "alice@example.com".to_owned()
+                },
+                to: vec![Address {
+                    name: "".to_owned(),
+                    address: "bob@example.com".to_owned()
+                }],
+                subject: "Hello".to_owned(),
+                snippet: "Hi Bob!".to_owned(),
+            }]
+        );
+    }
+}
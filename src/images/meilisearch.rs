@@ -0,0 +1,234 @@
+use crate::{
+    core::{wait::blocking_request, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+const CONTAINER_IDENTIFIER: &str = "getmeili/meilisearch";
+const DEFAULT_TAG: &str = "v1.5.1";
+
+/// Port of the REST API.
+pub const PORT: u16 = 7700;
+
+const TASK_TIMEOUT: Duration = Duration::from_secs(30);
+const TASK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Default, Clone)]
+pub struct MeilisearchArgs;
+
+impl IntoIterator for MeilisearchArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A Meilisearch server.
+///
+/// Meilisearch enqueues all writes, e.g. creating an index or adding documents, as tasks and
+/// processes them in the background. To not race the indexing, tests wait for the task returned by
+/// a write through [`Tasks::wait_for_task`] before searching.
+///
+/// [`Tasks::wait_for_task`]: struct.Tasks.html#method.wait_for_task
+#[derive(Debug)]
+pub struct Meilisearch {
+    tag: String,
+    arguments: MeilisearchArgs,
+    master_key: Option<String>,
+    env: Option<String>,
+}
+
+impl Default for Meilisearch {
+    fn default() -> Self {
+        Meilisearch {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: MeilisearchArgs,
+            master_key: None,
+            env: None,
+        }
+    }
+}
+
+impl Image for Meilisearch {
+    type Args = MeilisearchArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(
+            HttpWaitStrategy::new(PORT, "/health").with_response_containing("available"),
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+        if let Some(master_key) = &self.master_key {
+            env_vars.insert("MEILI_MASTER_KEY".to_owned(), master_key.clone());
+        }
+        if let Some(env) = &self.env {
+            env_vars.insert("MEILI_ENV".to_owned(), env.clone());
+        }
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Meilisearch { arguments, ..self }
+    }
+}
+
+impl Meilisearch {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Meilisearch {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Protects the API with the given master key, which requests pass as bearer token.
+    ///
+    /// Unless set, the API is not protected.
+    pub fn with_master_key<K: Into<String>>(self, master_key: K) -> Self {
+        Meilisearch {
+            master_key: Some(master_key.into()),
+            ..self
+        }
+    }
+
+    /// Sets the environment the server runs in, `development` or `production`, which requires a
+    /// master key of at least 16 bytes.
+    pub fn with_env<E: Into<String>>(self, env: E) -> Self {
+        Meilisearch {
+            env: Some(env.into()),
+            ..self
+        }
+    }
+
+    /// Returns the master key set through [`with_master_key`], if any.
+    ///
+    /// [`with_master_key`]: struct.Meilisearch.html#method.with_master_key
+    pub fn master_key(&self) -> Option<&str> {
+        self.master_key.as_deref()
+    }
+
+    /// Returns the url of the REST API, given the host port mapped to [`PORT`].
+    pub fn url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Returns the task queue of the server, given the host port mapped to [`PORT`].
+    pub fn tasks(&self, host_port: u16) -> Tasks {
+        Tasks {
+            url: self.url(host_port),
+            master_key: self.master_key.clone(),
+        }
+    }
+}
+
+/// The task queue of a Meilisearch server, see [`Meilisearch::tasks`].
+///
+/// [`Meilisearch::tasks`]: struct.Meilisearch.html#method.tasks
+#[derive(Debug, Clone)]
+pub struct Tasks {
+    url: String,
+    master_key: Option<String>,
+}
+
+/// A task of a Meilisearch server, e.g. adding documents to an index.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    pub uid: u64,
+    /// One of `enqueued`, `processing`, `succeeded`, `failed` or `canceled`.
+    pub status: String,
+    /// The reason why the task failed, if it did.
+    #[serde(default)]
+    pub error: Option<serde_json::Value>,
+}
+
+impl Task {
+    fn is_finished(&self) -> bool {
+        !matches!(self.status.as_str(), "enqueued" | "processing")
+    }
+}
+
+impl Tasks {
+    /// Blocks until the task with the given uid, as returned by the write that enqueued it,
+    /// finished and returns it.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the server cannot be reached or the task does not finish within 30
+    /// seconds.
+    pub fn wait_for_task(&self, task_uid: u64) -> Task {
+        let deadline = Instant::now() + TASK_TIMEOUT;
+
+        loop {
+            let task = self.get(task_uid);
+
+            if task.is_finished() {
+                return task;
+            }
+            if Instant::now() >= deadline {
+                panic!("task {} did not finish within {:?}", task_uid, TASK_TIMEOUT)
+            }
+
+            std::thread::sleep(TASK_POLL_INTERVAL);
+        }
+    }
+
+    fn get(&self, task_uid: u64) -> Task {
+        let url = format!("{}/tasks/{}", self.url, task_uid);
+        let master_key = self.master_key.clone();
+
+        let response = blocking_request(move |client| {
+            let request = client.get(&url);
+
+            match master_key {
+                Some(master_key) => request.bearer_auth(master_key),
+                None => request,
+            }
+        })
+        .unwrap_or_else(|e| panic!("failed to fetch task {}: {}", task_uid, e));
+
+        serde_json::from_str(&response)
+            .unwrap_or_else(|e| panic!("failed to parse task {}: {}", task_uid, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_only_consider_tasks_in_final_state_finished() {
+        let task = |status: &str| {
+            serde_json::from_value::<Task>(serde_json::json!({ "uid": 0, "status": status }))
+                .unwrap()
+        };
+
+        assert!(!task("enqueued").is_finished());
+        assert!(!task("processing").is_finished());
+        assert!(task("succeeded").is_finished());
+        assert!(task("failed").is_finished());
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{
+    core::{HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "minio/minio";
+const DEFAULT_TAG: &str = "RELEASE.2023-12-23T07-19-11Z";
+
+const DEFAULT_ACCESS_KEY: &str = "minioadmin";
+const DEFAULT_SECRET_KEY: &str = "minioadmin";
+
+/// Port of the S3 API.
+pub const API_PORT: u16 = 9000;
+/// Port of the web console.
+pub const CONSOLE_PORT: u16 = 9001;
+
+#[derive(Debug, Default, Clone)]
+pub struct MinIOArgs;
+
+impl IntoIterator for MinIOArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![
+            "server".to_owned(),
+            "/data".to_owned(),
+            "--console-address".to_owned(),
+            format!(":{}", CONSOLE_PORT),
+        ]
+        .into_iter()
+    }
+}
+
+/// A single-node MinIO server that offers an S3-compatible API.
+///
+/// S3 clients need to use path-style requests, i.e. `http://localhost:<port>/<bucket>/<key>`,
+/// and may use any region.
+#[derive(Debug)]
+pub struct MinIO {
+    tag: String,
+    arguments: MinIOArgs,
+    access_key: String,
+    secret_key: String,
+}
+
+impl Default for MinIO {
+    fn default() -> Self {
+        MinIO {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: MinIOArgs {},
+            access_key: DEFAULT_ACCESS_KEY.to_string(),
+            secret_key: DEFAULT_SECRET_KEY.to_string(),
+        }
+    }
+}
+
+impl Image for MinIO {
+    type Args = MinIOArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(
+            API_PORT,
+            "/minio/health/live",
+        ))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("MINIO_ROOT_USER".to_owned(), self.access_key.clone());
+        env_vars.insert("MINIO_ROOT_PASSWORD".to_owned(), self.secret_key.clone());
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        MinIO { arguments, ..self }
+    }
+}
+
+impl MinIO {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        MinIO {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the credentials of the root user.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the secret key is shorter than 8 characters, which MinIO refuses to
+    /// start with.
+    pub fn with_credentials<A: Into<String>, S: Into<String>>(
+        self,
+        access_key: A,
+        secret_key: S,
+    ) -> Self {
+        let secret_key = secret_key.into();
+
+        assert!(
+            secret_key.len() >= 8,
+            "secret key of MinIO must be at least 8 characters long"
+        );
+
+        MinIO {
+            access_key: access_key.into(),
+            secret_key,
+            ..self
+        }
+    }
+
+    /// Returns the url of the S3 API, given the host port mapped to [`API_PORT`].
+    pub fn endpoint(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Returns the url of the web console, given the host port mapped to [`CONSOLE_PORT`].
+    pub fn console_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    pub fn access_key(&self) -> &str {
+        &self.access_key
+    }
+
+    pub fn secret_key(&self) -> &str {
+        &self.secret_key
+    }
+}
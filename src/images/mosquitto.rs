@@ -0,0 +1,158 @@
+use crate::{
+    core::{CopyToContainer, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "eclipse-mosquitto";
+const DEFAULT_TAG: &str = "2.0.18";
+
+/// Port of the MQTT listener.
+pub const PORT: u16 = 1883;
+
+const CONFIG_DIR: &str = "/mosquitto/config";
+
+/// Mosquitto 2 only accepts local connections unless a listener is configured explicitly.
+const DEFAULT_CONFIG: &str = "listener 1883\nallow_anonymous true\n";
+
+#[derive(Debug, Default, Clone)]
+pub struct MosquittoArgs;
+
+impl IntoIterator for MosquittoArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// An Eclipse Mosquitto MQTT broker, accepting anonymous clients on [`PORT`] by default.
+///
+/// A config passed through [`with_config`] needs to keep logging to stderr, which is the default
+/// of Mosquitto, as the broker is ready once it logs that it is running.
+///
+/// [`with_config`]: struct.Mosquitto.html#method.with_config
+#[derive(Debug)]
+pub struct Mosquitto {
+    tag: String,
+    arguments: MosquittoArgs,
+    config: Vec<u8>,
+    config_files: Vec<(String, Vec<u8>)>,
+}
+
+impl Default for Mosquitto {
+    fn default() -> Self {
+        Mosquitto {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: MosquittoArgs,
+            config: DEFAULT_CONFIG.into(),
+            config_files: Vec::new(),
+        }
+    }
+}
+
+impl Image for Mosquitto {
+    type Args = MosquittoArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        // e.g. `mosquitto version 2.0.18 starting` and `mosquitto version 2.0.18 running`
+        vec![WaitFor::messages_in_order_on_stderr(vec![
+            "mosquitto version",
+            " running",
+        ])]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Mosquitto { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        let mut files = vec![CopyToContainer::new(
+            self.config.clone(),
+            format!("{}/mosquitto.conf", CONFIG_DIR),
+        )];
+        files.extend(self.config_files.iter().map(|(name, content)| {
+            CopyToContainer::new(content.clone(), format!("{}/{}", CONFIG_DIR, name))
+        }));
+
+        files
+    }
+}
+
+impl Mosquitto {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Mosquitto {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Replaces the config of the broker, e.g. to require authentication:
+    /// `listener 1883\npassword_file /mosquitto/config/passwd\n`.
+    pub fn with_config<C: Into<Vec<u8>>>(self, config: C) -> Self {
+        Mosquitto {
+            config: config.into(),
+            ..self
+        }
+    }
+
+    /// Adds a file the config refers to, e.g. a password file created through `mosquitto_passwd`
+    /// or an ACL file, which is copied to `/mosquitto/config/<name>`.
+    pub fn with_config_file<N: Into<String>, C: Into<Vec<u8>>>(
+        mut self,
+        name: N,
+        content: C,
+    ) -> Self {
+        self.config_files.push((name.into(), content.into()));
+        self
+    }
+
+    /// Returns the url MQTT clients connect to, given the host port mapped to [`PORT`].
+    pub fn broker_url(&self, host_port: u16) -> String {
+        format!("mqtt://localhost:{}", host_port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_copy_config_files_next_to_config() {
+        let image = Mosquitto::default()
+            .with_config("listener 1883\npassword_file /mosquitto/config/passwd\n")
+            .with_config_file("passwd", "user:$7$hash");
+
+        assert_eq!(
+            image
+                .copy_to_sources()
+                .into_iter()
+                .map(|file| file.target)
+                .collect::<Vec<_>>(),
+            vec![
+                "/mosquitto/config/mosquitto.conf",
+                "/mosquitto/config/passwd"
+            ]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use crate::{
+    core::{CopyToContainer, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "nginx";
+const DEFAULT_TAG: &str = "1.25.3-alpine";
+
+/// Port of the HTTP server.
+pub const PORT: u16 = 80;
+/// Port of the HTTPS server, only listening if configured through [`Nginx::with_tls`].
+///
+/// [`Nginx::with_tls`]: struct.Nginx.html#method.with_tls
+pub const TLS_PORT: u16 = 443;
+
+const CONFIG_FILE: &str = "/etc/nginx/conf.d/default.conf";
+const TLS_CONFIG_FILE: &str = "/etc/nginx/conf.d/testcontainers-tls.conf";
+const CERT_FILE: &str = "/etc/nginx/certs/testcontainers.crt";
+const KEY_FILE: &str = "/etc/nginx/certs/testcontainers.key";
+const HTML_DIR: &str = "/usr/share/nginx/html";
+
+#[derive(Debug, Default, Clone)]
+pub struct NginxArgs;
+
+impl IntoIterator for NginxArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// An nginx server, serving the files below `/usr/share/nginx/html` on [`PORT`] by default.
+///
+/// Files added through [`with_static_file`] are served next to the welcome page of the image.
+/// The server becomes ready once it serves `/`, or once its workers started if the default config
+/// was replaced through [`with_config`], which might not serve `/` at all.
+///
+/// [`with_static_file`]: struct.Nginx.html#method.with_static_file
+/// [`with_config`]: struct.Nginx.html#method.with_config
+#[derive(Debug)]
+pub struct Nginx {
+    tag: String,
+    arguments: NginxArgs,
+    config: Option<Vec<u8>>,
+    static_files: Vec<(String, Vec<u8>)>,
+    tls: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Default for Nginx {
+    fn default() -> Self {
+        Nginx {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: NginxArgs,
+            config: None,
+            static_files: Vec::new(),
+            tls: None,
+        }
+    }
+}
+
+impl Image for Nginx {
+    type Args = NginxArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        match self.config {
+            // the notices of nginx go to stderr
+            Some(_) => vec![WaitFor::message_on_stderr("start worker processes")],
+            None => vec![WaitFor::http(HttpWaitStrategy::new(PORT, "/"))],
+        }
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Nginx { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        let mut files = self
+            .static_files
+            .iter()
+            .map(|(path, content)| {
+                CopyToContainer::new(
+                    content.clone(),
+                    format!("{}/{}", HTML_DIR, path.trim_start_matches('/')),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(config) = &self.config {
+            files.push(CopyToContainer::new(config.clone(), CONFIG_FILE));
+        }
+
+        if let Some((cert_pem, key_pem)) = &self.tls {
+            files.push(CopyToContainer::new(cert_pem.clone(), CERT_FILE));
+            files.push(CopyToContainer::new(key_pem.clone(), KEY_FILE).with_mode(0o600));
+            files.push(CopyToContainer::new(tls_config(), TLS_CONFIG_FILE));
+        }
+
+        files
+    }
+
+    fn expose_ports(&self) -> Vec<u16> {
+        // the image only declares the HTTP port
+        match self.tls {
+            Some(_) => vec![TLS_PORT],
+            None => vec![],
+        }
+    }
+}
+
+impl Nginx {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Nginx {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Replaces `/etc/nginx/conf.d/default.conf`, which serves the static files on [`PORT`], with
+    /// the given config, e.g. to proxy requests to another container.
+    pub fn with_config<C: Into<Vec<u8>>>(self, config: C) -> Self {
+        Nginx {
+            config: Some(config.into()),
+            ..self
+        }
+    }
+
+    /// Serves the given content at the path, relative to `/usr/share/nginx/html`, e.g.
+    /// `hello.txt` for `/hello.txt` or `index.html` to replace the welcome page.
+    pub fn with_static_file<P: Into<String>, C: Into<Vec<u8>>>(
+        mut self,
+        path: P,
+        content: C,
+    ) -> Self {
+        self.static_files.push((path.into(), content.into()));
+        self
+    }
+
+    /// Serves the static files over HTTPS on [`TLS_PORT`] as well, using the given PEM encoded
+    /// certificate chain and private key.
+    pub fn with_tls<C: Into<Vec<u8>>, K: Into<Vec<u8>>>(self, cert_pem: C, key_pem: K) -> Self {
+        Nginx {
+            tls: Some((cert_pem.into(), key_pem.into())),
+            ..self
+        }
+    }
+
+    /// Returns the url of the HTTP server, given the host port mapped to [`PORT`].
+    pub fn base_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Returns the url of the HTTPS server, given the host port mapped to [`TLS_PORT`].
+    pub fn tls_base_url(&self, host_port: u16) -> String {
+        format!("https://localhost:{}", host_port)
+    }
+}
+
+fn tls_config() -> String {
+    format!(
+        "server {{\n    listen {} ssl;\n    server_name localhost;\n    ssl_certificate {};\n    ssl_certificate_key {};\n\n    location / {{\n        root {};\n        index index.html;\n    }}\n}}\n",
+        TLS_PORT, CERT_FILE, KEY_FILE, HTML_DIR
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_serve_static_files_over_tls_only_if_configured() {
+        let image = Nginx::default().with_static_file("/assets/hello.txt", "hello");
+        assert_eq!(
+            image
+                .copy_to_sources()
+                .into_iter()
+                .map(|file| file.target)
+                .collect::<Vec<_>>(),
+            vec!["/usr/share/nginx/html/assets/hello.txt"]
+        );
+        assert!(image.expose_ports().is_empty());
+
+        let image = image.with_tls("cert", "key");
+        assert_eq!(
+            image
+                .copy_to_sources()
+                .into_iter()
+                .map(|file| file.target)
+                .collect::<Vec<_>>(),
+            vec![
+                "/usr/share/nginx/html/assets/hello.txt",
+                "/etc/nginx/certs/testcontainers.crt",
+                "/etc/nginx/certs/testcontainers.key",
+                "/etc/nginx/conf.d/testcontainers-tls.conf"
+            ]
+        );
+        assert_eq!(image.expose_ports(), vec![TLS_PORT]);
+    }
+}
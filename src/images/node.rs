@@ -0,0 +1,185 @@
+use crate::{
+    core::{CopyToContainer, HttpWaitStrategy, WaitFor},
+    images::generic::GenericImage,
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "node";
+const DEFAULT_TAG: &str = "20.10.0-alpine";
+
+/// Port the script listens on unless changed through [`NodeApp::with_port`].
+///
+/// [`NodeApp::with_port`]: struct.NodeApp.html#method.with_port
+pub const DEFAULT_PORT: u16 = 3000;
+
+const SCRIPT_FILE: &str = "/app/index.js";
+
+/// A Node.js script run through `node /app/index.js` in the official image, e.g. a webhook
+/// receiver that a test points the system under test to.
+///
+/// The script is told the port to listen on through the `PORT` env variable. It is ready once it
+/// answers a `GET` request for `/`, be it with `200` or `404`, unless configured otherwise
+/// through [`with_ready_path`] or [`with_ready_message`].
+///
+/// [`with_ready_path`]: struct.NodeApp.html#method.with_ready_path
+/// [`with_ready_message`]: struct.NodeApp.html#method.with_ready_message
+#[derive(Debug)]
+pub struct NodeApp {
+    image: GenericImage,
+    script: Vec<u8>,
+    port: u16,
+    ready_condition: ReadyCondition,
+}
+
+#[derive(Debug)]
+enum ReadyCondition {
+    Listening,
+    Path(String),
+    Message(String),
+}
+
+impl Default for NodeApp {
+    fn default() -> Self {
+        NodeApp::new(DEFAULT_TAG)
+    }
+}
+
+impl NodeApp {
+    /// Creates an app running in the image with the given tag, e.g. `20-alpine`.
+    pub fn new(tag: &str) -> Self {
+        NodeApp {
+            image: GenericImage::new(format!("{}:{}", CONTAINER_IDENTIFIER, tag))
+                .with_args(vec!["node".to_owned(), SCRIPT_FILE.to_owned()]),
+            script: Vec::new(),
+            port: DEFAULT_PORT,
+            ready_condition: ReadyCondition::Listening,
+        }
+    }
+
+    /// Sets the source of the script, which is copied to `/app/index.js`. The script can only
+    /// require the built-in modules of Node.js, as there is no `node_modules` directory.
+    pub fn with_script<S: Into<Vec<u8>>>(self, script: S) -> Self {
+        NodeApp {
+            script: script.into(),
+            ..self
+        }
+    }
+
+    /// Sets the port the script listens on, [`DEFAULT_PORT`] by default.
+    pub fn with_port(self, port: u16) -> Self {
+        NodeApp { port, ..self }
+    }
+
+    /// Sets an env variable of the script.
+    pub fn with_env_var<K: Into<String>, V: Into<String>>(self, key: K, value: V) -> Self {
+        NodeApp {
+            image: self.image.with_env_var(key, value),
+            ..self
+        }
+    }
+
+    /// Considers the script ready once it answers a `GET` request for the given path with `200`.
+    pub fn with_ready_path<P: Into<String>>(self, path: P) -> Self {
+        NodeApp {
+            ready_condition: ReadyCondition::Path(path.into()),
+            ..self
+        }
+    }
+
+    /// Considers the script ready once it prints the given message to stdout, e.g. through
+    /// `console.log` once its server listens.
+    pub fn with_ready_message<M: Into<String>>(self, message: M) -> Self {
+        NodeApp {
+            ready_condition: ReadyCondition::Message(message.into()),
+            ..self
+        }
+    }
+
+    /// Returns the port the script listens on.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns the url of the script, given the host port mapped to its port.
+    pub fn base_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+}
+
+impl Image for NodeApp {
+    type Args = Vec<String>;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        self.image.descriptor()
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        let condition = match &self.ready_condition {
+            // the path a test needs might be another one, but any answer shows the server listens
+            ReadyCondition::Listening => WaitFor::http(
+                HttpWaitStrategy::new(self.port, "/")
+                    .with_expected_status_code(200)
+                    .with_expected_status_code(404),
+            ),
+            ReadyCondition::Path(path) => WaitFor::http(HttpWaitStrategy::new(self.port, path)),
+            ReadyCondition::Message(message) => WaitFor::message_on_stdout(message),
+        };
+
+        vec![condition]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.image.args()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        self.image.volumes()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = self.image.env_vars();
+        env_vars.insert("PORT".to_owned(), self.port.to_string());
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        NodeApp {
+            image: self.image.with_args(arguments),
+            ..self
+        }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        vec![CopyToContainer::new(self.script.clone(), SCRIPT_FILE)]
+    }
+
+    fn expose_ports(&self) -> Vec<u16> {
+        // the image does not declare any port
+        vec![self.port]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_run_script_on_configured_port() {
+        let image = NodeApp::new("20-alpine")
+            .with_script("console.log('hello')")
+            .with_port(8080)
+            .with_env_var("SECRET", "s3cr3t");
+
+        assert_eq!(image.descriptor(), "node:20-alpine");
+        assert_eq!(image.args(), vec!["node", "/app/index.js"]);
+        assert_eq!(image.copy_to_sources()[0].target, "/app/index.js");
+        assert_eq!(image.env_vars().get("PORT"), Some(&"8080".to_owned()));
+        assert_eq!(image.env_vars().get("SECRET"), Some(&"s3cr3t".to_owned()));
+        assert_eq!(image.expose_ports(), vec![8080]);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{
+    core::{
+        wait::{blocking_request, json_post},
+        CopyToContainer, HttpWaitStrategy, WaitFor,
+    },
+    Image,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "openpolicyagent/opa";
+const DEFAULT_TAG: &str = "0.58.0";
+
+/// Port of the REST API.
+pub const PORT: u16 = 8181;
+
+const POLICY_DIR: &str = "/policies";
+
+#[derive(Debug, Default, Clone)]
+pub struct OpenPolicyAgentArgs {
+    policies: bool,
+}
+
+impl IntoIterator for OpenPolicyAgentArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        let mut args = vec![
+            "run".to_owned(),
+            "--server".to_owned(),
+            "--addr".to_owned(),
+            format!("0.0.0.0:{}", PORT),
+        ];
+
+        // OPA refuses to start if a path to load does not exist
+        if self.policies {
+            args.push(POLICY_DIR.to_owned());
+        }
+
+        args.into_iter()
+    }
+}
+
+/// An Open Policy Agent server, e.g. to test authorization decisions against rego policies.
+///
+/// Policies added through [`with_policy`] are loaded on startup and evaluated through the data API,
+/// e.g. through [`query`].
+///
+/// [`with_policy`]: struct.OpenPolicyAgent.html#method.with_policy
+/// [`query`]: struct.OpenPolicyAgent.html#method.query
+#[derive(Debug)]
+pub struct OpenPolicyAgent {
+    tag: String,
+    arguments: OpenPolicyAgentArgs,
+    policies: Vec<(String, Vec<u8>)>,
+}
+
+#[derive(Deserialize)]
+struct QueryResponse {
+    #[serde(default)]
+    result: serde_json::Value,
+}
+
+impl Default for OpenPolicyAgent {
+    fn default() -> Self {
+        OpenPolicyAgent {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: OpenPolicyAgentArgs::default(),
+            policies: Vec::new(),
+        }
+    }
+}
+
+impl Image for OpenPolicyAgent {
+    type Args = OpenPolicyAgentArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(PORT, "/health"))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        OpenPolicyAgent { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        self.policies
+            .iter()
+            .map(|(name, policy)| {
+                CopyToContainer::new(policy.clone(), format!("{}/{}.rego", POLICY_DIR, name))
+            })
+            .collect()
+    }
+}
+
+impl OpenPolicyAgent {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        OpenPolicyAgent {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Loads the given rego source on startup, stored under the given file name.
+    ///
+    /// The name only identifies the file, the rules are addressed in [`query`] through the package
+    /// declared in the source.
+    ///
+    /// [`query`]: struct.OpenPolicyAgent.html#method.query
+    pub fn with_policy<N: Into<String>, P: Into<Vec<u8>>>(mut self, name: N, policy: P) -> Self {
+        self.arguments.policies = true;
+        self.policies.push((name.into(), policy.into()));
+        self
+    }
+
+    /// Returns the url of the REST API, given the host port mapped to [`PORT`].
+    pub fn url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Evaluates the document at the given path of the data API, e.g. `authz/allow` for the rule
+    /// `allow` of the package `authz`, against the given input, given the host port mapped to
+    /// [`PORT`].
+    ///
+    /// Returns `Value::Null` if the document is undefined for the input.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the API cannot be reached or rejects the query.
+    pub fn query(
+        &self,
+        host_port: u16,
+        path: &str,
+        input: &serde_json::Value,
+    ) -> serde_json::Value {
+        let url = format!(
+            "{}/v1/data/{}",
+            self.url(host_port),
+            path.trim_start_matches('/')
+        );
+        let body = serde_json::json!({ "input": input }).to_string();
+
+        let response = blocking_request(move |client| json_post(client, &url, &body))
+            .unwrap_or_else(|e| panic!("failed to query {}: {}", path, e));
+
+        parse_result(&response)
+    }
+}
+
+fn parse_result(body: &str) -> serde_json::Value {
+    serde_json::from_str::<QueryResponse>(body)
+        .unwrap_or_else(|e| panic!("failed to parse query response: {}", e))
+        .result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_load_policies_from_bundle_directory() {
+        let image = OpenPolicyAgent::default().with_policy("authz", "package authz");
+
+        assert_eq!(
+            image.args().into_iter().collect::<Vec<_>>(),
+            vec!["run", "--server", "--addr", "0.0.0.0:8181", "/policies"]
+        );
+        assert_eq!(image.copy_to_sources()[0].target, "/policies/authz.rego");
+    }
+
+    #[test]
+    fn should_return_null_for_undefined_document() {
+        assert_eq!(parse_result("{}"), serde_json::Value::Null);
+        assert_eq!(
+            parse_result(r#"{ "result": true }"#),
+            serde_json::Value::Bool(true)
+        );
+    }
+}
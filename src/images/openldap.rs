@@ -0,0 +1,224 @@
+use crate::{
+    core::{shell_quote, ContainerState, CopyToContainer, ExecCommand, WaitFor},
+    Image,
+};
+use std::{collections::HashMap, time::Duration};
+
+const CONTAINER_IDENTIFIER: &str = "osixia/openldap";
+const DEFAULT_TAG: &str = "1.5.0";
+
+const DEFAULT_ADMIN_PASSWORD: &str = "admin";
+const DEFAULT_ROOT_DN: &str = "dc=example,dc=org";
+
+/// Port of the LDAP protocol.
+pub const LDAP_PORT: u16 = 389;
+/// Port of the LDAP protocol over TLS.
+pub const LDAPS_PORT: u16 = 636;
+
+/// LDIF files in this directory are loaded when the server starts for the first time.
+const BOOTSTRAP_DIR: &str = "/container/service/slapd/assets/config/bootstrap/ldif/custom";
+
+/// How long slapd may take to accept connections once it logged that it is starting.
+const BIND_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default, Clone)]
+pub struct OpenLdapArgs;
+
+impl IntoIterator for OpenLdapArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// An OpenLDAP server.
+///
+/// The entries added through [`with_ldif`] are loaded on startup. If TLS is enabled, the server
+/// uses a self-signed certificate and does not ask clients for certificates.
+///
+/// [`with_ldif`]: struct.OpenLdap.html#method.with_ldif
+#[derive(Debug)]
+pub struct OpenLdap {
+    tag: String,
+    arguments: OpenLdapArgs,
+    admin_password: String,
+    root_dn: String,
+    ldifs: Vec<Vec<u8>>,
+    tls: bool,
+}
+
+impl Default for OpenLdap {
+    fn default() -> Self {
+        OpenLdap {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: OpenLdapArgs {},
+            admin_password: DEFAULT_ADMIN_PASSWORD.to_string(),
+            root_dn: DEFAULT_ROOT_DN.to_string(),
+            ldifs: Vec::new(),
+            tls: false,
+        }
+    }
+}
+
+impl Image for OpenLdap {
+    type Args = OpenLdapArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        // slapd writes its debug output to stderr
+        vec![WaitFor::message_on_stderr("slapd starting")]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+        env_vars.insert(
+            "LDAP_ADMIN_PASSWORD".to_owned(),
+            self.admin_password.clone(),
+        );
+        env_vars.insert("LDAP_BASE_DN".to_owned(), self.root_dn.clone());
+        env_vars.insert("LDAP_DOMAIN".to_owned(), domain(&self.root_dn));
+        env_vars.insert("LDAP_TLS".to_owned(), self.tls.to_string());
+
+        if self.tls {
+            env_vars.insert("LDAP_TLS_VERIFY_CLIENT".to_owned(), "never".to_owned());
+        }
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        OpenLdap { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        self.ldifs
+            .iter()
+            .enumerate()
+            .map(|(index, ldif)| {
+                CopyToContainer::new(
+                    ldif.clone(),
+                    format!("{}/{:02}-seed.ldif", BOOTSTRAP_DIR, index),
+                )
+            })
+            .collect()
+    }
+
+    fn exec_after_start(&self, _: ContainerState) -> Vec<ExecCommand> {
+        // slapd logs that it is starting before it accepts connections
+        vec![ExecCommand::shell_until(
+            format!(
+                "ldapwhoami -x -H ldap://127.0.0.1:{} -D {} -w {} > /dev/null 2>&1",
+                LDAP_PORT,
+                shell_quote(&self.bind_dn()),
+                shell_quote(&self.admin_password)
+            ),
+            Duration::from_millis(100),
+            BIND_TIMEOUT,
+        )]
+    }
+}
+
+impl OpenLdap {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        OpenLdap {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the password of the admin user.
+    pub fn with_admin_password<P: Into<String>>(self, password: P) -> Self {
+        OpenLdap {
+            admin_password: password.into(),
+            ..self
+        }
+    }
+
+    /// Sets the base DN of the directory, e.g. `dc=example,dc=org`.
+    pub fn with_root_dn<D: Into<String>>(self, root_dn: D) -> Self {
+        OpenLdap {
+            root_dn: root_dn.into(),
+            ..self
+        }
+    }
+
+    /// Adds LDIF entries that are loaded when the server starts.
+    pub fn with_ldif<L: Into<Vec<u8>>>(mut self, ldif: L) -> Self {
+        self.ldifs.push(ldif.into());
+        self
+    }
+
+    /// Enables `ldaps` on [`LDAPS_PORT`] using a self-signed certificate.
+    pub fn with_tls(self, tls: bool) -> Self {
+        OpenLdap { tls, ..self }
+    }
+
+    /// Returns the url of the server, given the host port mapped to [`LDAP_PORT`].
+    pub fn ldap_url(&self, host_port: u16) -> String {
+        format!("ldap://localhost:{}", host_port)
+    }
+
+    /// Returns the TLS url of the server, given the host port mapped to [`LDAPS_PORT`].
+    pub fn ldaps_url(&self, host_port: u16) -> String {
+        format!("ldaps://localhost:{}", host_port)
+    }
+
+    pub fn root_dn(&self) -> &str {
+        &self.root_dn
+    }
+
+    /// Returns the DN to bind as the admin user.
+    pub fn bind_dn(&self) -> String {
+        format!("cn=admin,{}", self.root_dn)
+    }
+
+    pub fn password(&self) -> &str {
+        &self.admin_password
+    }
+}
+
+/// The image derives the organisation from the domain, which is why both need to match.
+fn domain(root_dn: &str) -> String {
+    root_dn
+        .split(',')
+        .filter_map(|component| {
+            let component = component.trim();
+
+            match component.get(..3) {
+                Some(prefix) if prefix.eq_ignore_ascii_case("dc=") => Some(&component[3..]),
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_derive_domain_from_root_dn() {
+        assert_eq!(domain("dc=example,dc=org"), "example.org");
+        assert_eq!(
+            domain("ou=people, DC=corp,dc=example,dc=com"),
+            "corp.example.com"
+        );
+    }
+}
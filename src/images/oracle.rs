@@ -0,0 +1,150 @@
+use crate::{core::WaitFor, Image};
+use std::{collections::HashMap, time::Duration};
+
+const CONTAINER_IDENTIFIER: &str = "gvenzl/oracle-free";
+const DEFAULT_TAG: &str = "23.3-slim-faststart";
+
+const DEFAULT_PASSWORD: &str = "password";
+const DEFAULT_DATABASE: &str = "FREEPDB1";
+
+/// Port of the database listener.
+pub const PORT: u16 = 1521;
+
+/// Creating an additional pluggable database on first boot takes several minutes.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Default, Clone)]
+pub struct OracleFreeArgs;
+
+impl IntoIterator for OracleFreeArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// An Oracle Database Free instance.
+///
+/// This image is only available with the `oracle` feature because it is several gigabytes in size.
+/// The password configured through [`with_password`] is used for the `SYS`, `SYSTEM` and
+/// `PDBADMIN` users.
+///
+/// [`with_password`]: struct.OracleFree.html#method.with_password
+#[derive(Debug)]
+pub struct OracleFree {
+    tag: String,
+    arguments: OracleFreeArgs,
+    password: String,
+    app_user: Option<(String, String)>,
+    database: Option<String>,
+}
+
+impl Default for OracleFree {
+    fn default() -> Self {
+        OracleFree {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: OracleFreeArgs {},
+            password: DEFAULT_PASSWORD.to_string(),
+            app_user: None,
+            database: None,
+        }
+    }
+}
+
+impl Image for OracleFree {
+    type Args = OracleFreeArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::message_on_stdout("DATABASE IS READY TO USE!")]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("ORACLE_PASSWORD".to_owned(), self.password.clone());
+
+        if let Some((user, password)) = &self.app_user {
+            env_vars.insert("APP_USER".to_owned(), user.clone());
+            env_vars.insert("APP_USER_PASSWORD".to_owned(), password.clone());
+        }
+        if let Some(database) = &self.database {
+            env_vars.insert("ORACLE_DATABASE".to_owned(), database.clone());
+        }
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        OracleFree { arguments, ..self }
+    }
+
+    fn startup_timeout(&self) -> Option<Duration> {
+        Some(STARTUP_TIMEOUT)
+    }
+}
+
+impl OracleFree {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        OracleFree {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the password of the `SYS`, `SYSTEM` and `PDBADMIN` users.
+    pub fn with_password<P: Into<String>>(self, password: P) -> Self {
+        OracleFree {
+            password: password.into(),
+            ..self
+        }
+    }
+
+    /// Creates a regular user, which owns its own schema, in every pluggable database.
+    pub fn with_app_user<U: Into<String>, P: Into<String>>(self, user: U, password: P) -> Self {
+        OracleFree {
+            app_user: Some((user.into(), password.into())),
+            ..self
+        }
+    }
+
+    /// Creates an additional pluggable database with the given name.
+    pub fn with_database<N: Into<String>>(self, name: N) -> Self {
+        OracleFree {
+            database: Some(name.into()),
+            ..self
+        }
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    /// Returns the EZConnect descriptor of the database, given the host port mapped to [`PORT`].
+    ///
+    /// Points to the database configured through [`with_database`], `FREEPDB1` otherwise.
+    ///
+    /// [`with_database`]: struct.OracleFree.html#method.with_database
+    pub fn connect_descriptor(&self, host_port: u16) -> String {
+        format!(
+            "localhost:{}/{}",
+            host_port,
+            self.database.as_deref().unwrap_or(DEFAULT_DATABASE)
+        )
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{
+    core::{Container, CopyToContainer, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "otel/opentelemetry-collector-contrib";
+const DEFAULT_TAG: &str = "0.91.0";
+
+/// Port of the OTLP receiver over gRPC.
+pub const OTLP_GRPC_PORT: u16 = 4317;
+/// Port of the OTLP receiver over HTTP.
+pub const OTLP_HTTP_PORT: u16 = 4318;
+/// Port of the health check extension in the default config.
+pub const HEALTH_CHECK_PORT: u16 = 13133;
+
+const CONFIG_FILE: &str = "/etc/otelcol-contrib/config.yaml";
+
+/// The file the default config exports all telemetry to, one JSON encoded OTLP request per line.
+pub const EXPORT_FILE: &str = "/otel/exported.jsonl";
+
+/// Receives traces, metrics and logs through OTLP and writes them to [`EXPORT_FILE`].
+const DEFAULT_CONFIG: &str = "extensions:
+  health_check:
+    endpoint: 0.0.0.0:13133
+receivers:
+  otlp:
+    protocols:
+      grpc:
+        endpoint: 0.0.0.0:4317
+      http:
+        endpoint: 0.0.0.0:4318
+exporters:
+  file:
+    path: /otel/exported.jsonl
+    flush_interval: 100ms
+service:
+  extensions: [health_check]
+  pipelines:
+    traces:
+      receivers: [otlp]
+      exporters: [file]
+    metrics:
+      receivers: [otlp]
+      exporters: [file]
+    logs:
+      receivers: [otlp]
+      exporters: [file]
+";
+
+#[derive(Debug, Default, Clone)]
+pub struct OtelCollectorArgs;
+
+impl IntoIterator for OtelCollectorArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// An OpenTelemetry Collector, which by default writes all telemetry it receives through OTLP to
+/// [`EXPORT_FILE`], so tests can assert on what a service exported through
+/// [`exported_payloads`].
+///
+/// A config passed through [`with_config`] needs to keep the `health_check` extension on
+/// [`HEALTH_CHECK_PORT`], as the collector is ready once it reports to be healthy.
+///
+/// [`exported_payloads`]: ../../core/struct.Container.html#method.exported_payloads
+/// [`with_config`]: struct.OtelCollector.html#method.with_config
+#[derive(Debug)]
+pub struct OtelCollector {
+    tag: String,
+    arguments: OtelCollectorArgs,
+    config: Vec<u8>,
+}
+
+impl Default for OtelCollector {
+    fn default() -> Self {
+        OtelCollector {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: OtelCollectorArgs,
+            config: DEFAULT_CONFIG.into(),
+        }
+    }
+}
+
+impl Image for OtelCollector {
+    type Args = OtelCollectorArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(HEALTH_CHECK_PORT, "/"))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        OtelCollector { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        vec![
+            CopyToContainer::new(self.config.clone(), CONFIG_FILE),
+            // the image has no writable directory for the collector, which does not run as root
+            CopyToContainer::new(Vec::new(), EXPORT_FILE).with_mode(0o666),
+        ]
+    }
+
+    fn expose_ports(&self) -> Vec<u16> {
+        vec![OTLP_GRPC_PORT, OTLP_HTTP_PORT, HEALTH_CHECK_PORT]
+    }
+}
+
+impl OtelCollector {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        OtelCollector {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Replaces the default config, e.g. to add a processor to a pipeline. The file exporter can
+    /// write to [`EXPORT_FILE`], which exists and is writable for the collector.
+    pub fn with_config<C: Into<Vec<u8>>>(self, config: C) -> Self {
+        OtelCollector {
+            config: config.into(),
+            ..self
+        }
+    }
+
+    /// Returns the endpoint of the OTLP receiver over gRPC, given the host port mapped to
+    /// [`OTLP_GRPC_PORT`], e.g. for `OTEL_EXPORTER_OTLP_ENDPOINT`.
+    pub fn otlp_grpc_endpoint(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Returns the endpoint of the OTLP receiver over HTTP, given the host port mapped to
+    /// [`OTLP_HTTP_PORT`]. Signals are sent to `/v1/traces`, `/v1/metrics` and `/v1/logs` below
+    /// it.
+    pub fn otlp_http_endpoint(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+}
+
+impl<'d> Container<'d, OtelCollector> {
+    /// Returns the OTLP requests written to [`EXPORT_FILE`] so far, e.g. to assert on
+    /// `["resourceSpans"][0]["scopeSpans"][0]["spans"]`. The file exporter flushes every 100ms
+    /// in the default config, so tests poll until the expected payload shows up.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the file cannot be read from the container or contains a line that
+    /// is not JSON.
+    ///
+    /// [`EXPORT_FILE`]: ../images/otel_collector/constant.EXPORT_FILE.html
+    pub fn exported_payloads(&self) -> Vec<serde_json::Value> {
+        parse_payloads(&self.copy_file_from(EXPORT_FILE))
+    }
+}
+
+fn parse_payloads(exported: &[u8]) -> Vec<serde_json::Value> {
+    String::from_utf8_lossy(exported)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .unwrap_or_else(|e| panic!("exported payload is not JSON: {}: {}", e, line))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_one_payload_per_line() {
+        let payloads = parse_payloads(b"{\"resourceSpans\":[]}\n{\"resourceMetrics\":[]}\n");
+
+        assert_eq!(
+            payloads,
+            vec![
+                serde_json::json!({ "resourceSpans": [] }),
+                serde_json::json!({ "resourceMetrics": [] })
+            ]
+        );
+    }
+}
@@ -1,38 +1,74 @@
-use crate::{core::WaitFor, Image};
+use crate::{
+    core::{Container, ContainerAsync, CopyToContainer, LogSource, WaitFor},
+    Image,
+};
 use std::collections::HashMap;
 
 const CONTAINER_IDENTIFIER: &str = "parity/parity";
 const DEFAULT_TAG: &str = "v2.5.0";
 
+/// Repository of the releases since Parity Ethereum was renamed to OpenEthereum.
+const OPENETHEREUM_IDENTIFIER: &str = "openethereum/openethereum";
+
+/// Port of the JSON-RPC API over HTTP.
+pub const HTTP_PORT: u16 = 8545;
+/// Port of the JSON-RPC API over WebSocket.
+pub const WS_PORT: u16 = 8546;
+
+const READY_MESSAGE: &str = "Public node URL:";
+
+const CHAIN_SPEC_FILE: &str = "/chain.json";
+const PASSWORD_DIR: &str = "/passwords";
+
 #[derive(Debug)]
 pub struct ParityEthereum {
     arguments: ParityEthereumArgs,
+    identifier: &'static str,
     tag: String,
+    chain_spec: Option<Vec<u8>>,
+    unlocked_accounts: Vec<(String, Vec<u8>)>,
 }
 
 #[derive(Default, Debug, Clone)]
-pub struct ParityEthereumArgs {}
+pub struct ParityEthereumArgs {
+    chain_spec: bool,
+    unlocked_accounts: Vec<String>,
+}
 
 impl IntoIterator for ParityEthereumArgs {
     type Item = String;
     type IntoIter = ::std::vec::IntoIter<String>;
 
     fn into_iter(self) -> Self::IntoIter {
-        vec![
+        let mut args = vec![
             "--config=dev".to_string(),
             "--jsonrpc-apis=all".to_string(),
             "--unsafe-expose".to_string(),
             "--tracing=on".to_string(),
-        ]
-        .into_iter()
+        ];
+
+        if self.chain_spec {
+            args.push(format!("--chain={}", CHAIN_SPEC_FILE));
+        }
+        if !self.unlocked_accounts.is_empty() {
+            args.push(format!("--unlock={}", self.unlocked_accounts.join(",")));
+        }
+        for address in &self.unlocked_accounts {
+            args.push(format!("--password={}", password_file(address)));
+        }
+
+        args.into_iter()
     }
 }
 
 impl Default for ParityEthereum {
     fn default() -> Self {
         ParityEthereum {
-            arguments: ParityEthereumArgs {},
+            arguments: ParityEthereumArgs::default(),
+            identifier: CONTAINER_IDENTIFIER,
             tag: DEFAULT_TAG.to_string(),
+            chain_spec: None,
+            unlocked_accounts: Vec::new(),
         }
     }
 }
@@ -44,11 +80,14 @@ impl Image for ParityEthereum {
     type EntryPoint = std::convert::Infallible;
 
     fn descriptor(&self) -> String {
-        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+        format!("{}:{}", self.identifier, &self.tag)
     }
 
     fn ready_conditions(&self) -> Vec<WaitFor> {
-        vec![WaitFor::message_on_stderr("Public node URL:")]
+        match self.log_source() {
+            LogSource::StdOut => vec![WaitFor::message_on_stdout(READY_MESSAGE)],
+            LogSource::StdErr => vec![WaitFor::message_on_stderr(READY_MESSAGE)],
+        }
     }
 
     fn args(&self) -> Self::Args {
@@ -66,6 +105,22 @@ impl Image for ParityEthereum {
     fn with_args(self, arguments: Self::Args) -> Self {
         Self { arguments, ..self }
     }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        let chain_spec = self
+            .chain_spec
+            .iter()
+            .map(|spec| CopyToContainer::new(spec.clone(), CHAIN_SPEC_FILE));
+        let passwords = self.unlocked_accounts.iter().map(|(address, password)| {
+            CopyToContainer::new(password.clone(), password_file(address))
+        });
+
+        chain_spec.chain(passwords).collect()
+    }
+
+    fn expose_ports(&self) -> Vec<u16> {
+        vec![HTTP_PORT, WS_PORT]
+    }
 }
 
 impl ParityEthereum {
@@ -75,4 +130,183 @@ impl ParityEthereum {
             ..self
         }
     }
+
+    /// Runs the given release of OpenEthereum, e.g. `v3.3.5`, instead of Parity Ethereum.
+    pub fn with_openethereum_tag(self, tag_str: &str) -> Self {
+        ParityEthereum {
+            identifier: OPENETHEREUM_IDENTIFIER,
+            ..self.with_tag(tag_str)
+        }
+    }
+
+    /// Runs the chain described by the given chain spec JSON instead of the built-in dev chain.
+    pub fn with_chain_spec<S: Into<Vec<u8>>>(self, chain_spec: S) -> Self {
+        ParityEthereum {
+            arguments: ParityEthereumArgs {
+                chain_spec: true,
+                ..self.arguments
+            },
+            chain_spec: Some(chain_spec.into()),
+            ..self
+        }
+    }
+
+    /// Unlocks the account with the given address for the lifetime of the node, using the given
+    /// contents of a password file.
+    ///
+    /// The key of the account has to be part of the keystore of the chain, e.g. the pre-funded
+    /// `0x00a329c0648769a73afac7f9381e08fb43dbea72` of the dev chain with an empty password.
+    pub fn with_unlocked_account<A: Into<String>, P: Into<Vec<u8>>>(
+        mut self,
+        address: A,
+        password_file: P,
+    ) -> Self {
+        let address = address.into();
+
+        self.arguments.unlocked_accounts.push(address.clone());
+        self.unlocked_accounts.push((address, password_file.into()));
+        self
+    }
+
+    /// Returns the url of the JSON-RPC API over HTTP, given the host port mapped to [`HTTP_PORT`].
+    pub fn http_rpc_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Returns the url of the JSON-RPC API over WebSocket, given the host port mapped to
+    /// [`WS_PORT`].
+    pub fn ws_url(&self, host_port: u16) -> String {
+        format!("ws://localhost:{}", host_port)
+    }
+
+    /// OpenEthereum logs to stdout, whereas Parity Ethereum logs to stderr.
+    fn log_source(&self) -> LogSource {
+        if self.identifier == OPENETHEREUM_IDENTIFIER {
+            LogSource::StdOut
+        } else {
+            LogSource::StdErr
+        }
+    }
+}
+
+impl<'d> Container<'d, ParityEthereum> {
+    /// Returns the enode url the node logged on startup, which other nodes use to connect to it
+    /// from within the docker network.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the url cannot be read from the logs.
+    pub fn enode_url(&self) -> String {
+        let lines = match self.image().log_source() {
+            LogSource::StdOut => self.stdout_lines_until(READY_MESSAGE),
+            LogSource::StdErr => self.stderr_lines_until(READY_MESSAGE),
+        };
+
+        parse_enode_url(&lines)
+    }
+}
+
+impl<'d> ContainerAsync<'d, ParityEthereum> {
+    /// Returns the enode url the node logged on startup, which other nodes use to connect to it
+    /// from within the docker network.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the url cannot be read from the logs.
+    pub async fn enode_url(&self) -> String {
+        let lines = match self.image().log_source() {
+            LogSource::StdOut => self.stdout_lines_until(READY_MESSAGE).await,
+            LogSource::StdErr => self.stderr_lines_until(READY_MESSAGE).await,
+        };
+
+        parse_enode_url(&lines)
+    }
+}
+
+/// Extracts the url from a line like `Public node URL: enode://5c3f...@172.17.0.2:30303`, which may
+/// be wrapped in terminal color codes.
+fn parse_enode_url(lines: &[String]) -> String {
+    lines
+        .iter()
+        .filter(|line| line.contains(READY_MESSAGE))
+        .flat_map(|line| line.split_whitespace())
+        .find(|word| word.contains("enode://"))
+        .map(|word| {
+            let start = word.find("enode://").unwrap_or(0);
+            let url = &word[start..];
+
+            url.split('\u{1b}').next().unwrap_or(url).to_owned()
+        })
+        .unwrap_or_else(|| panic!("no enode url in logs: {:?}", lines))
+}
+
+fn password_file(address: &str) -> String {
+    format!("{}/{}.txt", PASSWORD_DIR, address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_pass_chain_spec_and_unlocked_accounts_to_node() {
+        let image = ParityEthereum::default()
+            .with_chain_spec(r#"{"name":"test"}"#)
+            .with_unlocked_account("0x00a329c0648769a73afac7f9381e08fb43dbea72", "");
+
+        assert_eq!(
+            image.args().into_iter().collect::<Vec<_>>(),
+            vec![
+                "--config=dev",
+                "--jsonrpc-apis=all",
+                "--unsafe-expose",
+                "--tracing=on",
+                "--chain=/chain.json",
+                "--unlock=0x00a329c0648769a73afac7f9381e08fb43dbea72",
+                "--password=/passwords/0x00a329c0648769a73afac7f9381e08fb43dbea72.txt",
+            ]
+        );
+        assert_eq!(
+            image
+                .copy_to_sources()
+                .into_iter()
+                .map(|file| file.target)
+                .collect::<Vec<_>>(),
+            vec![
+                "/chain.json",
+                "/passwords/0x00a329c0648769a73afac7f9381e08fb43dbea72.txt",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_wait_for_node_url_on_stream_of_release() {
+        assert_eq!(
+            ParityEthereum::default().ready_conditions(),
+            vec![WaitFor::message_on_stderr(READY_MESSAGE)]
+        );
+        assert_eq!(
+            ParityEthereum::default()
+                .with_openethereum_tag("v3.3.5")
+                .ready_conditions(),
+            vec![WaitFor::message_on_stdout(READY_MESSAGE)]
+        );
+        assert_eq!(
+            ParityEthereum::default()
+                .with_openethereum_tag("v3.3.5")
+                .descriptor(),
+            "openethereum/openethereum:v3.3.5"
+        );
+    }
+
+    #[test]
+    fn should_parse_colored_enode_url() {
+        let lines = vec![
+            "2021-05-04 10:00:00  Starting Parity-Ethereum/v2.5.0-stable".to_owned(),
+            "2021-05-04 10:00:01  Public node URL: \u{1b}[1menode://5c3f@172.17.0.2:30303\u{1b}[0m"
+                .to_owned(),
+        ];
+
+        assert_eq!(parse_enode_url(&lines), "enode://5c3f@172.17.0.2:30303");
+    }
 }
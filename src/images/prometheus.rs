@@ -0,0 +1,255 @@
+use crate::{
+    core::{wait::blocking_request, CopyToContainer, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "prom/prometheus";
+const DEFAULT_TAG: &str = "v2.48.1";
+
+/// Port of the HTTP API and web UI.
+pub const PORT: u16 = 9090;
+
+/// The hostname under which the container reaches the host, e.g. to scrape the test process.
+pub const HOST_GATEWAY: &str = "host.docker.internal";
+
+const CONFIG_FILE: &str = "/etc/prometheus/prometheus.yml";
+
+#[derive(Debug, Default, Clone)]
+pub struct PrometheusArgs;
+
+impl IntoIterator for PrometheusArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A Prometheus server.
+///
+/// Without further configuration, the server only scrapes itself. Use [`with_scrape_target`] to
+/// scrape an exporter started by the test, or [`with_config`] to provide a complete configuration.
+///
+/// [`with_scrape_target`]: struct.Prometheus.html#method.with_scrape_target
+/// [`with_config`]: struct.Prometheus.html#method.with_config
+#[derive(Debug)]
+pub struct Prometheus {
+    tag: String,
+    arguments: PrometheusArgs,
+    config: Option<Vec<u8>>,
+    reach_host: bool,
+}
+
+impl Default for Prometheus {
+    fn default() -> Self {
+        Prometheus {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: PrometheusArgs {},
+            config: None,
+            reach_host: false,
+        }
+    }
+}
+
+/// A single time series of an instant vector returned by [`Prometheus::query`].
+///
+/// [`Prometheus::query`]: struct.Prometheus.html#method.query
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Sample {
+    /// The labels of the time series, including `__name__`.
+    pub metric: HashMap<String, String>,
+    /// The unix timestamp in seconds and the value of the sample.
+    pub value: (f64, String),
+}
+
+impl Sample {
+    /// Returns the value of the sample as a number.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the value is not a number.
+    pub fn as_f64(&self) -> f64 {
+        self.value
+            .1
+            .parse()
+            .unwrap_or_else(|e| panic!("sample value {} is not a number: {}", self.value.1, e))
+    }
+}
+
+#[derive(Deserialize)]
+struct QueryResponse {
+    data: QueryData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryData {
+    result_type: String,
+    result: serde_json::Value,
+}
+
+impl Image for Prometheus {
+    type Args = PrometheusArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(PORT, "/-/ready"))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Prometheus { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        self.config
+            .iter()
+            .map(|config| CopyToContainer::new(config.clone(), CONFIG_FILE))
+            .collect()
+    }
+
+    fn hosts(&self) -> HashMap<String, String> {
+        let mut hosts = HashMap::new();
+
+        if self.reach_host {
+            hosts.insert(HOST_GATEWAY.to_owned(), "host-gateway".to_owned());
+        }
+
+        hosts
+    }
+}
+
+impl Prometheus {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Prometheus {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Replaces the configuration of the server with the given `prometheus.yml`.
+    pub fn with_config<C: Into<Vec<u8>>>(self, config: C) -> Self {
+        Prometheus {
+            config: Some(config.into()),
+            ..self
+        }
+    }
+
+    /// Configures the server to scrape `/metrics` of the given target every second.
+    ///
+    /// Pass [`HOST_GATEWAY`] as `host` to scrape a server started by the test itself, which needs
+    /// to listen on all interfaces for the container to reach it.
+    pub fn with_scrape_target(self, host: &str, port: u16) -> Self {
+        Prometheus {
+            config: Some(scrape_config(host, port).into_bytes()),
+            reach_host: host == HOST_GATEWAY,
+            ..self
+        }
+    }
+
+    /// Returns the url of the HTTP API, given the host port mapped to [`PORT`].
+    pub fn url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Evaluates the given expression at the current time, given the host port mapped to [`PORT`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the API cannot be reached or the expression does not evaluate to an
+    /// instant vector.
+    pub fn query(&self, host_port: u16, expr: &str) -> Vec<Sample> {
+        let url = format!("{}/api/v1/query", self.url(host_port));
+        let expr = expr.to_owned();
+
+        let body = blocking_request(move |client| client.get(&url).query(&[("query", expr)]))
+            .unwrap_or_else(|e| panic!("failed to query Prometheus: {}", e));
+
+        parse_vector(&body)
+    }
+}
+
+fn scrape_config(host: &str, port: u16) -> String {
+    format!(
+        "global:\n  scrape_interval: 1s\nscrape_configs:\n  - job_name: testcontainers\n    static_configs:\n      - targets: ['{}:{}']\n",
+        host, port
+    )
+}
+
+fn parse_vector(body: &str) -> Vec<Sample> {
+    let data = serde_json::from_str::<QueryResponse>(body)
+        .unwrap_or_else(|e| panic!("failed to parse query response: {}", e))
+        .data;
+
+    assert_eq!(
+        data.result_type, "vector",
+        "expression must evaluate to an instant vector"
+    );
+
+    serde_json::from_value(data.result)
+        .unwrap_or_else(|e| panic!("failed to parse query result: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_instant_vector() {
+        let samples = parse_vector(
+            r#"{
+                "status": "success",
+                "data": {
+                    "resultType": "vector",
+                    "result": [
+                        {
+                            "metric": { "__name__": "up", "job": "testcontainers" },
+                            "value": [1700000000.123, "1"]
+                        }
+                    ]
+                }
+            }"#,
+        );
+
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].metric["job"], "testcontainers");
+        assert_eq!(samples[0].as_f64(), 1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "instant vector")]
+    fn should_reject_scalar_result() {
+        parse_vector(
+            r#"{ "status": "success", "data": { "resultType": "scalar", "result": [1700000000.123, "1"] } }"#,
+        );
+    }
+
+    #[test]
+    fn should_only_map_host_gateway_when_scraping_the_host() {
+        let local = Prometheus::default().with_scrape_target("exporter", 8080);
+        let host = Prometheus::default().with_scrape_target(HOST_GATEWAY, 8080);
+
+        assert!(local.hosts().is_empty());
+        assert_eq!(host.hosts()[HOST_GATEWAY], "host-gateway");
+    }
+}
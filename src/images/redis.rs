@@ -1,9 +1,15 @@
-use crate::{core::WaitFor, Image};
+use crate::{
+    core::{ConnectionString, WaitFor},
+    Image,
+};
 use std::collections::HashMap;
 
 const CONTAINER_IDENTIFIER: &str = "redis";
 const DEFAULT_TAG: &str = "5.0";
 
+/// Port of the Redis protocol.
+pub const PORT: u16 = 6379;
+
 #[derive(Debug, Default, Clone)]
 pub struct RedisArgs;
 
@@ -70,3 +76,13 @@ impl Redis {
         }
     }
 }
+
+impl ConnectionString for Redis {
+    fn connection_port(&self) -> u16 {
+        PORT
+    }
+
+    fn connection_string(&self, host_port: u16) -> String {
+        format!("redis://localhost:{}", host_port)
+    }
+}
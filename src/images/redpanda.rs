@@ -0,0 +1,334 @@
+use crate::{
+    core::{shell_quote, ContainerState, ExecCommand, WaitFor},
+    Image,
+};
+use std::{collections::HashMap, time::Duration};
+
+const CONTAINER_IDENTIFIER: &str = "redpandadata/redpanda";
+const DEFAULT_TAG: &str = "v23.2.17";
+
+/// Port of the Kafka API for clients on the host.
+pub const KAFKA_PORT: u16 = 9092;
+/// Port of the admin API.
+pub const ADMIN_PORT: u16 = 9644;
+/// Port of the schema registry, see [`Redpanda::with_schema_registry`].
+///
+/// [`Redpanda::with_schema_registry`]: struct.Redpanda.html#method.with_schema_registry
+pub const SCHEMA_REGISTRY_PORT: u16 = 8081;
+
+/// Port of the Kafka API for other containers on the docker network, see
+/// [`Redpanda::with_network_alias`].
+///
+/// [`Redpanda::with_network_alias`]: struct.Redpanda.html#method.with_network_alias
+pub const NETWORK_KAFKA_PORT: u16 = 9093;
+
+/// Port of the Kafka API for clients inside the container, e.g. the schema registry.
+const INTERNAL_KAFKA_PORT: u16 = 29092;
+
+const START_SCRIPT: &str = "/tmp/testcontainers_start.sh";
+
+/// How long the container waits for the start script, and the start script for the broker to
+/// become ready.
+const START_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Default, Clone)]
+pub struct RedpandaArgs;
+
+impl IntoIterator for RedpandaArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        // the broker is started once the host port to advertise is known, see `exec_after_start`
+        vec![
+            "-c".to_owned(),
+            format!(
+                "timeout {} sh -c 'until [ -f {script} ]; do sleep 0.1; done' && exec sh {script}",
+                START_TIMEOUT.as_secs(),
+                script = START_SCRIPT
+            ),
+        ]
+        .into_iter()
+    }
+}
+
+/// A single Redpanda broker, which speaks the Kafka protocol without needing ZooKeeper.
+///
+/// Kafka clients connect to the addresses the broker advertises for the listener they
+/// bootstrapped from, which has to be the host port mapped to [`KAFKA_PORT`]. As the host port is
+/// only known once the container runs, the broker is started after the container through
+/// [`exec_after_start`], hence the container is only ready once these commands finished.
+///
+/// [`exec_after_start`]: ../../core/trait.Image.html#method.exec_after_start
+#[derive(Debug)]
+pub struct Redpanda {
+    tag: String,
+    arguments: RedpandaArgs,
+    node_config: Vec<(String, String)>,
+    schema_registry: bool,
+    network_alias: Option<String>,
+}
+
+impl Default for Redpanda {
+    fn default() -> Self {
+        Redpanda {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: RedpandaArgs,
+            node_config: Vec::new(),
+            schema_registry: false,
+            network_alias: None,
+        }
+    }
+}
+
+impl Image for Redpanda {
+    type Args = RedpandaArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Redpanda { arguments, ..self }
+    }
+
+    fn entrypoint(&self) -> Option<String> {
+        Some("sh".to_owned())
+    }
+
+    fn expose_ports(&self) -> Vec<u16> {
+        let mut ports = vec![KAFKA_PORT, ADMIN_PORT];
+
+        if self.schema_registry {
+            ports.push(SCHEMA_REGISTRY_PORT);
+        }
+
+        ports
+    }
+
+    fn exec_after_start(&self, cs: ContainerState) -> Vec<ExecCommand> {
+        let mut commands = self
+            .node_config
+            .iter()
+            .map(|(key, value)| {
+                ExecCommand::new(vec![
+                    "rpk",
+                    "redpanda",
+                    "config",
+                    "set",
+                    key.as_str(),
+                    value.as_str(),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        if self.schema_registry {
+            // the schema registry connects to the first broker, which must not advertise the host
+            commands.push(ExecCommand::new(vec![
+                "rpk".to_owned(),
+                "redpanda".to_owned(),
+                "config".to_owned(),
+                "set".to_owned(),
+                "schema_registry_client.brokers".to_owned(),
+                format!(
+                    r#"[{{"address":"127.0.0.1","port":{}}}]"#,
+                    INTERNAL_KAFKA_PORT
+                ),
+            ]));
+        }
+
+        commands.push(ExecCommand::shell(format!(
+            "printf '%s\\n' {} > {script}.tmp && mv {script}.tmp {script}",
+            shell_quote(&self.start_command(cs.host_port(KAFKA_PORT))),
+            script = START_SCRIPT
+        )));
+        commands.push(ExecCommand::shell_until(
+            format!(
+                "curl -sf http://127.0.0.1:{}/v1/status/ready > /dev/null",
+                ADMIN_PORT
+            ),
+            Duration::from_millis(100),
+            START_TIMEOUT,
+        ));
+
+        commands
+    }
+}
+
+impl Redpanda {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Redpanda {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the given property of the node configuration `redpanda.yaml` before the broker
+    /// starts, e.g. `redpanda.auto_create_topics_enabled` to `false`.
+    pub fn with_node_config<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.node_config.push((key.into(), value.into()));
+        self
+    }
+
+    /// Serves the schema registry on [`SCHEMA_REGISTRY_PORT`].
+    pub fn with_schema_registry(self) -> Self {
+        Redpanda {
+            schema_registry: true,
+            ..self
+        }
+    }
+
+    /// Serves the Kafka API to other containers on [`NETWORK_KAFKA_PORT`], advertising the given
+    /// host name, which the container has to be started under through [`RunArgs::with_name`] on
+    /// the same docker network.
+    ///
+    /// [`RunArgs::with_name`]: ../../core/struct.RunArgs.html#method.with_name
+    pub fn with_network_alias<A: Into<String>>(self, alias: A) -> Self {
+        Redpanda {
+            network_alias: Some(alias.into()),
+            ..self
+        }
+    }
+
+    /// Returns the `bootstrap.servers` of Kafka clients in other containers on the docker
+    /// network, if configured through [`with_network_alias`].
+    ///
+    /// [`with_network_alias`]: struct.Redpanda.html#method.with_network_alias
+    pub fn network_bootstrap_servers(&self) -> Option<String> {
+        self.network_alias
+            .as_ref()
+            .map(|alias| format!("{}:{}", alias, NETWORK_KAFKA_PORT))
+    }
+
+    /// Returns the `bootstrap.servers` of Kafka clients, given the host port mapped to
+    /// [`KAFKA_PORT`].
+    pub fn bootstrap_servers(&self, host_port: u16) -> String {
+        format!("localhost:{}", host_port)
+    }
+
+    /// Returns the url of the schema registry, given the host port mapped to
+    /// [`SCHEMA_REGISTRY_PORT`].
+    pub fn schema_registry_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    fn start_command(&self, kafka_host_port: u16) -> String {
+        let mut addrs = vec![
+            format!("internal://0.0.0.0:{}", INTERNAL_KAFKA_PORT),
+            format!("external://0.0.0.0:{}", KAFKA_PORT),
+        ];
+        let mut advertised_addrs = vec![
+            format!("internal://127.0.0.1:{}", INTERNAL_KAFKA_PORT),
+            format!("external://localhost:{}", kafka_host_port),
+        ];
+
+        if let Some(alias) = &self.network_alias {
+            addrs.push(format!("network://0.0.0.0:{}", NETWORK_KAFKA_PORT));
+            advertised_addrs.push(format!("network://{}:{}", alias, NETWORK_KAFKA_PORT));
+        }
+
+        let mut command = format!(
+            "exec rpk redpanda start --mode dev-container --smp 1 --kafka-addr {} --advertise-kafka-addr {}",
+            shell_quote(&addrs.join(",")),
+            shell_quote(&advertised_addrs.join(","))
+        );
+
+        if self.schema_registry {
+            command.push_str(&format!(
+                " --schema-registry-addr 0.0.0.0:{}",
+                SCHEMA_REGISTRY_PORT
+            ));
+        }
+
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ports::Ports;
+
+    #[test]
+    fn should_advertise_host_port_to_kafka_clients() {
+        let binding = vec![("HostPort".to_owned(), "32768".to_owned())]
+            .into_iter()
+            .collect();
+        let ports = vec![(format!("{}/tcp", KAFKA_PORT), Some(vec![binding]))]
+            .into_iter()
+            .collect();
+
+        let commands = Redpanda::default()
+            .with_node_config("redpanda.auto_create_topics_enabled", "true")
+            .exec_after_start(ContainerState::new(Ports::new(ports)));
+
+        assert_eq!(commands.len(), 3);
+        assert_eq!(
+            commands[0].cmd,
+            vec![
+                "rpk",
+                "redpanda",
+                "config",
+                "set",
+                "redpanda.auto_create_topics_enabled",
+                "true"
+            ]
+        );
+        assert!(commands[1].cmd[2].contains("external://localhost:32768"));
+        assert!(!commands[1].cmd[2].contains("--schema-registry-addr"));
+        assert!(!commands[1].cmd[2].contains("network://"));
+    }
+
+    #[test]
+    fn should_advertise_network_alias_to_other_containers() {
+        let image = Redpanda::default().with_network_alias("redpanda");
+
+        assert!(image
+            .start_command(32768)
+            .contains("network://redpanda:9093"));
+        assert_eq!(
+            image.network_bootstrap_servers(),
+            Some("redpanda:9093".to_owned())
+        );
+    }
+
+    #[test]
+    fn should_quote_network_alias_in_start_script() {
+        let image = Redpanda::default().with_network_alias("it's; reboot");
+
+        let start_command = image.start_command(32768);
+        assert!(start_command.ends_with(r"network://it'\''s; reboot:9093'"));
+
+        let binding = vec![("HostPort".to_owned(), "32768".to_owned())]
+            .into_iter()
+            .collect();
+        let ports = vec![(format!("{}/tcp", KAFKA_PORT), Some(vec![binding]))]
+            .into_iter()
+            .collect();
+        let commands = image.exec_after_start(ContainerState::new(Ports::new(ports)));
+
+        assert!(commands[0].cmd[2].starts_with(&format!(
+            r"printf '%s\n' {} > ",
+            shell_quote(&start_command)
+        )));
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{
+    core::{CopyToContainer, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "registry";
+const DEFAULT_TAG: &str = "2.8.3";
+
+/// Port of the registry API.
+pub const PORT: u16 = 5000;
+
+const HTPASSWD_FILE: &str = "/auth/htpasswd";
+
+/// The registry checks the password on every request, a low cost keeps them fast.
+#[cfg(feature = "bcrypt")]
+const BCRYPT_COST: u32 = 4;
+
+#[derive(Debug, Default, Clone)]
+pub struct DockerRegistryArgs;
+
+impl IntoIterator for DockerRegistryArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A Docker registry implementing the distribution API, e.g. to test pushing and pulling images.
+///
+/// The docker daemon only pushes to registries served over plain HTTP if they are configured as
+/// `insecure-registries`, which `localhost` is by default. Hence `docker push` works for
+/// references like `localhost:<host port>/<repository>`, but is rejected with `server gave HTTP
+/// response to HTTPS client` if the registry is addressed through any other host name, e.g. from
+/// within another container.
+///
+/// Authentication is configured through an htpasswd file passed to [`with_htpasswd_file`], or
+/// through [`with_htpasswd`] which hashes the password itself and is available behind the
+/// `bcrypt` feature.
+///
+/// [`with_htpasswd_file`]: struct.DockerRegistry.html#method.with_htpasswd_file
+/// [`with_htpasswd`]: struct.DockerRegistry.html#method.with_htpasswd
+#[derive(Debug)]
+pub struct DockerRegistry {
+    tag: String,
+    arguments: DockerRegistryArgs,
+    htpasswd: Option<Vec<u8>>,
+    credentials: Option<(String, String)>,
+    delete_enabled: bool,
+}
+
+impl Default for DockerRegistry {
+    fn default() -> Self {
+        DockerRegistry {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: DockerRegistryArgs,
+            htpasswd: None,
+            credentials: None,
+            delete_enabled: false,
+        }
+    }
+}
+
+impl Image for DockerRegistry {
+    type Args = DockerRegistryArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        // the API root asks for credentials if authentication is enabled
+        vec![WaitFor::http(
+            HttpWaitStrategy::new(PORT, "/v2/")
+                .with_expected_status_code(200)
+                .with_expected_status_code(401),
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+
+        if self.htpasswd.is_some() {
+            env_vars.insert("REGISTRY_AUTH".to_owned(), "htpasswd".to_owned());
+            env_vars.insert(
+                "REGISTRY_AUTH_HTPASSWD_REALM".to_owned(),
+                "Registry Realm".to_owned(),
+            );
+            env_vars.insert(
+                "REGISTRY_AUTH_HTPASSWD_PATH".to_owned(),
+                HTPASSWD_FILE.to_owned(),
+            );
+        }
+        if self.delete_enabled {
+            env_vars.insert(
+                "REGISTRY_STORAGE_DELETE_ENABLED".to_owned(),
+                "true".to_owned(),
+            );
+        }
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        DockerRegistry { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        self.htpasswd
+            .iter()
+            .map(|htpasswd| CopyToContainer::new(htpasswd.clone(), HTPASSWD_FILE))
+            .collect()
+    }
+}
+
+impl DockerRegistry {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        DockerRegistry {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Requires clients to authenticate through HTTP basic authentication with the given
+    /// credentials, e.g. through `docker login`.
+    #[cfg(feature = "bcrypt")]
+    pub fn with_htpasswd<U: Into<String>, P: Into<String>>(self, username: U, password: P) -> Self {
+        let username = username.into();
+        let password = password.into();
+
+        DockerRegistry {
+            htpasswd: Some(htpasswd(&username, &password)),
+            credentials: Some((username, password)),
+            ..self
+        }
+    }
+
+    /// Requires clients to authenticate through HTTP basic authentication against the given
+    /// htpasswd file, whose entries need to be hashed with bcrypt, e.g. by `htpasswd -Bbn`.
+    pub fn with_htpasswd_file<H: Into<Vec<u8>>>(self, htpasswd: H) -> Self {
+        DockerRegistry {
+            htpasswd: Some(htpasswd.into()),
+            credentials: None,
+            ..self
+        }
+    }
+
+    /// Allows to delete blobs and manifests, which the registry refuses by default.
+    pub fn with_delete_enabled(self, delete_enabled: bool) -> Self {
+        DockerRegistry {
+            delete_enabled,
+            ..self
+        }
+    }
+
+    /// Returns the credentials configured through [`with_htpasswd`], if any. The credentials of
+    /// an htpasswd file passed to [`with_htpasswd_file`] are not known.
+    ///
+    /// [`with_htpasswd`]: struct.DockerRegistry.html#method.with_htpasswd
+    /// [`with_htpasswd_file`]: struct.DockerRegistry.html#method.with_htpasswd_file
+    pub fn credentials(&self) -> Option<(&str, &str)> {
+        self.credentials
+            .as_ref()
+            .map(|(username, password)| (username.as_str(), password.as_str()))
+    }
+
+    /// Returns the url of the registry API, given the host port mapped to [`PORT`].
+    pub fn registry_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+}
+
+/// Creates an htpasswd file with a single entry, hashed with bcrypt which is the only scheme the
+/// registry supports, and Zot as well.
+#[cfg(feature = "bcrypt")]
+pub(crate) fn htpasswd(username: &str, password: &str) -> Vec<u8> {
+    let hash = bcrypt::hash_with_result(password, BCRYPT_COST)
+        .unwrap_or_else(|e| panic!("failed to hash password of {}: {}", username, e));
+
+    format!(
+        "{}:{}\n",
+        username,
+        hash.format_for_version(bcrypt::Version::TwoY)
+    )
+    .into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "bcrypt")]
+    fn should_configure_htpasswd_authentication() {
+        let image = DockerRegistry::default().with_htpasswd("alice", "secret");
+        let files = image.copy_to_sources();
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].target, "/auth/htpasswd");
+
+        let htpasswd = String::from_utf8(files[0].data.clone()).unwrap();
+        let hash = htpasswd.trim_end().strip_prefix("alice:").unwrap();
+
+        assert!(hash.starts_with("$2y$04$"));
+        assert!(bcrypt::verify("secret", hash).unwrap());
+        assert_eq!(
+            image.env_vars().get("REGISTRY_AUTH_HTPASSWD_PATH"),
+            Some(&"/auth/htpasswd".to_owned())
+        );
+    }
+
+    #[test]
+    fn should_copy_given_htpasswd_file() {
+        let htpasswd = "alice:$2y$04$3Jsqkt.iFKreuNI3jv3iEOwjUNRXhKPlEhqXM3f7lajzuwTEzBl0.\n";
+        let image = DockerRegistry::default().with_htpasswd_file(htpasswd);
+        let files = image.copy_to_sources();
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].data, htpasswd.as_bytes());
+        assert_eq!(image.credentials(), None);
+        assert_eq!(
+            image.env_vars().get("REGISTRY_AUTH"),
+            Some(&"htpasswd".to_owned())
+        );
+    }
+}
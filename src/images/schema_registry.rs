@@ -0,0 +1,183 @@
+use crate::{
+    core::{
+        wait::{blocking_request, json_post},
+        HttpWaitStrategy, WaitFor,
+    },
+    images::redpanda::Redpanda,
+    Image,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "confluentinc/cp-schema-registry";
+const DEFAULT_TAG: &str = "7.5.3";
+
+/// Port of the REST API.
+pub const PORT: u16 = 8081;
+
+#[derive(Debug, Default, Clone)]
+pub struct SchemaRegistryArgs;
+
+impl IntoIterator for SchemaRegistryArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A Confluent Schema Registry, e.g. to test producers and consumers of Avro records.
+///
+/// The registry stores its schemas in a Kafka topic, hence it needs a broker on the same docker
+/// network, which is configured through [`with_kafka_bootstrap`] or [`with_redpanda`]. The
+/// registry only becomes ready once it reached the broker.
+///
+/// [`with_kafka_bootstrap`]: struct.SchemaRegistry.html#method.with_kafka_bootstrap
+/// [`with_redpanda`]: struct.SchemaRegistry.html#method.with_redpanda
+#[derive(Debug)]
+pub struct SchemaRegistry {
+    tag: String,
+    arguments: SchemaRegistryArgs,
+    bootstrap_servers: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RegisteredSchema {
+    id: u32,
+}
+
+impl Default for SchemaRegistry {
+    fn default() -> Self {
+        SchemaRegistry {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: SchemaRegistryArgs,
+            bootstrap_servers: None,
+        }
+    }
+}
+
+impl Image for SchemaRegistry {
+    type Args = SchemaRegistryArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(PORT, "/subjects"))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+        env_vars.insert(
+            "SCHEMA_REGISTRY_HOST_NAME".to_owned(),
+            "schema-registry".to_owned(),
+        );
+        env_vars.insert(
+            "SCHEMA_REGISTRY_LISTENERS".to_owned(),
+            format!("http://0.0.0.0:{}", PORT),
+        );
+        if let Some(bootstrap_servers) = &self.bootstrap_servers {
+            env_vars.insert(
+                "SCHEMA_REGISTRY_KAFKASTORE_BOOTSTRAP_SERVERS".to_owned(),
+                format!("PLAINTEXT://{}", bootstrap_servers),
+            );
+        }
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        SchemaRegistry { arguments, ..self }
+    }
+}
+
+impl SchemaRegistry {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        SchemaRegistry {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Stores the schemas through the Kafka broker at the given address on the docker network,
+    /// e.g. `kafka:9092`.
+    pub fn with_kafka_bootstrap<B: Into<String>>(self, bootstrap_servers: B) -> Self {
+        SchemaRegistry {
+            bootstrap_servers: Some(bootstrap_servers.into()),
+            ..self
+        }
+    }
+
+    /// Stores the schemas through the given Redpanda broker on the docker network.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the broker does not serve other containers, see
+    /// [`Redpanda::with_network_alias`].
+    ///
+    /// [`Redpanda::with_network_alias`]: ../redpanda/struct.Redpanda.html#method.with_network_alias
+    pub fn with_redpanda(self, redpanda: &Redpanda) -> Self {
+        let bootstrap_servers = redpanda
+            .network_bootstrap_servers()
+            .expect("Redpanda needs a network alias to be reachable from the schema registry");
+
+        self.with_kafka_bootstrap(bootstrap_servers)
+    }
+
+    /// Returns the url of the REST API, given the host port mapped to [`PORT`].
+    pub fn url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Registers the given Avro schema under the given subject and returns its id, given the host
+    /// port mapped to [`PORT`].
+    ///
+    /// Registering a schema the subject already contains returns the id of the existing one.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the registry cannot be reached or rejects the schema, e.g. because
+    /// it is incompatible to the previous version.
+    pub fn register_schema(&self, host_port: u16, subject: &str, avro_schema: &str) -> u32 {
+        let url = format!("{}/subjects/{}/versions", self.url(host_port), subject);
+        let body = serde_json::json!({ "schema": avro_schema }).to_string();
+
+        let response = blocking_request(move |client| json_post(client, &url, &body))
+            .unwrap_or_else(|e| panic!("failed to register schema for {}: {}", subject, e));
+
+        serde_json::from_str::<RegisteredSchema>(&response)
+            .unwrap_or_else(|e| panic!("failed to parse registered schema: {}", e))
+            .id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_store_schemas_through_redpanda_on_network() {
+        let redpanda = Redpanda::default().with_network_alias("redpanda");
+        let image = SchemaRegistry::default().with_redpanda(&redpanda);
+
+        assert_eq!(
+            image
+                .env_vars()
+                .get("SCHEMA_REGISTRY_KAFKASTORE_BOOTSTRAP_SERVERS"),
+            Some(&"PLAINTEXT://redpanda:9093".to_owned())
+        );
+    }
+}
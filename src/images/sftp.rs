@@ -0,0 +1,247 @@
+use crate::{
+    core::{CopyToContainer, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "atmoz/sftp";
+const DEFAULT_TAG: &str = "debian";
+
+const DEFAULT_USER: &str = "testcontainers";
+const DEFAULT_PASSWORD: &str = "testcontainers";
+const DEFAULT_UID: u32 = 1001;
+const DEFAULT_DIR: &str = "upload";
+
+/// Port of the SSH server.
+pub const PORT: u16 = 22;
+
+const HOST_KEY_FILE: &str = "/etc/ssh/ssh_host_ed25519_key";
+
+/// A user that can log into the [`SftpServer`] with a password.
+///
+/// [`SftpServer`]: struct.SftpServer.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct SftpUser {
+    name: String,
+    password: String,
+    uid: u32,
+    dir: String,
+}
+
+impl SftpUser {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    /// Returns the writable directory of the user, relative to its home directory.
+    pub fn dir(&self) -> &str {
+        &self.dir
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SftpArgs {
+    users: Vec<SftpUser>,
+    default_user: bool,
+}
+
+impl Default for SftpArgs {
+    fn default() -> Self {
+        SftpArgs {
+            users: vec![SftpUser {
+                name: DEFAULT_USER.to_owned(),
+                password: DEFAULT_PASSWORD.to_owned(),
+                uid: DEFAULT_UID,
+                dir: DEFAULT_DIR.to_owned(),
+            }],
+            default_user: true,
+        }
+    }
+}
+
+impl IntoIterator for SftpArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        // the group id is left empty, which makes the image create a group of the same name
+        self.users
+            .iter()
+            .map(|user| format!("{}:{}:{}::{}", user.name, user.password, user.uid, user.dir))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+/// An SFTP server whose users are chrooted into their home directory.
+///
+/// Unless users are added through [`with_user`], a user `testcontainers` with the password
+/// `testcontainers` and a writable `upload` directory is created. Since users cannot write to
+/// their home directory itself, files need to be uploaded into the configured directory.
+///
+/// [`with_user`]: struct.SftpServer.html#method.with_user
+#[derive(Debug)]
+pub struct SftpServer {
+    tag: String,
+    arguments: SftpArgs,
+    host_key: Option<Vec<u8>>,
+}
+
+impl Default for SftpServer {
+    fn default() -> Self {
+        SftpServer {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: SftpArgs::default(),
+            host_key: None,
+        }
+    }
+}
+
+impl Image for SftpServer {
+    type Args = SftpArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::message_on_stderr(
+            "Server listening on 0.0.0.0 port 22",
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        SftpServer { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        // sshd ignores host keys that are readable by other users
+        self.host_key
+            .iter()
+            .map(|key| CopyToContainer::new(key.clone(), HOST_KEY_FILE).with_mode(0o600))
+            .collect()
+    }
+}
+
+impl SftpServer {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        SftpServer {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Adds a user that logs in with the given password and can write to `dir`, which is
+    /// relative to its home directory. The first call replaces the default user.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the name, password or directory contain a `:`, which separates the
+    /// fields of a user in the configuration of the image.
+    pub fn with_user<N, P, D>(mut self, name: N, password: P, uid: u32, dir: D) -> Self
+    where
+        N: Into<String>,
+        P: Into<String>,
+        D: Into<String>,
+    {
+        let user = SftpUser {
+            name: name.into(),
+            password: password.into(),
+            uid,
+            dir: dir.into(),
+        };
+
+        assert!(
+            ![&user.name, &user.password, &user.dir]
+                .iter()
+                .any(|field| field.contains(':')),
+            "name, password and directory of SFTP user {} must not contain ':'",
+            user.name
+        );
+
+        if self.arguments.default_user {
+            self.arguments.users.clear();
+            self.arguments.default_user = false;
+        }
+        self.arguments.users.push(user);
+        self
+    }
+
+    /// Uses the given private ed25519 key in OpenSSH format as host key, which keeps the
+    /// fingerprint of the server the same across runs.
+    pub fn with_host_key<K: Into<Vec<u8>>>(self, key: K) -> Self {
+        SftpServer {
+            host_key: Some(key.into()),
+            ..self
+        }
+    }
+
+    /// Returns the address of the server, given the host port mapped to [`PORT`].
+    pub fn sftp_addr(&self, host_port: u16) -> String {
+        format!("localhost:{}", host_port)
+    }
+
+    pub fn users(&self) -> &[SftpUser] {
+        &self.arguments.users
+    }
+
+    /// Returns the user with the given name.
+    pub fn user(&self, name: &str) -> Option<&SftpUser> {
+        self.arguments.users.iter().find(|user| user.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_render_users_into_args() {
+        let sftp = SftpServer::default()
+            .with_user("alice", "secret", 1001, "upload")
+            .with_user("bob", "hunter2", 1002, "inbox");
+
+        assert_eq!(
+            sftp.args().into_iter().collect::<Vec<_>>(),
+            vec!["alice:secret:1001::upload", "bob:hunter2:1002::inbox"]
+        );
+        assert_eq!(sftp.user("bob").map(SftpUser::password), Some("hunter2"));
+    }
+
+    #[test]
+    fn should_create_default_user_without_configured_users() {
+        assert_eq!(
+            SftpServer::default().args().into_iter().collect::<Vec<_>>(),
+            vec!["testcontainers:testcontainers:1001::upload"]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "must not contain ':'")]
+    fn should_reject_password_with_separator() {
+        SftpServer::default().with_user("alice", "se:cret", 1001, "upload");
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{
+    core::{CopyToContainer, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::{collections::HashMap, time::Duration};
+
+const CONTAINER_IDENTIFIER: &str = "solanalabs/solana";
+const DEFAULT_TAG: &str = "v1.18.26";
+
+/// Port of the JSON-RPC API.
+pub const RPC_PORT: u16 = 8899;
+/// Port of the PubSub API over WebSocket, which the validator always binds next to the RPC port.
+pub const WS_PORT: u16 = 8900;
+
+const PROGRAM_DIR: &str = "/programs";
+const ACCOUNT_DIR: &str = "/accounts";
+
+/// The validator only reports to be healthy once it produced its first slots.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(180);
+
+#[derive(Debug, Default, Clone)]
+pub struct SolanaTestValidatorArgs {
+    reset: bool,
+    programs: Vec<String>,
+    accounts: Vec<String>,
+}
+
+impl IntoIterator for SolanaTestValidatorArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        let mut args = vec![
+            "solana-test-validator".to_owned(),
+            "--rpc-port".to_owned(),
+            RPC_PORT.to_string(),
+            "--bind-address".to_owned(),
+            "0.0.0.0".to_owned(),
+        ];
+
+        if self.reset {
+            args.push("--reset".to_owned());
+        }
+        for program_id in self.programs {
+            args.push("--bpf-program".to_owned());
+            args.push(program_id.clone());
+            args.push(program_file(&program_id));
+        }
+        for pubkey in self.accounts {
+            args.push("--account".to_owned());
+            args.push(pubkey.clone());
+            args.push(account_file(&pubkey));
+        }
+
+        args.into_iter()
+    }
+}
+
+/// A single-node Solana cluster run by `solana-test-validator`.
+///
+/// The validator runs a faucet, hence accounts can be funded through the `requestAirdrop` RPC
+/// method.
+#[derive(Debug)]
+pub struct SolanaTestValidator {
+    tag: String,
+    arguments: SolanaTestValidatorArgs,
+    programs: Vec<(String, Vec<u8>)>,
+    accounts: Vec<(String, Vec<u8>)>,
+}
+
+impl Default for SolanaTestValidator {
+    fn default() -> Self {
+        SolanaTestValidator {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: SolanaTestValidatorArgs::default(),
+            programs: Vec::new(),
+            accounts: Vec::new(),
+        }
+    }
+}
+
+impl Image for SolanaTestValidator {
+    type Args = SolanaTestValidatorArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(
+            HttpWaitStrategy::new(RPC_PORT, "/")
+                .with_method("POST")
+                .with_header("Content-Type", "application/json")
+                .with_body(r#"{"jsonrpc":"2.0","id":1,"method":"getHealth"}"#)
+                .with_response_containing(r#""result":"ok""#),
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        SolanaTestValidatorArgs {
+            programs: self.programs.iter().map(|(id, _)| id.clone()).collect(),
+            accounts: self
+                .accounts
+                .iter()
+                .map(|(pubkey, _)| pubkey.clone())
+                .collect(),
+            ..self.arguments.clone()
+        }
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        SolanaTestValidator { arguments, ..self }
+    }
+
+    fn startup_timeout(&self) -> Option<Duration> {
+        Some(STARTUP_TIMEOUT)
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        let programs = self
+            .programs
+            .iter()
+            .map(|(id, so)| CopyToContainer::new(so.clone(), program_file(id)));
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|(pubkey, json)| CopyToContainer::new(json.clone(), account_file(pubkey)));
+
+        programs.chain(accounts).collect()
+    }
+}
+
+impl SolanaTestValidator {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        SolanaTestValidator {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Starts from an empty ledger even if the container is restarted.
+    pub fn with_ledger_reset(self) -> Self {
+        SolanaTestValidator {
+            arguments: SolanaTestValidatorArgs {
+                reset: true,
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Deploys the compiled program, i.e. the `.so` file built by `cargo build-sbf`, at the given
+    /// program id on startup.
+    pub fn with_bpf_program<P: Into<String>, S: Into<Vec<u8>>>(
+        mut self,
+        program_id: P,
+        so: S,
+    ) -> Self {
+        self.programs.push((program_id.into(), so.into()));
+        self
+    }
+
+    /// Creates the account from a JSON dump, as written by `solana account --output json`, on
+    /// startup.
+    pub fn with_account_fixture<P: Into<String>, J: Into<Vec<u8>>>(
+        mut self,
+        pubkey: P,
+        json: J,
+    ) -> Self {
+        self.accounts.push((pubkey.into(), json.into()));
+        self
+    }
+
+    /// Returns the url of the JSON-RPC API, given the host port mapped to [`RPC_PORT`].
+    pub fn rpc_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Returns the url of the PubSub API, given the host port mapped to [`WS_PORT`].
+    pub fn ws_url(&self, host_port: u16) -> String {
+        format!("ws://localhost:{}", host_port)
+    }
+}
+
+fn program_file(program_id: &str) -> String {
+    format!("{}/{}.so", PROGRAM_DIR, program_id)
+}
+
+fn account_file(pubkey: &str) -> String {
+    format!("{}/{}.json", ACCOUNT_DIR, pubkey)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_pass_copied_programs_and_accounts_to_validator() {
+        let image = SolanaTestValidator::default()
+            .with_ledger_reset()
+            .with_bpf_program("Prog1111111111111111111111111111111111111111", "elf")
+            .with_account_fixture("Acct1111111111111111111111111111111111111111", "{}");
+
+        assert_eq!(
+            image.args().into_iter().collect::<Vec<_>>(),
+            vec![
+                "solana-test-validator",
+                "--rpc-port",
+                "8899",
+                "--bind-address",
+                "0.0.0.0",
+                "--reset",
+                "--bpf-program",
+                "Prog1111111111111111111111111111111111111111",
+                "/programs/Prog1111111111111111111111111111111111111111.so",
+                "--account",
+                "Acct1111111111111111111111111111111111111111",
+                "/accounts/Acct1111111111111111111111111111111111111111.json",
+            ]
+        );
+        assert_eq!(
+            image
+                .copy_to_sources()
+                .into_iter()
+                .map(|file| file.target)
+                .collect::<Vec<_>>(),
+            vec![
+                "/programs/Prog1111111111111111111111111111111111111111.so",
+                "/accounts/Acct1111111111111111111111111111111111111111.json",
+            ]
+        );
+    }
+}
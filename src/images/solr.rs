@@ -0,0 +1,159 @@
+use crate::{
+    core::{ContainerState, ExecCommand, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "solr";
+const DEFAULT_TAG: &str = "9.4.1";
+
+/// Port of the web server, serving the APIs below `/solr`.
+pub const PORT: u16 = 8983;
+
+#[derive(Debug, Default, Clone)]
+pub struct SolrArgs {
+    core: Option<String>,
+    cloud_mode: bool,
+}
+
+impl IntoIterator for SolrArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        // the collections of SolrCloud are created through `exec_after_start` instead
+        match (self.cloud_mode, self.core) {
+            (true, _) => vec!["-c".to_owned()],
+            (false, Some(core)) => vec!["solr-precreate".to_owned(), core],
+            (false, None) => vec![],
+        }
+        .into_iter()
+    }
+}
+
+/// An Apache Solr server.
+///
+/// By default, the server runs standalone without any cores. A core created through [`with_core`]
+/// uses the default configset, which guesses the schema from the indexed documents.
+///
+/// [`with_core`]: struct.Solr.html#method.with_core
+#[derive(Debug)]
+pub struct Solr {
+    tag: String,
+    arguments: SolrArgs,
+}
+
+impl Default for Solr {
+    fn default() -> Self {
+        Solr {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: SolrArgs::default(),
+        }
+    }
+}
+
+impl Image for Solr {
+    type Args = SolrArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        let strategy = HttpWaitStrategy::new(PORT, "/solr/admin/cores?action=STATUS");
+
+        match (&self.arguments.core, self.arguments.cloud_mode) {
+            (Some(core), false) => vec![WaitFor::http(
+                strategy.with_response_containing(format!(r#""name":"{}""#, core)),
+            )],
+            _ => vec![WaitFor::http(strategy)],
+        }
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Solr { arguments, ..self }
+    }
+
+    fn exec_after_start(&self, _: ContainerState) -> Vec<ExecCommand> {
+        match (&self.arguments.core, self.arguments.cloud_mode) {
+            (Some(core), true) => vec![ExecCommand::new(vec!["solr", "create", "-c", core])],
+            _ => vec![],
+        }
+    }
+}
+
+impl Solr {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Solr {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Creates a core with the given name on startup, or a collection in SolrCloud mode.
+    pub fn with_core<C: Into<String>>(self, core: C) -> Self {
+        Solr {
+            arguments: SolrArgs {
+                core: Some(core.into()),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Runs the server in SolrCloud mode with an embedded ZooKeeper.
+    pub fn with_cloud_mode(self) -> Self {
+        Solr {
+            arguments: SolrArgs {
+                cloud_mode: true,
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Returns the url of the given core or collection, given the host port mapped to [`PORT`].
+    pub fn core_url(&self, host_port: u16, core: &str) -> String {
+        format!("http://localhost:{}/solr/{}", host_port, core)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ports::Ports;
+
+    #[test]
+    fn should_create_collection_after_start_in_cloud_mode() {
+        let image = Solr::default().with_core("books").with_cloud_mode();
+
+        assert_eq!(image.args().into_iter().collect::<Vec<_>>(), vec!["-c"]);
+        assert_eq!(
+            image.exec_after_start(ContainerState::new(Ports::default()))[0].cmd,
+            vec!["solr", "create", "-c", "books"]
+        );
+        assert_eq!(
+            Solr::default()
+                .with_core("books")
+                .args()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["solr-precreate", "books"]
+        );
+    }
+}
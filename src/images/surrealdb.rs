@@ -0,0 +1,243 @@
+use crate::{
+    core::{HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "surrealdb/surrealdb";
+const DEFAULT_TAG: &str = "v1.0.0";
+
+/// Port of the HTTP and WebSocket APIs.
+pub const PORT: u16 = 8000;
+
+const DATA_DIR: &str = "/data";
+
+#[derive(Debug, Clone)]
+pub struct SurrealDbArgs {
+    user: String,
+    password: String,
+    strict: bool,
+    file_storage: bool,
+}
+
+impl Default for SurrealDbArgs {
+    fn default() -> Self {
+        SurrealDbArgs {
+            user: "root".to_owned(),
+            password: "root".to_owned(),
+            strict: false,
+            file_storage: false,
+        }
+    }
+}
+
+impl IntoIterator for SurrealDbArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        let mut args = vec![
+            "start".to_owned(),
+            "--user".to_owned(),
+            self.user,
+            "--pass".to_owned(),
+            self.password,
+        ];
+
+        if self.strict {
+            args.push("--strict".to_owned());
+        }
+
+        if self.file_storage {
+            args.push(format!("file:{}/database.db", DATA_DIR));
+        } else {
+            args.push("memory".to_owned());
+        }
+
+        args.into_iter()
+    }
+}
+
+/// A SurrealDB server, storing its data in memory unless configured through
+/// [`with_file_storage`].
+///
+/// The server authenticates the root user `root` with the password `root` by default.
+///
+/// [`with_file_storage`]: struct.SurrealDb.html#method.with_file_storage
+#[derive(Debug)]
+pub struct SurrealDb {
+    tag: String,
+    arguments: SurrealDbArgs,
+    volumes: HashMap<String, String>,
+}
+
+impl Default for SurrealDb {
+    fn default() -> Self {
+        SurrealDb {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: SurrealDbArgs::default(),
+            volumes: HashMap::new(),
+        }
+    }
+}
+
+impl Image for SurrealDb {
+    type Args = SurrealDbArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(
+            PORT,
+            self.health_path(),
+        ))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        self.volumes.clone()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        SurrealDb { arguments, ..self }
+    }
+}
+
+impl SurrealDb {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        SurrealDb {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the name of the root user, `root` by default.
+    pub fn with_user<U: Into<String>>(self, user: U) -> Self {
+        SurrealDb {
+            arguments: SurrealDbArgs {
+                user: user.into(),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Sets the password of the root user, `root` by default.
+    pub fn with_password<P: Into<String>>(self, password: P) -> Self {
+        SurrealDb {
+            arguments: SurrealDbArgs {
+                password: password.into(),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Starts the server in strict mode, which rejects queries on namespaces, databases and
+    /// tables that were not defined before.
+    pub fn with_strict(self) -> Self {
+        SurrealDb {
+            arguments: SurrealDbArgs {
+                strict: true,
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Stores the data in a file on the given named volume or host directory, e.g. to keep it
+    /// across containers.
+    pub fn with_file_storage<V: Into<String>>(mut self, volume: V) -> Self {
+        self.volumes.insert(volume.into(), DATA_DIR.to_owned());
+
+        SurrealDb {
+            arguments: SurrealDbArgs {
+                file_storage: true,
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Returns the name of the root user.
+    pub fn user(&self) -> &str {
+        &self.arguments.user
+    }
+
+    /// Returns the password of the root user.
+    pub fn password(&self) -> &str {
+        &self.arguments.password
+    }
+
+    /// Returns the url of the HTTP API, given the host port mapped to [`PORT`].
+    pub fn http_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Returns the url of the WebSocket API the official clients connect to, given the host port
+    /// mapped to [`PORT`].
+    pub fn ws_url(&self, host_port: u16) -> String {
+        format!("ws://localhost:{}/rpc", host_port)
+    }
+
+    /// The beta releases of SurrealDB only serve `/status`, which later releases kept next to
+    /// `/health`.
+    fn health_path(&self) -> &'static str {
+        if self.tag.contains("beta") {
+            "/status"
+        } else {
+            "/health"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_store_data_on_volume_in_file_storage_mode() {
+        let image = SurrealDb::default()
+            .with_user("admin")
+            .with_strict()
+            .with_file_storage("surrealdb-data");
+
+        assert_eq!(
+            image.args().into_iter().collect::<Vec<_>>(),
+            vec![
+                "start",
+                "--user",
+                "admin",
+                "--pass",
+                "root",
+                "--strict",
+                "file:/data/database.db"
+            ]
+        );
+        assert_eq!(
+            image.volumes().get("surrealdb-data"),
+            Some(&"/data".to_owned())
+        );
+    }
+
+    #[test]
+    fn should_check_status_endpoint_of_beta_releases() {
+        assert_eq!(SurrealDb::default().health_path(), "/health");
+        assert_eq!(
+            SurrealDb::default().with_tag("1.0.0-beta.9").health_path(),
+            "/status"
+        );
+    }
+}
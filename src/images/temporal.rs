@@ -0,0 +1,160 @@
+use crate::{
+    core::{ContainerState, ExecCommand, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "temporalio/temporal";
+const DEFAULT_TAG: &str = "1.1.0";
+
+/// Port of the gRPC frontend that workers and clients connect to.
+pub const GRPC_PORT: u16 = 7233;
+/// Port of the web UI.
+pub const UI_PORT: u16 = 8233;
+
+const DEFAULT_NAMESPACE: &str = "default";
+
+#[derive(Debug, Default, Clone)]
+pub struct TemporalArgs;
+
+impl IntoIterator for TemporalArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![
+            "server".to_owned(),
+            "start-dev".to_owned(),
+            "--ip".to_owned(),
+            "0.0.0.0".to_owned(),
+        ]
+        .into_iter()
+    }
+}
+
+/// A Temporal development server, which keeps its state in memory.
+///
+/// The server is ready once the web UI lists the `default` namespace, which it fetches from the
+/// gRPC frontend. A namespace configured through [`with_namespace`] is created afterwards.
+///
+/// [`with_namespace`]: struct.Temporal.html#method.with_namespace
+#[derive(Debug)]
+pub struct Temporal {
+    tag: String,
+    arguments: TemporalArgs,
+    namespace: String,
+}
+
+impl Default for Temporal {
+    fn default() -> Self {
+        Temporal {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: TemporalArgs,
+            namespace: DEFAULT_NAMESPACE.to_owned(),
+        }
+    }
+}
+
+impl Image for Temporal {
+    type Args = TemporalArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(
+            HttpWaitStrategy::new(UI_PORT, "/api/v1/namespaces")
+                .with_response_containing(format!(r#""{}""#, DEFAULT_NAMESPACE)),
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Temporal { arguments, ..self }
+    }
+
+    fn exec_after_start(&self, _: ContainerState) -> Vec<ExecCommand> {
+        if self.namespace == DEFAULT_NAMESPACE {
+            return vec![];
+        }
+
+        vec![ExecCommand::new(vec![
+            "temporal",
+            "operator",
+            "namespace",
+            "create",
+            "--namespace",
+            &self.namespace,
+        ])]
+    }
+}
+
+impl Temporal {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Temporal {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Creates the given namespace once the server started, `default` by default, which always
+    /// exists.
+    pub fn with_namespace<N: Into<String>>(self, namespace: N) -> Self {
+        Temporal {
+            namespace: namespace.into(),
+            ..self
+        }
+    }
+
+    /// Returns the namespace workflows of the test run in.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Returns the address of the gRPC frontend, given the host port mapped to [`GRPC_PORT`].
+    pub fn grpc_endpoint(&self, host_port: u16) -> String {
+        format!("localhost:{}", host_port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ports::Ports;
+
+    #[test]
+    fn should_only_create_namespace_other_than_default() {
+        assert!(Temporal::default()
+            .exec_after_start(ContainerState::new(Ports::default()))
+            .is_empty());
+        assert_eq!(
+            Temporal::default()
+                .with_namespace("orders")
+                .exec_after_start(ContainerState::new(Ports::default()))[0]
+                .cmd,
+            vec![
+                "temporal",
+                "operator",
+                "namespace",
+                "create",
+                "--namespace",
+                "orders"
+            ]
+        );
+    }
+}
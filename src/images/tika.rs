@@ -0,0 +1,176 @@
+use crate::{
+    core::{wait::blocking_request, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "apache/tika";
+const DEFAULT_TAG: &str = "2.9.1.0";
+
+/// Port of the REST API.
+pub const PORT: u16 = 9998;
+
+#[derive(Debug, Default, Clone)]
+pub struct TikaServerArgs;
+
+impl IntoIterator for TikaServerArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// An Apache Tika server, extracting text and metadata from documents sent to [`PORT`], e.g.
+/// through [`extract_text`].
+///
+/// The default image does not come with Tesseract, so text in images or scanned PDFs is only
+/// extracted with [`with_ocr`]. Tika configures OCR per request rather than through the
+/// environment, which [`extract_text`] takes care of.
+///
+/// [`extract_text`]: struct.TikaServer.html#method.extract_text
+/// [`with_ocr`]: struct.TikaServer.html#method.with_ocr
+#[derive(Debug)]
+pub struct TikaServer {
+    tag: String,
+    arguments: TikaServerArgs,
+    ocr: bool,
+    ocr_language: Option<String>,
+}
+
+impl Default for TikaServer {
+    fn default() -> Self {
+        TikaServer {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: TikaServerArgs,
+            ocr: false,
+            ocr_language: None,
+        }
+    }
+}
+
+impl Image for TikaServer {
+    type Args = TikaServerArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        // the variant with Tesseract and its language packs is tagged with a `-full` suffix
+        if self.ocr {
+            format!("{}:{}-full", CONTAINER_IDENTIFIER, &self.tag)
+        } else {
+            format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+        }
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(
+            HttpWaitStrategy::new(PORT, "/tika").with_response_containing("This is Tika Server"),
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        TikaServer { arguments, ..self }
+    }
+}
+
+impl TikaServer {
+    /// Sets the tag of the image, without the `-full` suffix that [`with_ocr`] adds.
+    ///
+    /// [`with_ocr`]: struct.TikaServer.html#method.with_ocr
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        TikaServer {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Runs the variant of the image that comes with Tesseract, and lets [`extract_text`] OCR
+    /// PDFs next to extracting their text layer. Images are always OCRed by this variant.
+    ///
+    /// [`extract_text`]: struct.TikaServer.html#method.extract_text
+    pub fn with_ocr(self) -> Self {
+        TikaServer { ocr: true, ..self }
+    }
+
+    /// Sets the languages Tesseract recognizes, e.g. `eng+deu`, instead of English. Only takes
+    /// effect together with [`with_ocr`].
+    ///
+    /// [`with_ocr`]: struct.TikaServer.html#method.with_ocr
+    pub fn with_ocr_language<L: Into<String>>(self, language: L) -> Self {
+        TikaServer {
+            ocr_language: Some(language.into()),
+            ..self
+        }
+    }
+
+    /// Returns the url of the REST API, given the host port mapped to [`PORT`], e.g.
+    /// `http://localhost:32768`.
+    pub fn url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Extracts the plain text of the document, given the host port mapped to [`PORT`] and the
+    /// media type of the document, e.g. `application/pdf`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the server cannot be reached or fails to parse the document.
+    pub fn extract_text(&self, host_port: u16, document: &[u8], content_type: &str) -> String {
+        let url = format!("{}/tika", self.url(host_port));
+        let document = document.to_vec();
+        let content_type = content_type.to_owned();
+        let ocr = self.ocr;
+        let ocr_language = self.ocr_language.clone();
+
+        blocking_request(move |client| {
+            let mut request = client
+                .put(&url)
+                .header(reqwest::header::ACCEPT, "text/plain")
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(document);
+
+            if ocr {
+                request = request.header("X-Tika-PDFOcrStrategy", "ocr_and_text");
+
+                if let Some(language) = ocr_language {
+                    request = request.header("X-Tika-OCRLanguage", language);
+                }
+            }
+
+            request
+        })
+        .unwrap_or_else(|e| panic!("failed to extract text through tika: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_run_full_variant_with_ocr() {
+        assert_eq!(TikaServer::default().descriptor(), "apache/tika:2.9.1.0");
+        assert_eq!(
+            TikaServer::default()
+                .with_tag("2.9.0.0")
+                .with_ocr()
+                .descriptor(),
+            "apache/tika:2.9.0.0-full"
+        );
+    }
+}
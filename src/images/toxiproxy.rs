@@ -0,0 +1,304 @@
+use crate::{
+    core::{
+        wait::{blocking_request, json_post},
+        WaitFor,
+    },
+    Image,
+};
+use std::{collections::HashMap, ops::RangeInclusive};
+
+const CONTAINER_IDENTIFIER: &str = "ghcr.io/shopify/toxiproxy";
+const DEFAULT_TAG: &str = "2.4.0";
+
+/// Port of the HTTP API used to manage proxies and toxics.
+pub const API_PORT: u16 = 8474;
+
+const DEFAULT_PROXY_PORTS: RangeInclusive<u16> = 8666..=8675;
+
+#[derive(Debug, Default, Clone)]
+pub struct ToxiproxyArgs;
+
+impl IntoIterator for ToxiproxyArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A Toxiproxy server for injecting network faults between a client and an upstream service.
+///
+/// Proxies are created at runtime through the [`ToxiproxyClient`] returned by [`client`], which is
+/// why the ports they can listen on need to be published up front. By default, these are the
+/// ports 8666 to 8675, see [`with_proxy_ports`].
+///
+/// [`ToxiproxyClient`]: struct.ToxiproxyClient.html
+/// [`client`]: struct.Toxiproxy.html#method.client
+/// [`with_proxy_ports`]: struct.Toxiproxy.html#method.with_proxy_ports
+#[derive(Debug)]
+pub struct Toxiproxy {
+    tag: String,
+    arguments: ToxiproxyArgs,
+    proxy_ports: RangeInclusive<u16>,
+}
+
+impl Default for Toxiproxy {
+    fn default() -> Self {
+        Toxiproxy {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: ToxiproxyArgs {},
+            proxy_ports: DEFAULT_PROXY_PORTS,
+        }
+    }
+}
+
+impl Image for Toxiproxy {
+    type Args = ToxiproxyArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::message_on_stderr("API HTTP server starting")]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Toxiproxy { arguments, ..self }
+    }
+
+    fn expose_ports(&self) -> Vec<u16> {
+        self.proxy_ports.clone().collect()
+    }
+}
+
+impl Toxiproxy {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Toxiproxy {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the ports proxies can listen on, which are published when the container starts.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the range is empty or contains the [`API_PORT`].
+    pub fn with_proxy_ports(self, proxy_ports: RangeInclusive<u16>) -> Self {
+        assert!(
+            proxy_ports.start() <= proxy_ports.end(),
+            "proxy ports must not be empty"
+        );
+        assert!(
+            !proxy_ports.contains(&API_PORT),
+            "proxy ports must not contain the API port {}",
+            API_PORT
+        );
+
+        Toxiproxy {
+            proxy_ports,
+            ..self
+        }
+    }
+
+    pub fn proxy_ports(&self) -> &RangeInclusive<u16> {
+        &self.proxy_ports
+    }
+
+    /// Returns the url of the HTTP API, given the host port mapped to [`API_PORT`].
+    pub fn api_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Returns a client for the HTTP API, given the host port mapped to [`API_PORT`].
+    pub fn client(&self, host_port: u16) -> ToxiproxyClient {
+        ToxiproxyClient {
+            url: self.api_url(host_port),
+            proxy_ports: self.proxy_ports.clone(),
+        }
+    }
+}
+
+/// A client for the HTTP API of a running [`Toxiproxy`] server.
+///
+/// [`Toxiproxy`]: struct.Toxiproxy.html
+#[derive(Debug, Clone)]
+pub struct ToxiproxyClient {
+    url: String,
+    proxy_ports: RangeInclusive<u16>,
+}
+
+impl ToxiproxyClient {
+    /// Creates a proxy that listens on the given port inside the container and forwards
+    /// connections to the upstream address, e.g. `redis:6379` for a container named `redis` on
+    /// the same network.
+    ///
+    /// Clients on the host connect to the proxy through the host port mapped to `listen_port`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `listen_port` is not one of the published proxy ports or if the
+    /// server rejects the proxy, e.g. because one with the same name already exists.
+    pub fn create_proxy<N: Into<String>, U: Into<String>>(
+        &self,
+        name: N,
+        listen_port: u16,
+        upstream: U,
+    ) -> Proxy {
+        assert!(
+            self.proxy_ports.contains(&listen_port),
+            "port {} is not one of the proxy ports {:?}",
+            listen_port,
+            self.proxy_ports
+        );
+
+        let name = name.into();
+        let url = format!("{}/proxies", self.url);
+        let body = serde_json::json!({
+            "name": name,
+            "listen": format!("0.0.0.0:{}", listen_port),
+            "upstream": upstream.into(),
+            "enabled": true,
+        })
+        .to_string();
+
+        blocking_request(move |client| json_post(client, &url, &body))
+            .unwrap_or_else(|e| panic!("failed to create proxy {}: {}", name, e));
+
+        Proxy {
+            url: format!("{}/proxies/{}", self.url, name),
+            name,
+            listen_port,
+        }
+    }
+}
+
+/// A proxy created through [`ToxiproxyClient::create_proxy`].
+///
+/// Toxics added through this handle affect the data sent from the upstream to the client.
+///
+/// [`ToxiproxyClient::create_proxy`]: struct.ToxiproxyClient.html#method.create_proxy
+#[derive(Debug, Clone)]
+pub struct Proxy {
+    url: String,
+    name: String,
+    listen_port: u16,
+}
+
+impl Proxy {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the port the proxy listens on inside the container.
+    pub fn listen_port(&self) -> u16 {
+        self.listen_port
+    }
+
+    /// Delays all data by `latency_ms`, give or take up to `jitter_ms`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the server rejects the toxic.
+    pub fn add_latency_toxic(&self, latency_ms: u32, jitter_ms: u32) {
+        self.add_toxic(
+            "latency",
+            serde_json::json!({ "latency": latency_ms, "jitter": jitter_ms }),
+        )
+    }
+
+    /// Limits the throughput to `rate_kb_per_s` kilobytes per second.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the server rejects the toxic.
+    pub fn add_bandwidth_toxic(&self, rate_kb_per_s: u64) {
+        self.add_toxic("bandwidth", serde_json::json!({ "rate": rate_kb_per_s }))
+    }
+
+    /// Disables the proxy, which closes all connections and refuses new ones.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the server cannot be reached.
+    pub fn down(&self) {
+        self.set_enabled(false)
+    }
+
+    /// Enables the proxy again after [`down`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the server cannot be reached.
+    ///
+    /// [`down`]: struct.Proxy.html#method.down
+    pub fn up(&self) {
+        self.set_enabled(true)
+    }
+
+    fn add_toxic(&self, kind: &str, attributes: serde_json::Value) {
+        let url = format!("{}/toxics", self.url);
+        let body = serde_json::json!({
+            "type": kind,
+            "stream": "downstream",
+            "toxicity": 1.0,
+            "attributes": attributes,
+        })
+        .to_string();
+
+        blocking_request(move |client| json_post(client, &url, &body)).unwrap_or_else(|e| {
+            panic!("failed to add {} toxic to proxy {}: {}", kind, self.name, e)
+        });
+    }
+
+    fn set_enabled(&self, enabled: bool) {
+        let url = self.url.clone();
+        let body = serde_json::json!({ "enabled": enabled }).to_string();
+
+        blocking_request(move |client| json_post(client, &url, &body))
+            .unwrap_or_else(|e| panic!("failed to update proxy {}: {}", self.name, e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_expose_proxy_ports() {
+        let toxiproxy = Toxiproxy::default().with_proxy_ports(9000..=9002);
+
+        assert_eq!(toxiproxy.expose_ports(), vec![9000, 9001, 9002]);
+    }
+
+    #[test]
+    #[should_panic(expected = "proxy ports must not contain the API port 8474")]
+    fn should_reject_proxy_ports_containing_api_port() {
+        Toxiproxy::default().with_proxy_ports(8470..=8480);
+    }
+
+    #[test]
+    #[should_panic(expected = "port 9000 is not one of the proxy ports 8666..=8675")]
+    fn should_reject_proxy_on_unpublished_port() {
+        Toxiproxy::default()
+            .client(8474)
+            .create_proxy("redis", 9000, "redis:6379");
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{
+    core::{CopyToContainer, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "trinodb/trino";
+const DEFAULT_TAG: &str = "403";
+
+/// Port of the HTTP API, serving both the REST statement API and JDBC clients.
+pub const PORT: u16 = 8080;
+
+const CATALOG_DIR: &str = "/etc/trino/catalog";
+
+#[derive(Debug, Default, Clone)]
+pub struct TrinoArgs;
+
+impl IntoIterator for TrinoArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A single-node Trino (formerly PrestoSQL) cluster, acting as coordinator and worker.
+///
+/// The server logs that it started a few seconds before it actually accepts queries, hence the
+/// container is only considered ready once `/v1/info` reports that the server is no longer starting.
+#[derive(Debug)]
+pub struct Trino {
+    tag: String,
+    arguments: TrinoArgs,
+    catalogs: Vec<(String, Vec<(String, String)>)>,
+    memory_mb: Option<u32>,
+}
+
+impl Default for Trino {
+    fn default() -> Self {
+        Trino {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: TrinoArgs {},
+            catalogs: Vec::new(),
+            memory_mb: None,
+        }
+    }
+}
+
+impl Image for Trino {
+    type Args = TrinoArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(
+            HttpWaitStrategy::new(PORT, "/v1/info").with_response_containing(r#""starting":false"#),
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Trino { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        let mut files = self
+            .catalogs
+            .iter()
+            .map(|(name, properties)| {
+                CopyToContainer::new(
+                    to_properties(properties),
+                    format!("{}/{}.properties", CATALOG_DIR, name),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(memory_mb) = self.memory_mb {
+            files.push(CopyToContainer::new(
+                jvm_config(memory_mb),
+                "/etc/trino/jvm.config",
+            ));
+            files.push(CopyToContainer::new(
+                node_config(memory_mb),
+                "/etc/trino/config.properties",
+            ));
+        }
+
+        files
+    }
+}
+
+impl Trino {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Trino {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Adds a catalog with the given properties, e.g. `connector.name=memory`.
+    ///
+    /// The catalog is written to `/etc/trino/catalog/<name>.properties` before the server starts.
+    pub fn with_catalog<N, I, K, V>(mut self, name: N, properties: I) -> Self
+    where
+        N: Into<String>,
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.catalogs.push((
+            name.into(),
+            properties
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        ));
+        self
+    }
+
+    /// Limits the JVM heap to the given size and scales the query memory limits accordingly.
+    ///
+    /// The default configuration of the image assumes more memory than small CI machines offer.
+    pub fn with_memory_mb(self, memory_mb: u32) -> Self {
+        Trino {
+            memory_mb: Some(memory_mb),
+            ..self
+        }
+    }
+
+    /// Returns the url to be used by JDBC clients, given the host port mapped to [`PORT`].
+    pub fn jdbc_like_url(&self, host_port: u16) -> String {
+        format!("jdbc:trino://localhost:{}", host_port)
+    }
+
+    /// Returns the url of the HTTP API, given the host port mapped to [`PORT`].
+    pub fn http_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+}
+
+fn to_properties(properties: &[(String, String)]) -> String {
+    properties
+        .iter()
+        .map(|(key, value)| format!("{}={}\n", key, value))
+        .collect()
+}
+
+fn jvm_config(memory_mb: u32) -> String {
+    format!(
+        "-server\n-Xmx{}M\n-XX:+UseG1GC\n-XX:+ExitOnOutOfMemoryError\n-Djdk.attach.allowAttachSelf=true\n",
+        memory_mb
+    )
+}
+
+/// The query memory must leave room for the heap headroom Trino reserves (30% of the heap).
+fn node_config(memory_mb: u32) -> String {
+    let query_memory_mb = memory_mb / 2;
+
+    to_properties(&[
+        ("coordinator".to_owned(), "true".to_owned()),
+        (
+            "node-scheduler.include-coordinator".to_owned(),
+            "true".to_owned(),
+        ),
+        ("http-server.http.port".to_owned(), PORT.to_string()),
+        (
+            "discovery.uri".to_owned(),
+            format!("http://localhost:{}", PORT),
+        ),
+        (
+            "query.max-memory".to_owned(),
+            format!("{}MB", query_memory_mb),
+        ),
+        (
+            "query.max-memory-per-node".to_owned(),
+            format!("{}MB", query_memory_mb),
+        ),
+    ])
+}
@@ -1,6 +1,25 @@
-use crate::{core::WaitFor, Image};
+use crate::{
+    core::{Container, ContainerAsync, WaitFor},
+    Image,
+};
 use std::collections::HashMap;
 
+const CONTAINER_IDENTIFIER: &str = "trufflesuite/ganache-cli";
+const DEFAULT_TAG: &str = "v6.12.2";
+
+/// Port of the JSON-RPC API.
+pub const PORT: u16 = 8545;
+
+const READY_MESSAGE: &str = "Listening on ";
+
+/// A local Ethereum node.
+///
+/// Unless [`with_mnemonic`] or [`with_deterministic`] is used, every container generates new
+/// accounts. The generated accounts can be retrieved through [`accounts`] on the container.
+///
+/// [`with_mnemonic`]: struct.GanacheCli.html#method.with_mnemonic
+/// [`with_deterministic`]: struct.GanacheCli.html#method.with_deterministic
+/// [`accounts`]: ../../core/struct.Container.html#method.accounts
 #[derive(Debug)]
 pub struct GanacheCli {
     tag: String,
@@ -12,12 +31,18 @@ pub struct GanacheCliArgs {
     pub network_id: u32,
     pub number_of_accounts: u32,
     pub mnemonic: String,
+    /// Generates the accounts from a fixed mnemonic that ganache ships with.
+    pub deterministic: bool,
+    pub default_balance_ether: Option<u64>,
+    pub chain_id: Option<u64>,
+    /// Mines a block every given number of seconds instead of one per transaction.
+    pub block_time: Option<u64>,
 }
 
 impl Default for GanacheCli {
     fn default() -> Self {
         GanacheCli {
-            tag: "v6.1.3".into(),
+            tag: DEFAULT_TAG.to_string(),
             arguments: GanacheCliArgs::default(),
         }
     }
@@ -29,6 +54,10 @@ impl Default for GanacheCliArgs {
             network_id: 42,
             number_of_accounts: 7,
             mnemonic: "supersecure".to_string(),
+            deterministic: false,
+            default_balance_ether: None,
+            chain_id: None,
+            block_time: None,
         }
     }
 }
@@ -40,20 +69,45 @@ impl IntoIterator for GanacheCliArgs {
     fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
         let mut args = Vec::new();
 
-        if !self.mnemonic.is_empty() {
+        if self.deterministic {
+            args.push("-d".to_string());
+        } else if !self.mnemonic.is_empty() {
             args.push("-m".to_string());
             args.push(self.mnemonic.to_string());
         }
 
         args.push("-a".to_string());
         args.push(self.number_of_accounts.to_string());
+
+        if let Some(balance) = self.default_balance_ether {
+            args.push("-e".to_string());
+            args.push(balance.to_string());
+        }
+
         args.push("-i".to_string());
         args.push(self.network_id.to_string());
 
+        if let Some(chain_id) = self.chain_id {
+            args.push("--chainId".to_string());
+            args.push(chain_id.to_string());
+        }
+        if let Some(block_time) = self.block_time {
+            args.push("-b".to_string());
+            args.push(block_time.to_string());
+        }
+
         args.into_iter()
     }
 }
 
+/// An account generated by ganache on startup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Account {
+    pub address: String,
+    /// The hex encoded private key, prefixed with `0x`.
+    pub private_key: String,
+}
+
 impl Image for GanacheCli {
     type Args = GanacheCliArgs;
     type EnvVars = HashMap<String, String>;
@@ -61,11 +115,11 @@ impl Image for GanacheCli {
     type EntryPoint = std::convert::Infallible;
 
     fn descriptor(&self) -> String {
-        format!("trufflesuite/ganache-cli:{}", self.tag)
+        format!("{}:{}", CONTAINER_IDENTIFIER, self.tag)
     }
 
     fn ready_conditions(&self) -> Vec<WaitFor> {
-        vec![WaitFor::message_on_stdout("Listening on localhost:")]
+        vec![WaitFor::message_on_stdout(READY_MESSAGE)]
     }
 
     fn args(&self) -> <Self as Image>::Args {
@@ -84,3 +138,252 @@ impl Image for GanacheCli {
         GanacheCli { arguments, ..self }
     }
 }
+
+impl GanacheCli {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        GanacheCli {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Generates the accounts from the given mnemonic.
+    pub fn with_mnemonic<M: Into<String>>(self, mnemonic: M) -> Self {
+        GanacheCli {
+            arguments: GanacheCliArgs {
+                mnemonic: mnemonic.into(),
+                deterministic: false,
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Generates the accounts from the mnemonic ganache uses for `-d`, which means that the
+    /// first account is always `0x90f8bf6a479f320ead074411a4b0e7944ea8c9c1`.
+    pub fn with_deterministic(self) -> Self {
+        GanacheCli {
+            arguments: GanacheCliArgs {
+                deterministic: true,
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Generates the given number of accounts, each funded with the given amount of ether.
+    pub fn with_accounts(self, number_of_accounts: u32, balance_ether: u64) -> Self {
+        GanacheCli {
+            arguments: GanacheCliArgs {
+                number_of_accounts,
+                default_balance_ether: Some(balance_ether),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Sets the chain id returned by `eth_chainId`, which is used to sign transactions.
+    pub fn with_chain_id(self, chain_id: u64) -> Self {
+        GanacheCli {
+            arguments: GanacheCliArgs {
+                chain_id: Some(chain_id),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Mines a block every given number of seconds instead of one per transaction.
+    pub fn with_block_time(self, seconds: u64) -> Self {
+        GanacheCli {
+            arguments: GanacheCliArgs {
+                block_time: Some(seconds),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Returns the url of the JSON-RPC API, given the host port mapped to [`PORT`].
+    pub fn rpc_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+}
+
+impl<'d> Container<'d, GanacheCli> {
+    /// Returns the accounts ganache generated on startup, in the order of their index.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the startup banner cannot be read from the logs.
+    pub fn accounts(&self) -> Vec<Account> {
+        parse_accounts(&self.stdout_lines_until(READY_MESSAGE))
+    }
+}
+
+impl<'d> ContainerAsync<'d, GanacheCli> {
+    /// Returns the accounts ganache generated on startup, in the order of their index.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the startup banner cannot be read from the logs.
+    pub async fn accounts(&self) -> Vec<Account> {
+        parse_accounts(&self.stdout_lines_until(READY_MESSAGE).await)
+    }
+}
+
+/// Parses the `Available Accounts` and `Private Keys` sections of the startup banner.
+///
+/// Entries look like `(0) 0x90f8bf6a479f320ead074411a4b0e7944ea8c9c1 (100 ETH)`, older versions
+/// neither print the balance nor prefix the private keys with `0x`.
+fn parse_accounts(banner: &[String]) -> Vec<Account> {
+    let mut addresses = Vec::new();
+    let mut private_keys = Vec::new();
+    let mut section = None;
+
+    for line in banner.iter().map(|line| line.trim()) {
+        if line.starts_with('(') {
+            let value = match line.split_whitespace().nth(1) {
+                Some(value) => value,
+                None => continue,
+            };
+
+            match section {
+                Some("Available Accounts") => addresses.push(value.to_owned()),
+                Some("Private Keys") => private_keys.push(if value.starts_with("0x") {
+                    value.to_owned()
+                } else {
+                    format!("0x{}", value)
+                }),
+                _ => {}
+            }
+        } else if !line.is_empty() && !line.starts_with('=') {
+            section = Some(line);
+        }
+    }
+
+    assert_eq!(
+        addresses.len(),
+        private_keys.len(),
+        "startup banner lists a different number of accounts and private keys"
+    );
+
+    addresses
+        .into_iter()
+        .zip(private_keys)
+        .map(|(address, private_key)| Account {
+            address,
+            private_key,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(banner: &str) -> Vec<String> {
+        banner.lines().map(ToOwned::to_owned).collect()
+    }
+
+    #[test]
+    fn should_render_configuration_into_args() {
+        let image = GanacheCli::default()
+            .with_deterministic()
+            .with_accounts(3, 50)
+            .with_chain_id(1337)
+            .with_block_time(2);
+
+        assert_eq!(
+            image.args().into_iter().collect::<Vec<_>>(),
+            vec![
+                "-d",
+                "-a",
+                "3",
+                "-e",
+                "50",
+                "-i",
+                "42",
+                "--chainId",
+                "1337",
+                "-b",
+                "2"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_prefer_mnemonic_set_after_deterministic() {
+        let image = GanacheCli::default()
+            .with_deterministic()
+            .with_mnemonic("test test test");
+
+        assert_eq!(
+            image.args().into_iter().collect::<Vec<_>>(),
+            vec!["-m", "test test test", "-a", "7", "-i", "42"]
+        );
+    }
+
+    #[test]
+    fn should_parse_accounts_from_startup_banner() {
+        let banner = lines(
+            "Ganache CLI v6.12.2 (ganache-core: 2.13.2)
+
+Available Accounts
+==================
+(0) 0x90F8bf6A479f320ead074411a4B0e7944Ea8c9C1 (100 ETH)
+(1) 0xFFcf8FDEE72ac11b5c542428B35EEF5769C409f0 (100 ETH)
+
+Private Keys
+==================
+(0) 0x4f3edf983ac636a65a842ce7c78d9aa706d3b113bce9c46f30d7d21715b23b1d
+(1) 0x6cbed15c793ce57650b9877cf6fa156fbef513c4e6134f022a85b1ffdd59b2a1
+
+HD Wallet
+==================
+Mnemonic:      myth like bonus scare over problem client lizard pioneer submit female collect
+Base HD Path:  m/44'/60'/0'/0/{account_index}
+
+Listening on 0.0.0.0:8545",
+        );
+
+        assert_eq!(
+            parse_accounts(&banner),
+            vec![
+                Account {
+                    address: "0x90F8bf6A479f320ead074411a4B0e7944Ea8c9C1".to_owned(),
+                    private_key:
+                        "0x4f3edf983ac636a65a842ce7c78d9aa706d3b113bce9c46f30d7d21715b23b1d"
+                            .to_owned(),
+                },
+                Account {
+                    address: "0xFFcf8FDEE72ac11b5c542428B35EEF5769C409f0".to_owned(),
+                    private_key:
+                        "0x6cbed15c793ce57650b9877cf6fa156fbef513c4e6134f022a85b1ffdd59b2a1"
+                            .to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_prefix_private_keys_of_older_versions() {
+        let banner = lines(
+            "Available Accounts
+==================
+(0) 0x90f8bf6a479f320ead074411a4b0e7944ea8c9c1
+
+Private Keys
+==================
+(0) 4f3edf983ac636a65a842ce7c78d9aa706d3b113bce9c46f30d7d21715b23b1d
+
+Listening on localhost:8545",
+        );
+
+        assert_eq!(
+            parse_accounts(&banner)[0].private_key,
+            "0x4f3edf983ac636a65a842ce7c78d9aa706d3b113bce9c46f30d7d21715b23b1d"
+        );
+    }
+}
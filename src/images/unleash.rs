@@ -0,0 +1,206 @@
+use crate::{
+    core::{
+        wait::{blocking_request, json_post},
+        HttpWaitStrategy, WaitFor,
+    },
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "unleashorg/unleash-server";
+const DEFAULT_TAG: &str = "5.7.3";
+
+/// Port of the admin UI and the APIs.
+pub const PORT: u16 = 4242;
+
+/// Points to a container named `postgres` on the same network, as started through
+/// `RunArgs::with_name("postgres")`, that accepts the `postgres` user without a password like the
+/// Postgres image does by default.
+const DEFAULT_DATABASE_URL: &str = "postgres://postgres@postgres:5432/postgres";
+
+/// Token of the admin API, created by the server on startup.
+pub const ADMIN_TOKEN: &str = "*:*.unleash-insecure-admin-api-token";
+/// Token of the client API for the `development` environment, created by the server on startup.
+pub const CLIENT_TOKEN: &str = "default:development.unleash-insecure-api-token";
+
+#[derive(Debug, Default, Clone)]
+pub struct UnleashArgs;
+
+impl IntoIterator for UnleashArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// An Unleash feature flag server, storing its flags in a PostgreSQL database that needs to run
+/// beforehand.
+///
+/// By default, the server connects to the `postgres` database of a container named `postgres` on
+/// the same network, e.g. the Postgres image started through
+/// `RunArgs::default().with_network(network).with_name("postgres")`. Other databases are
+/// configured through [`with_database_url`]. The server creates [`ADMIN_TOKEN`] and
+/// [`CLIENT_TOKEN`] so tests do not need to log in to the admin UI.
+///
+/// [`with_database_url`]: struct.Unleash.html#method.with_database_url
+#[derive(Debug)]
+pub struct Unleash {
+    tag: String,
+    arguments: UnleashArgs,
+    database_url: String,
+}
+
+impl Default for Unleash {
+    fn default() -> Self {
+        Unleash {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: UnleashArgs,
+            database_url: DEFAULT_DATABASE_URL.to_owned(),
+        }
+    }
+}
+
+impl Image for Unleash {
+    type Args = UnleashArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(PORT, "/health"))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("DATABASE_URL".to_owned(), self.database_url.clone());
+        env_vars.insert("DATABASE_SSL".to_owned(), "false".to_owned());
+        env_vars.insert("INIT_ADMIN_API_TOKENS".to_owned(), ADMIN_TOKEN.to_owned());
+        env_vars.insert("INIT_CLIENT_API_TOKENS".to_owned(), CLIENT_TOKEN.to_owned());
+
+        env_vars
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Unleash { arguments, ..self }
+    }
+}
+
+impl Unleash {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Unleash {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the url of the database, as seen from within the container, e.g.
+    /// `postgres://unleash:secret@db:5432/unleash`.
+    pub fn with_database_url<U: Into<String>>(self, database_url: U) -> Self {
+        Unleash {
+            database_url: database_url.into(),
+            ..self
+        }
+    }
+
+    /// Returns the url of the APIs, given the host port mapped to [`PORT`], e.g. for the
+    /// `url` of an SDK: `http://localhost:32768/api`.
+    pub fn api_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}/api", host_port)
+    }
+
+    /// Returns the token of the admin API, see [`ADMIN_TOKEN`].
+    pub fn admin_token(&self) -> &str {
+        ADMIN_TOKEN
+    }
+
+    /// Returns the token SDKs authenticate with, see [`CLIENT_TOKEN`].
+    pub fn client_token(&self) -> &str {
+        CLIENT_TOKEN
+    }
+
+    /// Creates a feature flag in the `default` project through the admin API, given the host port
+    /// mapped to [`PORT`]. The flag is disabled in all environments.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the server cannot be reached or rejects the flag, e.g. because it
+    /// already exists.
+    pub fn create_feature(&self, host_port: u16, name: &str) {
+        let url = format!(
+            "{}/admin/projects/default/features",
+            self.api_url(host_port)
+        );
+        let body = serde_json::json!({ "name": name }).to_string();
+
+        self.admin_request(move |client| json_post(client, &url, &body))
+            .unwrap_or_else(|e| panic!("failed to create feature {}: {}", name, e));
+    }
+
+    /// Enables the feature flag in the `development` environment for everyone through the admin
+    /// API, given the host port mapped to [`PORT`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the server cannot be reached or the flag does not exist.
+    pub fn enable_feature(&self, host_port: u16, name: &str) {
+        let environment_url = format!(
+            "{}/admin/projects/default/features/{}/environments/development",
+            self.api_url(host_port),
+            name
+        );
+        let strategies_url = format!("{}/strategies", environment_url);
+        let on_url = format!("{}/on", environment_url);
+
+        // a flag without a strategy is disabled for everyone even if its environment is on
+        self.admin_request(move |client| {
+            json_post(client, &strategies_url, r#"{"name":"default"}"#)
+        })
+        .and_then(|_| self.admin_request(move |client| client.post(&on_url)))
+        .unwrap_or_else(|e| panic!("failed to enable feature {}: {}", name, e));
+    }
+
+    fn admin_request<F>(&self, request: F) -> Result<String, reqwest::Error>
+    where
+        F: FnOnce(&reqwest::blocking::Client) -> reqwest::blocking::RequestBuilder + Send + 'static,
+    {
+        blocking_request(move |client| {
+            request(client).header(reqwest::header::AUTHORIZATION, ADMIN_TOKEN)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_connect_to_sibling_postgres_by_default() {
+        let image = Unleash::default();
+
+        assert_eq!(
+            image.env_vars().get("DATABASE_URL"),
+            Some(&DEFAULT_DATABASE_URL.to_owned())
+        );
+        assert_eq!(
+            Unleash::default()
+                .with_database_url("postgres://unleash:secret@db:5432/unleash")
+                .env_vars()
+                .get("DATABASE_URL"),
+            Some(&"postgres://unleash:secret@db:5432/unleash".to_owned())
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{
+    core::{wait::blocking_request, CopyToContainer, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "verdaccio/verdaccio";
+const DEFAULT_TAG: &str = "5.29.2";
+
+/// Port of the registry.
+pub const PORT: u16 = 4873;
+
+const CONFIG_FILE: &str = "/verdaccio/conf/config.yaml";
+
+/// Lets everyone read and publish any package and does not proxy npmjs.org, so tests neither need
+/// to authenticate nor reach the internet.
+const DEFAULT_CONFIG: &str = "storage: /verdaccio/storage/data
+plugins: /verdaccio/plugins
+auth:
+  htpasswd:
+    file: /verdaccio/storage/htpasswd
+packages:
+  '**':
+    access: $all
+    publish: $all
+    unpublish: $all
+log: { type: stdout, format: pretty, level: http }
+";
+
+#[derive(Debug, Default, Clone)]
+pub struct VerdaccioArgs;
+
+impl IntoIterator for VerdaccioArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A Verdaccio npm registry, which lets anonymous clients publish packages by default.
+///
+/// Users registered through [`add_user`] are stored in an htpasswd file, so a config passed
+/// through [`with_config`] keeps the `htpasswd` auth plugin if tests authenticate.
+///
+/// [`add_user`]: struct.Verdaccio.html#method.add_user
+/// [`with_config`]: struct.Verdaccio.html#method.with_config
+#[derive(Debug)]
+pub struct Verdaccio {
+    tag: String,
+    arguments: VerdaccioArgs,
+    config: Vec<u8>,
+}
+
+impl Default for Verdaccio {
+    fn default() -> Self {
+        Verdaccio {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: VerdaccioArgs,
+            config: DEFAULT_CONFIG.into(),
+        }
+    }
+}
+
+impl Image for Verdaccio {
+    type Args = VerdaccioArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(PORT, "/-/ping"))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        Verdaccio { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        vec![CopyToContainer::new(self.config.clone(), CONFIG_FILE)]
+    }
+}
+
+impl Verdaccio {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        Verdaccio {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Replaces the config of the registry, e.g. to only let authenticated users publish or to
+    /// proxy npmjs.org through an uplink.
+    pub fn with_config<C: Into<Vec<u8>>>(self, config: C) -> Self {
+        Verdaccio {
+            config: config.into(),
+            ..self
+        }
+    }
+
+    /// Returns the url of the registry, given the host port mapped to [`PORT`], e.g. for
+    /// `npm publish --registry`.
+    pub fn registry_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Registers a user with the given password through the CouchDB style `/-/user/` endpoint
+    /// that `npm adduser` uses and returns its token, given the host port mapped to [`PORT`]. npm
+    /// authenticates with the token through `//localhost:<port>/:_authToken=<token>` in `.npmrc`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the registry cannot be reached or rejects the user, e.g. because it
+    /// already exists with another password.
+    pub fn add_user(&self, host_port: u16, name: &str, password: &str) -> String {
+        let url = format!(
+            "{}/-/user/org.couchdb.user:{}",
+            self.registry_url(host_port),
+            name
+        );
+        let body = serde_json::json!({
+            "_id": format!("org.couchdb.user:{}", name),
+            "name": name,
+            "password": password,
+            "type": "user",
+            "roles": [],
+        })
+        .to_string();
+
+        let response = blocking_request(move |client| {
+            client
+                .put(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body)
+        })
+        .unwrap_or_else(|e| panic!("failed to add user {}: {}", name, e));
+
+        let response: serde_json::Value = serde_json::from_str(&response)
+            .unwrap_or_else(|e| panic!("failed to parse response adding {}: {}", name, e));
+
+        response["token"]
+            .as_str()
+            .unwrap_or_else(|| panic!("registry did not return a token for {}", name))
+            .to_owned()
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{
+    core::{wait::blocking_request, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "victoriametrics/victoria-metrics";
+const DEFAULT_TAG: &str = "v1.95.1";
+
+/// Port of the HTTP API, which ingests and queries samples.
+pub const PORT: u16 = 8428;
+
+#[derive(Debug, Default, Clone)]
+pub struct VictoriaMetricsArgs {
+    retention_period: Option<String>,
+    flags: Vec<String>,
+}
+
+impl IntoIterator for VictoriaMetricsArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        let mut args = Vec::new();
+
+        if let Some(retention_period) = self.retention_period {
+            args.push(format!("-retentionPeriod={}", retention_period));
+        }
+        args.extend(self.flags);
+
+        args.into_iter()
+    }
+}
+
+/// A single-node VictoriaMetrics server, ingesting samples through the Prometheus remote write,
+/// Influx line protocol and Graphite APIs among others.
+///
+/// Keep in mind that VictoriaMetrics hides samples of the last 30 seconds from queries by default
+/// to not return incomplete data. Tests that query samples they just wrote pass
+/// `-search.latencyOffset=0s` through [`with_flag`].
+///
+/// [`with_flag`]: struct.VictoriaMetrics.html#method.with_flag
+#[derive(Debug)]
+pub struct VictoriaMetrics {
+    tag: String,
+    arguments: VictoriaMetricsArgs,
+}
+
+impl Default for VictoriaMetrics {
+    fn default() -> Self {
+        VictoriaMetrics {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: VictoriaMetricsArgs::default(),
+        }
+    }
+}
+
+impl Image for VictoriaMetrics {
+    type Args = VictoriaMetricsArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(
+            HttpWaitStrategy::new(PORT, "/health").with_response_containing("OK"),
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        VictoriaMetrics { arguments, ..self }
+    }
+}
+
+impl VictoriaMetrics {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        VictoriaMetrics {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets how long samples are kept, e.g. `1d` or `2w`, one month by default.
+    pub fn with_retention_period<R: Into<String>>(self, retention_period: R) -> Self {
+        VictoriaMetrics {
+            arguments: VictoriaMetricsArgs {
+                retention_period: Some(retention_period.into()),
+                ..self.arguments
+            },
+            ..self
+        }
+    }
+
+    /// Passes an additional command line flag to the server, e.g. `-search.latencyOffset=0s`.
+    pub fn with_flag<F: Into<String>>(mut self, flag: F) -> Self {
+        self.arguments.flags.push(flag.into());
+        self
+    }
+
+    /// Returns the url of the HTTP API, given the host port mapped to [`PORT`].
+    pub fn url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Returns the url that ingests samples in the Influx line protocol, given the host port
+    /// mapped to [`PORT`]. The field `value` of the line `cpu,host=a value=42` becomes the series
+    /// `cpu_value{host="a"}`.
+    pub fn write_url(&self, host_port: u16) -> String {
+        format!("{}/write", self.url(host_port))
+    }
+
+    /// Returns the url that samples are sent to through Prometheus remote write, given the host
+    /// port mapped to [`PORT`].
+    pub fn remote_write_url(&self, host_port: u16) -> String {
+        format!("{}/api/v1/write", self.url(host_port))
+    }
+
+    /// Evaluates the PromQL query at the current time and returns the response of
+    /// `/api/v1/query`, given the host port mapped to [`PORT`], e.g. to assert on
+    /// `["data"]["result"][0]["metric"]`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the server cannot be reached, rejects the query or does not respond
+    /// with JSON.
+    pub fn query(&self, host_port: u16, promql: &str) -> serde_json::Value {
+        let url = format!("{}/api/v1/query", self.url(host_port));
+        let query = promql.to_owned();
+
+        let response = blocking_request(move |client| client.get(&url).query(&[("query", query)]))
+            .unwrap_or_else(|e| panic!("failed to query {}: {}", promql, e));
+
+        serde_json::from_str(&response)
+            .unwrap_or_else(|e| panic!("failed to parse result of {}: {}", promql, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_pass_retention_period_before_extra_flags() {
+        let image = VictoriaMetrics::default()
+            .with_flag("-search.latencyOffset=0s")
+            .with_retention_period("1d");
+
+        assert_eq!(
+            image.args().into_iter().collect::<Vec<_>>(),
+            vec!["-retentionPeriod=1d", "-search.latencyOffset=0s"]
+        );
+    }
+}
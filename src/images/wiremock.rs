@@ -0,0 +1,186 @@
+use crate::{
+    core::{
+        wait::{blocking_request, json_post},
+        CopyToContainer, HttpWaitStrategy, WaitFor,
+    },
+    Image,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "wiremock/wiremock";
+const DEFAULT_TAG: &str = "3.3.1";
+
+/// Port of the mock server and its admin API.
+pub const PORT: u16 = 8080;
+
+const MAPPINGS_DIR: &str = "/home/wiremock/mappings";
+
+#[derive(Debug, Default, Clone)]
+pub struct WireMockArgs;
+
+impl IntoIterator for WireMockArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A WireMock server for stubbing HTTP APIs.
+///
+/// Stubs can be provided up front through [`with_mapping_json`] or registered at runtime through
+/// the [`WireMockAdmin`] returned by [`admin`].
+///
+/// [`with_mapping_json`]: struct.WireMock.html#method.with_mapping_json
+/// [`admin`]: struct.WireMock.html#method.admin
+/// [`WireMockAdmin`]: struct.WireMockAdmin.html
+#[derive(Debug)]
+pub struct WireMock {
+    tag: String,
+    arguments: WireMockArgs,
+    mappings: Vec<Vec<u8>>,
+}
+
+impl Default for WireMock {
+    fn default() -> Self {
+        WireMock {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: WireMockArgs {},
+            mappings: Vec::new(),
+        }
+    }
+}
+
+impl Image for WireMock {
+    type Args = WireMockArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::http(HttpWaitStrategy::new(
+            PORT,
+            "/__admin/mappings",
+        ))]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        WireMock { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        self.mappings
+            .iter()
+            .enumerate()
+            .map(|(index, mapping)| {
+                CopyToContainer::new(
+                    mapping.clone(),
+                    format!("{}/{:02}-stub.json", MAPPINGS_DIR, index),
+                )
+            })
+            .collect()
+    }
+}
+
+impl WireMock {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        WireMock {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Adds a stub mapping in WireMock's JSON format, which is loaded on startup.
+    pub fn with_mapping_json<M: Into<Vec<u8>>>(mut self, mapping: M) -> Self {
+        self.mappings.push(mapping.into());
+        self
+    }
+
+    /// Returns the url of the mock server, given the host port mapped to [`PORT`].
+    pub fn base_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    /// Returns a client for the admin API, given the host port mapped to [`PORT`].
+    pub fn admin(&self, host_port: u16) -> WireMockAdmin {
+        WireMockAdmin {
+            url: format!("{}/__admin", self.base_url(host_port)),
+        }
+    }
+}
+
+/// A client for the admin API of a running [`WireMock`] server.
+///
+/// [`WireMock`]: struct.WireMock.html
+#[derive(Debug, Clone)]
+pub struct WireMockAdmin {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct RequestCount {
+    count: usize,
+}
+
+impl WireMockAdmin {
+    /// Registers a stub mapping in WireMock's JSON format.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the server rejects the stub.
+    pub fn register_stub(&self, stub: &serde_json::Value) {
+        let url = format!("{}/mappings", self.url);
+        let stub = stub.to_string();
+
+        blocking_request(move |client| json_post(client, &url, &stub))
+            .unwrap_or_else(|e| panic!("failed to register stub: {}", e));
+    }
+
+    /// Returns how many received requests match the given request pattern, e.g.
+    /// `{ "method": "GET", "url": "/users/1" }`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the server rejects the pattern.
+    pub fn verify_requests(&self, pattern: &serde_json::Value) -> usize {
+        let url = format!("{}/requests/count", self.url);
+        let pattern = pattern.to_string();
+
+        let body = blocking_request(move |client| json_post(client, &url, &pattern))
+            .unwrap_or_else(|e| panic!("failed to count requests: {}", e));
+
+        serde_json::from_str::<RequestCount>(&body)
+            .unwrap_or_else(|e| panic!("failed to parse request count: {}", e))
+            .count
+    }
+
+    /// Removes all stubs registered at runtime and clears the request journal.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the server cannot be reached.
+    pub fn reset(&self) {
+        let url = format!("{}/reset", self.url);
+
+        blocking_request(move |client| client.post(&url))
+            .unwrap_or_else(|e| panic!("failed to reset WireMock: {}", e));
+    }
+}
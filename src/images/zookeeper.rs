@@ -4,6 +4,9 @@ use std::collections::HashMap;
 const CONTAINER_IDENTIFIER: &str = "zookeeper";
 const DEFAULT_TAG: &str = "3.6.2";
 
+/// Port clients connect to.
+pub const PORT: u16 = 2181;
+
 #[derive(Debug, Default, Clone)]
 pub struct ZookeeperArgs;
 impl IntoIterator for ZookeeperArgs {
@@ -15,6 +18,15 @@ impl IntoIterator for ZookeeperArgs {
     }
 }
 
+/// A standalone Zookeeper server, e.g. for classic Kafka deployments.
+///
+/// To make the server reachable from other containers, run it on a user-defined network under a
+/// name through [`RunArgs`] and hand the [`internal_connect_string`] to the other containers.
+///
+/// The `ruok` and `srvr` four-letter commands are enabled.
+///
+/// [`RunArgs`]: ../../core/struct.RunArgs.html
+/// [`internal_connect_string`]: struct.Zookeeper.html#method.internal_connect_string
 #[derive(Debug)]
 pub struct Zookeeper {
     tag: String,
@@ -39,7 +51,8 @@ impl Image for Zookeeper {
     }
 
     fn ready_conditions(&self) -> Vec<WaitFor> {
-        vec![WaitFor::message_on_stdout("Started AdminServer")]
+        // the admin server is started before the server binds to the client port
+        vec![WaitFor::message_on_stdout("binding to port")]
     }
 
     fn args(&self) -> <Self as Image>::Args {
@@ -51,7 +64,13 @@ impl Image for Zookeeper {
     }
 
     fn env_vars(&self) -> Self::EnvVars {
-        HashMap::new()
+        let mut env_vars = HashMap::new();
+        env_vars.insert(
+            "ZOO_4LW_COMMANDS_WHITELIST".to_owned(),
+            "ruok,srvr".to_owned(),
+        );
+
+        env_vars
     }
 
     fn with_args(self, arguments: <Self as Image>::Args) -> Self {
@@ -65,4 +84,15 @@ impl Zookeeper {
             ..self
         }
     }
+
+    /// Returns the connect string for clients on the host, given the host port mapped to [`PORT`].
+    pub fn connect_string(&self, host_port: u16) -> String {
+        format!("localhost:{}", host_port)
+    }
+
+    /// Returns the connect string for containers in the same network, given the name the
+    /// container was started with.
+    pub fn internal_connect_string(&self, name: &str) -> String {
+        format!("{}:{}", name, PORT)
+    }
 }
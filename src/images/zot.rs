@@ -0,0 +1,220 @@
+#[cfg(feature = "bcrypt")]
+use crate::images::registry::htpasswd;
+use crate::{
+    core::{CopyToContainer, HttpWaitStrategy, WaitFor},
+    Image,
+};
+use std::collections::HashMap;
+
+const CONTAINER_IDENTIFIER: &str = "ghcr.io/project-zot/zot";
+const DEFAULT_TAG: &str = "v2.0.0";
+
+/// Port of the registry API.
+pub const PORT: u16 = 5000;
+
+const CONFIG_FILE: &str = "/etc/zot/config.json";
+const HTPASSWD_FILE: &str = "/etc/zot/htpasswd";
+
+#[derive(Debug, Default, Clone)]
+pub struct ZotRegistryArgs;
+
+impl IntoIterator for ZotRegistryArgs {
+    type Item = String;
+    type IntoIter = ::std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        vec![].into_iter()
+    }
+}
+
+/// A Zot registry, which stores OCI artifacts besides container images and indexes them through
+/// its search extension, e.g. to test tools pushing Helm charts or signatures.
+///
+/// The generated config enables the search extension, which answers GraphQL queries at
+/// `/v2/_zot/ext/search`, and the authentication configured through [`with_htpasswd_file`], or
+/// through [`with_htpasswd`] which is available behind the `bcrypt` feature.
+///
+/// [`with_htpasswd_file`]: struct.ZotRegistry.html#method.with_htpasswd_file
+/// [`with_htpasswd`]: struct.ZotRegistry.html#method.with_htpasswd
+#[derive(Debug)]
+pub struct ZotRegistry {
+    tag: String,
+    arguments: ZotRegistryArgs,
+    config: Option<Vec<u8>>,
+    htpasswd: Option<Vec<u8>>,
+    credentials: Option<(String, String)>,
+}
+
+impl Default for ZotRegistry {
+    fn default() -> Self {
+        ZotRegistry {
+            tag: DEFAULT_TAG.to_string(),
+            arguments: ZotRegistryArgs,
+            config: None,
+            htpasswd: None,
+            credentials: None,
+        }
+    }
+}
+
+impl Image for ZotRegistry {
+    type Args = ZotRegistryArgs;
+    type EnvVars = HashMap<String, String>;
+    type Volumes = HashMap<String, String>;
+    type EntryPoint = std::convert::Infallible;
+
+    fn descriptor(&self) -> String {
+        format!("{}:{}", CONTAINER_IDENTIFIER, &self.tag)
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        // the API root asks for credentials if authentication is enabled
+        vec![WaitFor::http(
+            HttpWaitStrategy::new(PORT, "/v2/")
+                .with_expected_status_code(200)
+                .with_expected_status_code(401),
+        )]
+    }
+
+    fn args(&self) -> <Self as Image>::Args {
+        self.arguments.clone()
+    }
+
+    fn volumes(&self) -> Self::Volumes {
+        HashMap::new()
+    }
+
+    fn env_vars(&self) -> Self::EnvVars {
+        HashMap::new()
+    }
+
+    fn with_args(self, arguments: <Self as Image>::Args) -> Self {
+        ZotRegistry { arguments, ..self }
+    }
+
+    fn copy_to_sources(&self) -> Vec<CopyToContainer> {
+        let config = match &self.config {
+            Some(config) => config.clone(),
+            None => self.generated_config().into_bytes(),
+        };
+        let mut files = vec![CopyToContainer::new(config, CONFIG_FILE)];
+
+        if let Some(htpasswd) = &self.htpasswd {
+            files.push(CopyToContainer::new(htpasswd.clone(), HTPASSWD_FILE));
+        }
+
+        files
+    }
+}
+
+impl ZotRegistry {
+    pub fn with_tag(self, tag_str: &str) -> Self {
+        ZotRegistry {
+            tag: tag_str.to_string(),
+            ..self
+        }
+    }
+
+    /// Replaces the generated config, e.g. to enable the scrub or sync extensions. A config that
+    /// uses the credentials of [`with_htpasswd`] or [`with_htpasswd_file`] refers to
+    /// `/etc/zot/htpasswd` and keeps serving on [`PORT`].
+    ///
+    /// [`with_htpasswd_file`]: struct.ZotRegistry.html#method.with_htpasswd_file
+    ///
+    /// [`with_htpasswd`]: struct.ZotRegistry.html#method.with_htpasswd
+    pub fn with_config<C: Into<Vec<u8>>>(self, config: C) -> Self {
+        ZotRegistry {
+            config: Some(config.into()),
+            ..self
+        }
+    }
+
+    /// Requires clients to authenticate through HTTP basic authentication with the given
+    /// credentials, e.g. through `oras login`.
+    #[cfg(feature = "bcrypt")]
+    pub fn with_htpasswd<U: Into<String>, P: Into<String>>(self, username: U, password: P) -> Self {
+        let username = username.into();
+        let password = password.into();
+
+        ZotRegistry {
+            htpasswd: Some(htpasswd(&username, &password)),
+            credentials: Some((username, password)),
+            ..self
+        }
+    }
+
+    /// Requires clients to authenticate through HTTP basic authentication against the given
+    /// htpasswd file, whose entries need to be hashed with bcrypt, e.g. by `htpasswd -Bbn`.
+    pub fn with_htpasswd_file<H: Into<Vec<u8>>>(self, htpasswd: H) -> Self {
+        ZotRegistry {
+            htpasswd: Some(htpasswd.into()),
+            credentials: None,
+            ..self
+        }
+    }
+
+    /// Returns the credentials configured through [`with_htpasswd`], if any. The credentials of
+    /// an htpasswd file passed to [`with_htpasswd_file`] are not known.
+    ///
+    /// [`with_htpasswd`]: struct.ZotRegistry.html#method.with_htpasswd
+    /// [`with_htpasswd_file`]: struct.ZotRegistry.html#method.with_htpasswd_file
+    pub fn credentials(&self) -> Option<(&str, &str)> {
+        self.credentials
+            .as_ref()
+            .map(|(username, password)| (username.as_str(), password.as_str()))
+    }
+
+    /// Returns the url of the registry API, given the host port mapped to [`PORT`].
+    pub fn registry_url(&self, host_port: u16) -> String {
+        format!("http://localhost:{}", host_port)
+    }
+
+    fn generated_config(&self) -> String {
+        let mut http = serde_json::json!({
+            "address": "0.0.0.0",
+            "port": PORT.to_string(),
+        });
+        if self.htpasswd.is_some() {
+            http["auth"] = serde_json::json!({ "htpasswd": { "path": HTPASSWD_FILE } });
+        }
+
+        serde_json::json!({
+            "distSpecVersion": "1.1.0-dev",
+            "storage": { "rootDirectory": "/var/lib/registry" },
+            "http": http,
+            "log": { "level": "info" },
+            "extensions": { "search": { "enable": true } },
+        })
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_only_configure_authentication_with_htpasswd() {
+        let config: serde_json::Value =
+            serde_json::from_str(&ZotRegistry::default().generated_config()).unwrap();
+        assert_eq!(config["extensions"]["search"]["enable"], true);
+        assert!(config["http"].get("auth").is_none());
+
+        let image = ZotRegistry::default().with_htpasswd_file(
+            "alice:$2y$04$3Jsqkt.iFKreuNI3jv3iEOwjUNRXhKPlEhqXM3f7lajzuwTEzBl0.\n",
+        );
+        let config: serde_json::Value = serde_json::from_str(&image.generated_config()).unwrap();
+        assert_eq!(
+            config["http"]["auth"]["htpasswd"]["path"],
+            "/etc/zot/htpasswd"
+        );
+        assert_eq!(
+            image
+                .copy_to_sources()
+                .into_iter()
+                .map(|file| file.target)
+                .collect::<Vec<_>>(),
+            vec!["/etc/zot/config.json", "/etc/zot/htpasswd"]
+        );
+    }
+}
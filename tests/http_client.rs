@@ -1,8 +1,8 @@
 use shiplift::ImageListOptions;
 use std::time::Duration;
 use testcontainers::{
-    core::WaitFor,
-    images::{generic::GenericImage, hello_world::HelloWorld},
+    core::{ConnectionString, WaitFor},
+    images::{generic::GenericImage, hello_world::HelloWorld, postgres::Postgres},
     *,
 };
 
@@ -60,3 +60,95 @@ async fn start_containers_in_parallel() {
     let timeout = Duration::from_secs(5);
     let _containers = tokio::time::timeout(timeout, run_all).await.unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn postgres_start_returns_connection_string() {
+    let _ = pretty_env_logger::try_init();
+
+    let docker = clients::Http::default();
+    let (_container, connection_string) = Postgres::default().start(&docker).await;
+
+    // the blocking client of `postgres` must not run on a thread of the async runtime
+    let first_column = tokio::task::spawn_blocking(move || {
+        let mut conn = postgres::Client::connect(&connection_string, postgres::NoTls).unwrap();
+
+        conn.query_one("SELECT 1 + 1", &[])
+            .unwrap()
+            .get::<_, i32>(0)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(first_column, 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg(feature = "chrono")]
+async fn logs_since_resumes_after_restart() {
+    use testcontainers::core::{LogPosition, LogSource};
+
+    let _ = pretty_env_logger::try_init();
+
+    let docker = clients::Http::default();
+    let image = GenericImage::new("alpine:3.13")
+        .with_wait_for(WaitFor::message_on_stdout("started"))
+        .with_args(vec![
+            "sh".to_owned(),
+            "-c".to_owned(),
+            "trap 'exit 0' TERM; echo started; sleep infinity & wait".to_owned(),
+        ]);
+    let container = docker.run(image).await;
+
+    let (lines, position) = container
+        .logs_since(LogSource::StdOut, LogPosition::start())
+        .await;
+    assert_eq!(lines, vec!["started"]);
+
+    container.stop().await;
+    container.start().await;
+
+    for _ in 0..50 {
+        let (lines, _) = container.logs_since(LogSource::StdOut, position).await;
+
+        if !lines.is_empty() {
+            assert_eq!(lines, vec!["started"]);
+            return;
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    panic!("container did not log again after the restart");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn container_status_follows_pause_unpause_and_stop() {
+    let _ = pretty_env_logger::try_init();
+    let docker = clients::Http::default();
+    let container = docker
+        .run(GenericImage::new("alpine:3.13").with_args(vec![
+            "sh".to_owned(),
+            "-c".to_owned(),
+            // exits right away on `docker stop`, which `sleep` as main process would ignore
+            "trap 'exit 0' TERM; sleep infinity & wait".to_owned(),
+        ]))
+        .await;
+    let timeout = Duration::from_secs(10);
+
+    assert_eq!(container.status().await, core::ContainerStatus::Running);
+
+    container.pause().await;
+    container
+        .wait_for_status(core::ContainerStatus::Paused, timeout)
+        .await;
+
+    container.unpause().await;
+    container
+        .wait_for_status(core::ContainerStatus::Running, timeout)
+        .await;
+
+    container.stop().await;
+    container
+        .wait_for_status(core::ContainerStatus::Exited, timeout)
+        .await;
+}
@@ -7,7 +7,11 @@ use rdkafka::{
 };
 use std::time::Duration;
 
-use testcontainers::{clients, core::RunArgs, images::kafka};
+use testcontainers::{
+    clients,
+    core::RunArgs,
+    images::{kafka, redpanda},
+};
 
 #[tokio::test]
 async fn test_produce_and_consume_messages() {
@@ -19,15 +23,31 @@ async fn test_produce_and_consume_messages() {
 
     let bootstrap_servers = format!("localhost:{}", kafka_node.get_host_port(kafka::KAFKA_PORT));
 
+    produce_and_consume_messages(&bootstrap_servers).await;
+}
+
+#[tokio::test]
+async fn redpanda_produce_and_consume_messages() {
+    let docker = clients::Cli::default();
+    let node = docker.run(redpanda::Redpanda::default());
+
+    let bootstrap_servers = node
+        .image()
+        .bootstrap_servers(node.get_host_port(redpanda::KAFKA_PORT));
+
+    produce_and_consume_messages(&bootstrap_servers).await;
+}
+
+async fn produce_and_consume_messages(bootstrap_servers: &str) {
     let producer = ClientConfig::new()
-        .set("bootstrap.servers", &bootstrap_servers)
+        .set("bootstrap.servers", bootstrap_servers)
         .set("message.timeout.ms", "5000")
         .create::<FutureProducer>()
         .expect("Failed to create Kafka FutureProducer");
 
     let consumer = ClientConfig::new()
         .set("group.id", "testcontainer-rs")
-        .set("bootstrap.servers", &bootstrap_servers)
+        .set("bootstrap.servers", bootstrap_servers)
         .set("session.timeout.ms", "6000")
         .set("enable.auto.commit", "false")
         .set("auto.offset.reset", "earliest")
@@ -36,7 +56,7 @@ async fn test_produce_and_consume_messages() {
 
     let topic = "test-topic";
     consumer
-        .subscribe(&[&topic])
+        .subscribe(&[topic])
         .expect("Failed to subscribe to a topic");
 
     let number_of_messages_to_produce = 5_usize;
@@ -47,7 +67,7 @@ async fn test_produce_and_consume_messages() {
     for (i, message) in expected.iter().enumerate() {
         producer
             .send(
-                FutureRecord::to(&topic)
+                FutureRecord::to(topic)
                     .payload(message)
                     .key(&format!("Key {}", i)),
                 Duration::from_secs(0),